@@ -0,0 +1,227 @@
+//! Differential fuzzing of sszb against ethereum_ssz.
+//!
+//! Every target decodes its input with both libraries as the same type (the containers in
+//! [`types`] derive the traits of both) and checks that they agree: either both reject the input,
+//! or both accept it, decode equal values and re-encode them to identical bytes. The types only
+//! sszb implements (its bitfields, byte lists, arrays and tuples, and the bench containers built
+//! from them) are checked against an ethereum_ssz type with the same encoding instead, comparing
+//! the re-encoded bytes. Targets covering several types pick one with the first byte of the input.
+//!
+//! Run a target with `cargo fuzz run <target>` from this directory. Minimized crashes go under
+//! `regressions/<target>/`, the `fuzz_regressions` test of sszb replays them with the workspace
+//! tests (and `cargo test` from here).
+use alloy_primitives::{Address, B256, U128, U256};
+use ssz::{Decode, Encode};
+use ssz_types::{BitList, BitVector, FixedVector, VariableList};
+use sszb::{Bitlist, Bitvector, ByteList, SszDecode, SszEncode};
+use std::fmt::Debug;
+use std::fs;
+use std::panic::catch_unwind;
+use std::path::Path;
+use typenum::{U16, U2, U2048, U32, U4, U48, U512, U8};
+
+pub mod types;
+
+#[allow(unused)]
+#[path = "../../sszb_lib/benches/beacon_block.rs"]
+pub mod beacon_block;
+
+#[allow(unused)]
+#[path = "../../sszb_lib/benches/beacon_state.rs"]
+pub mod beacon_state;
+
+/// Decodes `data` as `T` with both libraries and panics if they disagree.
+pub fn differential<T>(data: &[u8])
+where
+    T: SszEncode + SszDecode + Encode + Decode + PartialEq + Debug,
+{
+    let sszb = <T as SszDecode>::from_ssz_bytes(data);
+    let ethereum_ssz = <T as Decode>::from_ssz_bytes(data);
+    match (sszb, ethereum_ssz) {
+        (Ok(sszb), Ok(ethereum_ssz)) => {
+            assert_eq!(sszb, ethereum_ssz, "decoded values differ");
+            assert_eq!(
+                sszb.to_ssz(),
+                ethereum_ssz.as_ssz_bytes(),
+                "re-encoded bytes differ"
+            );
+        }
+        (Err(_), Err(_)) => {}
+        (sszb, ethereum_ssz) => panic!(
+            "sszb and ethereum_ssz disagree on the input:\nsszb: {:?}\nethereum_ssz: {:?}",
+            sszb, ethereum_ssz
+        ),
+    }
+}
+
+/// Decodes `data` as `A` with sszb and as `B`, a type with the same encoding, with ethereum_ssz,
+/// and panics if they disagree. With `stricter`, `A` may reject inputs that `B` accepts.
+pub fn equivalent<A, B>(data: &[u8], stricter: bool)
+where
+    A: SszEncode + SszDecode + Debug,
+    B: Encode + Decode + Debug,
+{
+    let sszb = <A as SszDecode>::from_ssz_bytes(data);
+    let ethereum_ssz = <B as Decode>::from_ssz_bytes(data);
+    match (sszb, ethereum_ssz) {
+        (Ok(sszb), Ok(ethereum_ssz)) => assert_eq!(
+            sszb.to_ssz(),
+            ethereum_ssz.as_ssz_bytes(),
+            "re-encoded bytes differ"
+        ),
+        (Err(_), Err(_)) => {}
+        (Err(_), Ok(_)) if stricter => {}
+        (sszb, ethereum_ssz) => panic!(
+            "sszb and ethereum_ssz disagree on the input:\nsszb: {:?}\nethereum_ssz: {:?}",
+            sszb, ethereum_ssz
+        ),
+    }
+}
+
+// runs `differential` on the rest of the input for one of the types, picked by the first byte
+macro_rules! select {
+    ($data:expr, [$($type:ty),* $(,)?]) => {{
+        let Some((selector, data)) = $data.split_first() else {
+            return;
+        };
+        let targets: &[fn(&[u8])] = &[$(differential::<$type>),*];
+        targets[*selector as usize % targets.len()](data)
+    }};
+}
+
+/// Integers, booleans and fixed-size byte types.
+pub fn basic(data: &[u8]) {
+    select!(
+        data,
+        [u8, u16, u32, u64, u128, U128, U256, bool, Address, B256]
+    )
+}
+
+/// Lists, vectors, bitfields and unions of basic and variable-size types.
+pub fn collections(data: &[u8]) {
+    select!(
+        data,
+        [
+            VariableList<u64, U16>,
+            VariableList<u8, U32>,
+            VariableList<B256, U4>,
+            VariableList<VariableList<u16, U4>, U4>,
+            FixedVector<u16, U4>,
+            FixedVector<u8, U48>,
+            FixedVector<VariableList<u8, U8>, U2>,
+            BitList<U8>,
+            BitList<U2048>,
+            BitVector<U4>,
+            BitVector<U512>,
+            Option<u64>,
+            Option<VariableList<u8, U8>>,
+            milhouse::List<u64, U16>,
+            milhouse::Vector<B256, U4>,
+        ]
+    )
+}
+
+/// The bitfields, byte lists, arrays and tuples of sszb, against the ssz_types they stand in for.
+pub fn native(data: &[u8]) {
+    let Some((selector, data)) = data.split_first() else {
+        return;
+    };
+    let targets: &[fn(&[u8])] = &[
+        |data| equivalent::<Bitlist<U8>, BitList<U8>>(data, false),
+        |data| equivalent::<Bitlist<U2048>, BitList<U2048>>(data, false),
+        |data| equivalent::<Bitvector<U4>, BitVector<U4>>(data, false),
+        |data| equivalent::<Bitvector<U512>, BitVector<U512>>(data, false),
+        |data| equivalent::<ByteList<U32>, VariableList<u8, U32>>(data, false),
+        |data| equivalent::<[u8; 48], FixedVector<u8, U48>>(data, false),
+        |data| equivalent::<[u16; 4], FixedVector<u16, U4>>(data, false),
+        |data| equivalent::<[ByteList<U8>; 2], FixedVector<VariableList<u8, U8>, U2>>(data, false),
+        |data| equivalent::<(u16, ByteList<U8>), (u16, VariableList<u8, U8>)>(data, false),
+        |data| {
+            equivalent::<
+                (ByteList<U8>, [u8; 4], Bitlist<U8>),
+                (VariableList<u8, U8>, [u8; 4], BitList<U8>),
+            >(data, false)
+        },
+    ];
+    targets[*selector as usize % targets.len()](data)
+}
+
+/// The containers of a beacon block and state, except the two with their own targets.
+pub fn containers(data: &[u8]) {
+    use types::*;
+    select!(
+        data,
+        [
+            Fork,
+            Checkpoint,
+            BeaconBlockHeader,
+            SignedBeaconBlockHeader,
+            Eth1Data,
+            ProposerSlashing,
+            AttestationData,
+            IndexedAttestation,
+            AttesterSlashing,
+            Attestation,
+            DepositData,
+            Deposit,
+            VoluntaryExit,
+            SignedVoluntaryExit,
+            SyncAggregate,
+            Withdrawal,
+            ExecutionPayload,
+            ExecutionPayloadHeader,
+            BlsToExecutionChange,
+            SignedBlsToExecutionChange,
+            BeaconBlockBody,
+            BeaconBlock,
+            Validator,
+            SyncCommittee,
+            HistoricalSummary,
+        ]
+    )
+}
+
+pub fn signed_beacon_block(data: &[u8]) {
+    differential::<types::SignedBeaconBlock>(data);
+    // the signatures of the bench block must be valid points, which the mirror does not check
+    equivalent::<beacon_block::SignedBeaconBlock, types::SignedBeaconBlock>(data, true)
+}
+
+pub fn beacon_state(data: &[u8]) {
+    differential::<types::BeaconState>(data);
+    equivalent::<beacon_state::BeaconState, types::BeaconState>(data, false)
+}
+
+fn target(name: &str) -> fn(&[u8]) {
+    match name {
+        "basic" => basic,
+        "collections" => collections,
+        "native" => native,
+        "containers" => containers,
+        "signed_beacon_block" => signed_beacon_block,
+        "beacon_state" => beacon_state,
+        _ => panic!("no fuzz target named {}", name),
+    }
+}
+
+/// Replays the inputs under `<regressions>/<target>`, minimized from crashes the fuzz targets
+/// found, and panics listing the ones that fail again.
+pub fn replay_regressions(regressions: &Path) {
+    let mut failures = vec![];
+    for dir in fs::read_dir(regressions).unwrap() {
+        let dir = dir.unwrap().path();
+        let run = target(dir.file_name().unwrap().to_str().unwrap());
+        for input in fs::read_dir(&dir).unwrap() {
+            let input = input.unwrap().path();
+            let data = fs::read(&input).unwrap();
+            if catch_unwind(|| run(&data)).is_err() {
+                failures.push(input.display().to_string());
+            }
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "failing inputs:\n{}",
+        failures.join("\n")
+    );
+}
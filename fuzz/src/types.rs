@@ -0,0 +1,291 @@
+//! The deneb containers of the benches, with field types that both sszb and ethereum_ssz
+//! implement: fixed-size byte arrays are `FixedVector<u8, N>` and the persistent lists and vectors
+//! of the state are `VariableList` and `FixedVector`.
+use alloy_primitives::{Address, B256, U256};
+use bytes::buf::{Buf, BufMut};
+use ssz_derive::{Decode, Encode};
+use ssz_types::{BitList, BitVector, FixedVector, VariableList};
+use sszb_derive::{SszbDecode, SszbEncode};
+
+type H32 = FixedVector<u8, typenum::U4>;
+type PublicKeyBytes = FixedVector<u8, typenum::U48>;
+type SignatureBytes = FixedVector<u8, typenum::U96>;
+type KZGCommitment = FixedVector<u8, typenum::U48>;
+type Transaction = VariableList<u8, typenum::U1073741824>;
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode, Encode, Decode)]
+pub struct Fork {
+    pub previous_version: H32,
+    pub current_version: H32,
+    pub epoch: u64,
+}
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode, Encode, Decode)]
+pub struct Checkpoint {
+    pub epoch: u64,
+    pub root: B256,
+}
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode, Encode, Decode)]
+pub struct BeaconBlockHeader {
+    pub slot: u64,
+    pub proposer_index: u64,
+    pub parent_root: B256,
+    pub state_root: B256,
+    pub body_root: B256,
+}
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode, Encode, Decode)]
+pub struct SignedBeaconBlockHeader {
+    pub message: BeaconBlockHeader,
+    pub signature: SignatureBytes,
+}
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode, Encode, Decode)]
+pub struct Eth1Data {
+    pub deposit_root: B256,
+    pub deposit_count: u64,
+    pub block_hash: B256,
+}
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode, Encode, Decode)]
+pub struct ProposerSlashing {
+    pub signed_header_1: SignedBeaconBlockHeader,
+    pub signed_header_2: SignedBeaconBlockHeader,
+}
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode, Encode, Decode)]
+pub struct AttestationData {
+    pub slot: u64,
+    pub index: u64,
+    pub beacon_block_root: B256,
+    pub source: Checkpoint,
+    pub target: Checkpoint,
+}
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode, Encode, Decode)]
+pub struct IndexedAttestation {
+    pub attesting_indices: VariableList<u64, typenum::U2048>,
+    pub data: AttestationData,
+    pub signature: SignatureBytes,
+}
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode, Encode, Decode)]
+pub struct AttesterSlashing {
+    pub attestation_1: IndexedAttestation,
+    pub attestation_2: IndexedAttestation,
+}
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode, Encode, Decode)]
+pub struct Attestation {
+    pub aggregation_bits: BitList<typenum::U2048>,
+    pub data: AttestationData,
+    pub signature: SignatureBytes,
+}
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode, Encode, Decode)]
+pub struct DepositData {
+    pub pubkey: PublicKeyBytes,
+    pub withdrawal_credentials: B256,
+    pub amount: u64,
+    pub signature: SignatureBytes,
+}
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode, Encode, Decode)]
+pub struct Deposit {
+    pub proof: FixedVector<B256, typenum::U33>,
+    pub data: DepositData,
+}
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode, Encode, Decode)]
+pub struct VoluntaryExit {
+    pub epoch: u64,
+    pub validator_index: u64,
+}
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode, Encode, Decode)]
+pub struct SignedVoluntaryExit {
+    pub message: VoluntaryExit,
+    pub signature: SignatureBytes,
+}
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode, Encode, Decode)]
+pub struct SyncAggregate {
+    pub sync_committee_bits: BitVector<typenum::U512>,
+    pub sync_committee_signature: SignatureBytes,
+}
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode, Encode, Decode)]
+pub struct Withdrawal {
+    pub index: u64,
+    pub validator_index: u64,
+    pub address: Address,
+    pub amount: u64,
+}
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode, Encode, Decode)]
+pub struct ExecutionPayload {
+    pub parent_hash: B256,
+    pub fee_recipient: Address,
+    pub state_root: B256,
+    pub receipts_root: B256,
+    pub logs_bloom: FixedVector<u8, typenum::U256>,
+    pub prev_randao: B256,
+    pub block_number: u64,
+    pub gas_limit: u64,
+    pub gas_used: u64,
+    pub timestamp: u64,
+    pub extra_data: VariableList<u8, typenum::U32>,
+    pub base_fee_per_gas: U256,
+    pub block_hash: B256,
+    pub transactions: VariableList<Transaction, typenum::U1048576>,
+    pub withdrawals: VariableList<Withdrawal, typenum::U16>,
+    pub blob_gas_used: u64,
+    pub excess_blob_gas: u64,
+}
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode, Encode, Decode)]
+pub struct ExecutionPayloadHeader {
+    pub parent_hash: B256,
+    pub fee_recipient: Address,
+    pub state_root: B256,
+    pub receipts_root: B256,
+    pub logs_bloom: FixedVector<u8, typenum::U256>,
+    pub prev_randao: B256,
+    pub block_number: u64,
+    pub gas_limit: u64,
+    pub gas_used: u64,
+    pub timestamp: u64,
+    pub extra_data: VariableList<u8, typenum::U32>,
+    pub base_fee_per_gas: U256,
+    pub block_hash: B256,
+    pub transactions_root: B256,
+    pub withdrawals_root: B256,
+    pub blob_gas_used: u64,
+    pub excess_blob_gas: u64,
+}
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode, Encode, Decode)]
+pub struct BlsToExecutionChange {
+    pub validator_index: u64,
+    pub from_bls_pubkey: PublicKeyBytes,
+    pub to_execution_address: Address,
+}
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode, Encode, Decode)]
+pub struct SignedBlsToExecutionChange {
+    pub message: BlsToExecutionChange,
+    pub signature: SignatureBytes,
+}
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode, Encode, Decode)]
+pub struct BeaconBlockBody {
+    pub randao_reveal: SignatureBytes,
+    pub eth1_data: Eth1Data,
+    pub graffiti: B256,
+    pub proposer_slashings: VariableList<ProposerSlashing, typenum::U16>,
+    pub attester_slashings: VariableList<AttesterSlashing, typenum::U2>,
+    pub attestations: VariableList<Attestation, typenum::U128>,
+    pub deposits: VariableList<Deposit, typenum::U16>,
+    pub voluntary_exits: VariableList<SignedVoluntaryExit, typenum::U16>,
+    pub sync_aggregate: SyncAggregate,
+    pub execution_payload: ExecutionPayload,
+    pub bls_to_execution_changes: VariableList<SignedBlsToExecutionChange, typenum::U16>,
+    pub blob_kzg_commitments: VariableList<KZGCommitment, typenum::U4096>,
+}
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode, Encode, Decode)]
+pub struct BeaconBlock {
+    pub slot: u64,
+    pub proposer_index: u64,
+    pub parent_root: B256,
+    pub state_root: B256,
+    pub body: BeaconBlockBody,
+}
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode, Encode, Decode)]
+pub struct SignedBeaconBlock {
+    pub message: BeaconBlock,
+    pub signature: SignatureBytes,
+}
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode, Encode, Decode)]
+pub struct Validator {
+    pub pubkey: PublicKeyBytes,
+    pub withdrawal_credentials: B256,
+    pub effective_balance: u64,
+    pub slashed: bool,
+    pub activation_eligibility_epoch: u64,
+    pub activation_epoch: u64,
+    pub exit_epoch: u64,
+    pub withdrawable_epoch: u64,
+}
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode, Encode, Decode)]
+pub struct SyncCommittee {
+    pub pubkeys: FixedVector<PublicKeyBytes, typenum::U512>,
+    pub aggregate_pubkey: PublicKeyBytes,
+}
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode, Encode, Decode)]
+pub struct HistoricalSummary {
+    pub block_summary_root: B256,
+    pub state_summary_root: B256,
+}
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode, Encode, Decode)]
+pub struct BeaconState {
+    // Versioning
+    pub genesis_time: u64,
+    pub genesis_validators_root: B256,
+    pub slot: u64,
+    pub fork: Fork,
+
+    // History
+    pub latest_block_header: BeaconBlockHeader,
+    pub block_roots: FixedVector<B256, typenum::U8192>,
+    pub state_roots: FixedVector<B256, typenum::U8192>,
+    pub historical_roots: VariableList<B256, typenum::U16777216>,
+
+    // Ethereum 1.0 chain data
+    pub eth1_data: Eth1Data,
+    pub eth1_data_votes: VariableList<Eth1Data, typenum::U2048>,
+    pub eth1_deposit_index: u64,
+
+    // Registry
+    pub validators: VariableList<Validator, typenum::U1099511627776>,
+    pub balances: VariableList<u64, typenum::U1099511627776>,
+
+    // Randomness
+    pub randao_mixes: FixedVector<B256, typenum::U65536>,
+
+    // Slashings
+    pub slashings: FixedVector<u64, typenum::U8192>,
+
+    // Participation (Altair and later)
+    pub previous_epoch_participation: VariableList<u8, typenum::U1099511627776>,
+    pub current_epoch_participation: VariableList<u8, typenum::U1099511627776>,
+
+    // Finality
+    pub justification_bits: BitVector<typenum::U4>,
+    pub previous_justified_checkpoint: Checkpoint,
+    pub current_justified_checkpoint: Checkpoint,
+    pub finalized_checkpoint: Checkpoint,
+
+    // Inactivity
+    pub inactivity_scores: VariableList<u64, typenum::U1099511627776>,
+
+    // Light-client sync committees
+    pub current_sync_committee: SyncCommittee,
+    pub next_sync_committee: SyncCommittee,
+
+    // Execution
+    pub latest_execution_payload_header: ExecutionPayloadHeader,
+
+    // Capella
+    pub next_withdrawal_index: u64,
+    pub next_withdrawal_validator_index: u64,
+
+    // Deneb
+    pub historical_summaries: VariableList<HistoricalSummary, typenum::U16777216>,
+}
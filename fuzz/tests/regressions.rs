@@ -0,0 +1,7 @@
+//! Replays the inputs under `regressions/<target>`, minimized from crashes the fuzz targets found.
+use std::path::Path;
+
+#[test]
+fn test_regressions() {
+    sszb_fuzz::replay_regressions(&Path::new(env!("CARGO_MANIFEST_DIR")).join("regressions"));
+}
@@ -0,0 +1,451 @@
+//! Inspect and convert SSZ encoded files from the command line.
+//!
+//! The `sszb` binary knows the consensus containers of every fork from phase0 to fulu, with the
+//! lengths of the mainnet preset (see `sszb_consensus::mainnet`). Any other container deriving
+//! `SszbEncode`, `SszbDecode`, `SszbSchema`, `TreeHash` and serde's traits (usually through
+//! `SszbSerde`, for the consensus spec JSON/YAML representation) can be registered and used with the same
+//! subcommands, from a binary of your own:
+//!
+//! ```ignore
+//! fn main() {
+//!     let mut registry = sszb_cli::Registry::with_builtins();
+//!     registry.register::<MyContainer>("custom", "MyContainer");
+//!     std::process::exit(sszb_cli::run(registry));
+//! }
+//! ```
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use sszb::{
+    ssz_layout, DecodeError, FieldSpan, SszDecode, SszEncode, SszLayout, SszReflect, SszSchema,
+};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::Write;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use tree_hash::TreeHash;
+
+#[derive(Debug)]
+pub enum Error {
+    /// No type was registered under this fork and name.
+    UnknownType {
+        fork: String,
+        name: String,
+    },
+    Decode(DecodeError),
+    Json(serde_json::Error),
+    Yaml(serde_yaml::Error),
+    Io(std::io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::UnknownType { fork, name } => {
+                write!(
+                    f,
+                    "unknown type {} for fork {}, see `sszb types`",
+                    name, fork
+                )
+            }
+            Error::Decode(e) => write!(f, "SSZ decoding failed: {:?}", e),
+            Error::Json(e) => write!(f, "invalid JSON: {}", e),
+            Error::Yaml(e) => write!(f, "invalid YAML: {}", e),
+            Error::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<DecodeError> for Error {
+    fn from(e: DecodeError) -> Self {
+        Error::Decode(e)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::Json(e)
+    }
+}
+
+impl From<serde_yaml::Error> for Error {
+    fn from(e: serde_yaml::Error) -> Self {
+        Error::Yaml(e)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+/// The operations behind each subcommand, for a registered type.
+pub trait Inspect {
+    fn decode(&self, bytes: &[u8]) -> Result<Value, Error>;
+    fn encode(&self, value: Value) -> Result<Vec<u8>, Error>;
+    fn root(&self, bytes: &[u8]) -> Result<tree_hash::Hash256, Error>;
+    fn layout(&self, bytes: &[u8]) -> Result<Vec<FieldSpan>, Error>;
+    fn schema(&self) -> SszSchema;
+}
+
+struct Typed<T>(PhantomData<T>);
+
+impl<T> Inspect for Typed<T>
+where
+    T: SszEncode + SszDecode + SszLayout + SszReflect + TreeHash + Serialize + DeserializeOwned,
+{
+    fn decode(&self, bytes: &[u8]) -> Result<Value, Error> {
+        Ok(serde_json::to_value(T::from_ssz_bytes(bytes)?)?)
+    }
+
+    fn encode(&self, value: Value) -> Result<Vec<u8>, Error> {
+        Ok(serde_json::from_value::<T>(value)?.to_ssz())
+    }
+
+    fn root(&self, bytes: &[u8]) -> Result<tree_hash::Hash256, Error> {
+        Ok(T::from_ssz_bytes(bytes)?.tree_hash_root())
+    }
+
+    fn layout(&self, bytes: &[u8]) -> Result<Vec<FieldSpan>, Error> {
+        Ok(ssz_layout::<T>(bytes)?)
+    }
+
+    fn schema(&self) -> SszSchema {
+        T::ssz_schema()
+    }
+}
+
+/// The types the CLI can work with, by fork and name.
+#[derive(Default)]
+pub struct Registry {
+    types: BTreeMap<(String, String), Box<dyn Inspect>>,
+}
+
+// registers the containers of each fork of the mainnet preset under their own names, those the
+// fork inherits from the earlier forks included
+macro_rules! register_forks {
+    ($registry:ident; [$($prev:ident),*];) => {};
+    ($registry:ident; [$($prev:ident),*]; $fork:ident [$($new:ident),* $(,)?] $($rest:tt)*) => {
+        $(
+            $registry.register::<sszb_consensus::mainnet::$fork::$prev>(
+                stringify!($fork),
+                stringify!($prev),
+            );
+        )*
+        $(
+            $registry.register::<sszb_consensus::mainnet::$fork::$new>(
+                stringify!($fork),
+                stringify!($new),
+            );
+        )*
+        register_forks!($registry; [$($prev,)* $($new),*]; $($rest)*);
+    };
+}
+
+impl Registry {
+    /// An empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A registry with the consensus containers of every fork, under the fork names of the
+    /// spec (`phase0`, `altair`... `fulu`).
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::new();
+        register_forks!(registry; [];
+            phase0 [
+                Fork, ForkData, Checkpoint, Validator, AttestationData, IndexedAttestation,
+                PendingAttestation, Eth1Data, HistoricalBatch, DepositMessage, DepositData,
+                BeaconBlockHeader, SigningData, ProposerSlashing, AttesterSlashing, Attestation,
+                Deposit, VoluntaryExit, BeaconBlockBody, BeaconBlock, BeaconState,
+                SignedVoluntaryExit, SignedBeaconBlock, SignedBeaconBlockHeader, Eth1Block,
+                AggregateAndProof, SignedAggregateAndProof,
+            ]
+            altair [
+                SyncAggregate, SyncCommittee, SyncCommitteeMessage, SyncCommitteeContribution,
+                ContributionAndProof, SignedContributionAndProof, SyncAggregatorSelectionData,
+            ]
+            bellatrix [ExecutionPayload, ExecutionPayloadHeader]
+            capella [
+                Withdrawal, BLSToExecutionChange, SignedBLSToExecutionChange, HistoricalSummary,
+            ]
+            deneb [BlobSidecar, BlobIdentifier]
+            electra [
+                PendingDeposit, PendingPartialWithdrawal, PendingConsolidation, DepositRequest,
+                WithdrawalRequest, ConsolidationRequest, ExecutionRequests, SingleAttestation,
+            ]
+            fulu [DataColumnSidecar, MatrixEntry, DataColumnsByRootIdentifier]
+        );
+        registry
+    }
+
+    /// Registers `T` under `fork` and `name`, replacing any type already registered there.
+    pub fn register<T>(&mut self, fork: &str, name: &str) -> &mut Self
+    where
+        T: SszEncode
+            + SszDecode
+            + SszLayout
+            + SszReflect
+            + TreeHash
+            + Serialize
+            + DeserializeOwned
+            + 'static,
+    {
+        self.types.insert(
+            (fork.to_string(), name.to_string()),
+            Box::new(Typed::<T>(PhantomData)),
+        );
+        self
+    }
+
+    pub fn get(&self, fork: &str, name: &str) -> Result<&dyn Inspect, Error> {
+        self.types
+            .get(&(fork.to_string(), name.to_string()))
+            .map(|inspect| inspect.as_ref())
+            .ok_or_else(|| Error::UnknownType {
+                fork: fork.to_string(),
+                name: name.to_string(),
+            })
+    }
+
+    /// The registered (fork, name) pairs, in order.
+    pub fn names(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.types
+            .keys()
+            .map(|(fork, name)| (fork.as_str(), name.as_str()))
+    }
+}
+
+/// A value that differs between two decoded objects, `None` where one side has no such value.
+#[derive(Debug, PartialEq)]
+pub struct Difference {
+    /// The path to the value, e.g. `message.body.attestations[3].data.slot`.
+    pub path: String,
+    pub left: Option<Value>,
+    pub right: Option<Value>,
+}
+
+/// Compares two decoded values field by field and element by element.
+pub fn diff_values(left: &Value, right: &Value) -> Vec<Difference> {
+    let mut differences = vec![];
+    diff_at(String::new(), Some(left), Some(right), &mut differences);
+    differences
+}
+
+fn diff_at(path: String, left: Option<&Value>, right: Option<&Value>, out: &mut Vec<Difference>) {
+    match (left, right) {
+        (Some(Value::Object(left)), Some(Value::Object(right))) => {
+            let keys = left
+                .keys()
+                .chain(right.keys().filter(|key| !left.contains_key(*key)));
+            for key in keys {
+                let path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                diff_at(path, left.get(key), right.get(key), out);
+            }
+        }
+        (Some(Value::Array(left)), Some(Value::Array(right))) => {
+            for i in 0..std::cmp::max(left.len(), right.len()) {
+                diff_at(format!("{}[{}]", path, i), left.get(i), right.get(i), out);
+            }
+        }
+        _ if left != right => out.push(Difference {
+            path,
+            left: left.cloned(),
+            right: right.cloned(),
+        }),
+        _ => {}
+    }
+}
+
+#[derive(Parser)]
+#[command(name = "sszb", about = "Inspect and convert SSZ encoded files")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Args)]
+struct TypeArgs {
+    /// The type of the SSZ data, e.g. BeaconState
+    #[arg(long = "type", short = 't')]
+    name: String,
+    /// The fork the type belongs to
+    #[arg(long, default_value = "deneb")]
+    fork: String,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Json,
+    Yaml,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Decode an SSZ file and print it as JSON or YAML
+    Decode {
+        #[command(flatten)]
+        ty: TypeArgs,
+        file: PathBuf,
+        #[arg(long, value_enum, default_value = "json")]
+        format: Format,
+    },
+    /// Encode a JSON or YAML file (by extension) to SSZ
+    Encode {
+        #[command(flatten)]
+        ty: TypeArgs,
+        file: PathBuf,
+        /// Where to write the SSZ bytes, stdout if not given
+        #[arg(long, short = 'o')]
+        output: Option<PathBuf>,
+    },
+    /// Print the hash tree root of an SSZ file
+    Root {
+        #[command(flatten)]
+        ty: TypeArgs,
+        file: PathBuf,
+    },
+    /// Show the byte ranges each field of an SSZ file occupies
+    Layout {
+        #[command(flatten)]
+        ty: TypeArgs,
+        file: PathBuf,
+    },
+    /// Compare two SSZ files of the same type, field by field
+    Diff {
+        #[command(flatten)]
+        ty: TypeArgs,
+        left: PathBuf,
+        right: PathBuf,
+    },
+    /// Print the SSZ schema of a type, one field per line
+    Schema {
+        #[command(flatten)]
+        ty: TypeArgs,
+    },
+    /// List the registered types
+    Types,
+}
+
+/// Runs the CLI on the process arguments, returning the exit code.
+///
+/// The exit code is 0 on success, 1 when `diff` finds differences and 2 on errors.
+pub fn run(registry: Registry) -> i32 {
+    let cli = Cli::parse();
+    match execute(&registry, cli.command, &mut std::io::stdout().lock()) {
+        Ok(true) => 0,
+        Ok(false) => 1,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            2
+        }
+    }
+}
+
+// returns false if diff found differences
+fn execute(registry: &Registry, command: Command, out: &mut impl Write) -> Result<bool, Error> {
+    match command {
+        Command::Decode { ty, file, format } => {
+            let value = registry
+                .get(&ty.fork, &ty.name)?
+                .decode(&std::fs::read(file)?)?;
+            match format {
+                Format::Json => writeln!(out, "{}", serde_json::to_string_pretty(&value)?)?,
+                Format::Yaml => write!(out, "{}", serde_yaml::to_string(&value)?)?,
+            }
+        }
+        Command::Encode { ty, file, output } => {
+            let bytes = registry
+                .get(&ty.fork, &ty.name)?
+                .encode(read_value(&file)?)?;
+            match output {
+                Some(output) => std::fs::write(output, bytes)?,
+                None => out.write_all(&bytes)?,
+            }
+        }
+        Command::Root { ty, file } => {
+            let root = registry
+                .get(&ty.fork, &ty.name)?
+                .root(&std::fs::read(file)?)?;
+            writeln!(out, "{:#x}", root)?;
+        }
+        Command::Layout { ty, file } => {
+            let spans = registry
+                .get(&ty.fork, &ty.name)?
+                .layout(&std::fs::read(file)?)?;
+            let width = spans
+                .iter()
+                .map(|span| span.field.name.len())
+                .max()
+                .unwrap_or(0);
+            for span in spans {
+                write!(
+                    out,
+                    "{:width$}  {:>10}..{:<10}",
+                    span.field.name, span.fixed.start, span.fixed.end
+                )?;
+                match span.variable {
+                    Some(variable) => writeln!(
+                        out,
+                        "  offset -> {}..{} ({} bytes)",
+                        variable.start,
+                        variable.end,
+                        variable.len()
+                    )?,
+                    None => writeln!(out, "  fixed ({} bytes)", span.fixed.len())?,
+                }
+            }
+        }
+        Command::Diff { ty, left, right } => {
+            let inspect = registry.get(&ty.fork, &ty.name)?;
+            let left = inspect.decode(&std::fs::read(left)?)?;
+            let right = inspect.decode(&std::fs::read(right)?)?;
+            let differences = diff_values(&left, &right);
+            for difference in &differences {
+                writeln!(
+                    out,
+                    "{}: {} != {}",
+                    difference.path,
+                    display_value(&difference.left),
+                    display_value(&difference.right)
+                )?;
+            }
+            return Ok(differences.is_empty());
+        }
+        Command::Schema { ty } => match registry.get(&ty.fork, &ty.name)?.schema() {
+            SszSchema::Container { name, fields } => {
+                writeln!(out, "{}", name)?;
+                for (name, schema) in fields {
+                    writeln!(out, "  {}: {}", name, schema)?;
+                }
+            }
+            schema => writeln!(out, "{}", schema)?,
+        },
+        Command::Types => {
+            for (fork, name) in registry.names() {
+                writeln!(out, "{} {}", fork, name)?;
+            }
+        }
+    }
+    Ok(true)
+}
+
+fn read_value(path: &Path) -> Result<Value, Error> {
+    let contents = std::fs::read_to_string(path)?;
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("yaml" | "yml") => Ok(serde_yaml::from_str(&contents)?),
+        _ => Ok(serde_json::from_str(&contents)?),
+    }
+}
+
+fn display_value(value: &Option<Value>) -> String {
+    value
+        .as_ref()
+        .map_or_else(|| "<missing>".to_string(), |value| value.to_string())
+}
@@ -0,0 +1,3 @@
+fn main() {
+    std::process::exit(sszb_cli::run(sszb_cli::Registry::with_builtins()));
+}
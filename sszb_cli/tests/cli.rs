@@ -0,0 +1,123 @@
+use bytes::buf::{Buf, BufMut};
+use serde_json::json;
+use ssz_types::VariableList;
+use sszb::{SszEncode, SszField, SszSchema};
+use sszb_cli::{diff_values, Difference, Registry};
+use sszb_derive::{SszbDecode, SszbEncode, SszbSchema, SszbSerde};
+use tree_hash_derive::TreeHash;
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode, TreeHash, SszbSerde, SszbSchema)]
+struct Custom {
+    a: u16,
+    b: VariableList<u8, typenum::U8>,
+    c: u8,
+    d: VariableList<u16, typenum::U8>,
+}
+
+#[test]
+fn test_registered_type() {
+    let mut registry = Registry::with_builtins();
+    registry.register::<Custom>("custom", "Custom");
+    assert!(registry.names().any(|name| name == ("custom", "Custom")));
+    assert!(registry
+        .names()
+        .any(|name| name == ("deneb", "BeaconState")));
+    assert!(registry
+        .names()
+        .any(|name| name == ("electra", "SingleAttestation")));
+    assert!(registry.get("altair", "BlobSidecar").is_err());
+    assert!(registry.get("custom", "Missing").is_err());
+
+    let custom = Custom {
+        a: 1,
+        b: VariableList::new(vec![2, 3]).unwrap(),
+        c: 4,
+        d: VariableList::new(vec![5]).unwrap(),
+    };
+    let bytes = custom.to_ssz();
+    let inspect = registry.get("custom", "Custom").unwrap();
+
+    let value = inspect.decode(&bytes).unwrap();
+    assert_eq!(
+        value,
+        json!({ "a": "1", "b": "0x0203", "c": "4", "d": ["5"] })
+    );
+    assert_eq!(inspect.encode(value).unwrap(), bytes);
+    assert!(inspect.decode(&bytes[..5]).is_err());
+
+    let layout = inspect.layout(&bytes).unwrap();
+    assert_eq!(
+        layout.iter().map(|span| span.field).collect::<Vec<_>>(),
+        vec![
+            SszField {
+                name: "a",
+                is_static: true,
+                fixed_len: 2
+            },
+            SszField {
+                name: "b",
+                is_static: false,
+                fixed_len: 4
+            },
+            SszField {
+                name: "c",
+                is_static: true,
+                fixed_len: 1
+            },
+            SszField {
+                name: "d",
+                is_static: false,
+                fixed_len: 4
+            },
+        ]
+    );
+    assert_eq!(
+        layout
+            .iter()
+            .map(|span| span.fixed.clone())
+            .collect::<Vec<_>>(),
+        vec![0..2, 2..6, 6..7, 7..11]
+    );
+    assert_eq!(
+        layout
+            .iter()
+            .map(|span| span.variable.clone())
+            .collect::<Vec<_>>(),
+        vec![None, Some(11..13), None, Some(13..15)]
+    );
+
+    assert_eq!(
+        inspect.schema(),
+        SszSchema::Container {
+            name: "Custom".to_string(),
+            fields: vec![
+                ("a".to_string(), SszSchema::Uint(2)),
+                ("b".to_string(), SszSchema::list(SszSchema::Uint(1), 8)),
+                ("c".to_string(), SszSchema::Uint(1)),
+                ("d".to_string(), SszSchema::list(SszSchema::Uint(2), 8)),
+            ],
+        }
+    );
+}
+
+#[test]
+fn test_diff_values() {
+    let left = json!({ "a": 1, "b": [2, 3], "c": { "d": 4 } });
+    let right = json!({ "a": 1, "b": [2], "c": { "d": 5 } });
+    assert_eq!(
+        diff_values(&left, &right),
+        vec![
+            Difference {
+                path: "b[1]".to_string(),
+                left: Some(json!(3)),
+                right: None,
+            },
+            Difference {
+                path: "c.d".to_string(),
+                left: Some(json!(4)),
+                right: Some(json!(5)),
+            },
+        ]
+    );
+    assert!(diff_values(&left, &left).is_empty());
+}
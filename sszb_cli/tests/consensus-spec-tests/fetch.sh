@@ -0,0 +1,61 @@
+#!/usr/bin/env bash
+# Replaces the vectors checked in next to this script with a subset of a consensus-spec-tests
+# release: the ssz_generic cases, and the smallest ssz_random case of every deneb container and
+# of a few containers of each other fork from the mainnet ssz_static cases. The release tag is
+# written to VERSION.
+#
+# Usage: ./fetch.sh [release tag]  (needs curl and network access)
+set -euo pipefail
+
+VERSION=${1:-v1.6.0}
+URL=https://github.com/ethereum/consensus-spec-tests/releases/download/$VERSION
+DIR=$(cd "$(dirname "$0")" && pwd)
+TMP=$(mktemp -d)
+trap 'rm -rf "$TMP"' EXIT
+
+# the containers each fork adds or changes, deneb has all of them as it is the fork of the benches
+declare -A STATIC=(
+    [phase0]="Fork Checkpoint AttestationData PendingAttestation Validator BeaconBlockBody SignedBeaconBlock"
+    [altair]="SyncAggregate SyncCommittee SyncCommitteeContribution BeaconBlockBody"
+    [bellatrix]="ExecutionPayload ExecutionPayloadHeader BeaconBlockBody"
+    [capella]="Withdrawal SignedBLSToExecutionChange HistoricalSummary ExecutionPayload BeaconBlockBody"
+    [deneb]="*"
+    [electra]="Attestation SingleAttestation PendingDeposit ExecutionRequests BeaconBlockBody"
+    [fulu]="DataColumnSidecar MatrixEntry DataColumnsByRootIdentifier"
+)
+# ssz_generic cases bigger than this are left out
+MAX_GENERIC_CASE=16384
+
+echo "downloading $URL/general.tar.gz"
+curl -fL "$URL/general.tar.gz" | tar -xz -C "$TMP" --wildcards 'tests/general/phase0/ssz_generic/*'
+echo "downloading $URL/mainnet.tar.gz"
+curl -fL "$URL/mainnet.tar.gz" | tar -xz -C "$TMP" --wildcards 'tests/mainnet/*/ssz_static/*'
+
+rm -rf "$DIR/tests"
+
+src=$TMP/tests/general/phase0/ssz_generic
+for case in "$src"/*/*/*/; do
+    if [ "$(du -sb "$case" | cut -f1)" -le $MAX_GENERIC_CASE ]; then
+        dest=$DIR/tests/general/phase0/ssz_generic/${case#"$src"/}
+        mkdir -p "$dest"
+        cp "$case"* "$dest"
+    fi
+done
+
+for fork in "${!STATIC[@]}"; do
+    src=$TMP/tests/mainnet/$fork/ssz_static
+    if [ "${STATIC[$fork]}" = "*" ]; then
+        names=$(ls "$src")
+    else
+        names=${STATIC[$fork]}
+    fi
+    for name in $names; do
+        case=$(du -sb "$src/$name"/ssz_random/*/ | sort -n | head -n 1 | cut -f2)
+        dest=$DIR/tests/mainnet/$fork/ssz_static/$name/ssz_random/$(basename "$case")
+        mkdir -p "$dest"
+        cp "$case"/* "$dest"
+    done
+done
+
+echo "$VERSION" > "$DIR/VERSION"
+du -sh "$DIR/tests"
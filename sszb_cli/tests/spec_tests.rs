@@ -0,0 +1,518 @@
+//! Runs the `ssz_static` and `ssz_generic` consensus spec test vectors.
+//!
+//! By default this runs the vectors checked in under `tests/consensus-spec-tests`, which its
+//! `fetch.sh` replaces with a subset of a consensus-spec-tests release and its `VERSION` describes,
+//! set `CONSENSUS_SPEC_TESTS` to an extracted release to run the full set. Cases for types sszb has
+//! no mapping for are counted as skipped.
+//!
+//! The checked-in vectors have no `ssz_static` cases, so `test_ssz_static` is ignored by default:
+//! once `fetch.sh` has been run, include it with `cargo test -p sszb_cli --test spec_tests --
+//! --include-ignored`.
+#![allow(non_snake_case)]
+
+use alloy_primitives::{U128, U256};
+use bytes::buf::{Buf, BufMut};
+use serde::Deserialize;
+use ssz_types::FixedVector;
+use sszb::spec_serde::SszSerdeOwned;
+use sszb::{
+    Bitlist, Bitvector, ByteList, SszDecode, SszEncode, SszReflect, SszSchema, SszSerde, SszValue,
+};
+use sszb_cli::{Inspect, Registry};
+use sszb_derive::{SszbDecode, SszbEncode, SszbSchema, SszbSerde};
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::fs;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use tree_hash::TreeHash;
+use tree_hash_derive::TreeHash;
+use typenum::{
+    U1, U1024, U128 as N128, U16, U2, U256 as N256, U3, U31, U4, U5, U512, U513, U6, U8,
+};
+
+#[allow(unused)]
+#[path = "../../sszb_lib/benches/beacon_block.rs"]
+mod beacon_block;
+
+#[allow(unused)]
+#[path = "../../sszb_lib/benches/beacon_state.rs"]
+mod beacon_state;
+
+// the containers of the ssz_generic `containers` handler
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode, TreeHash, SszbSerde, SszbSchema)]
+struct SingleFieldTestStruct {
+    A: u8,
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode, TreeHash, SszbSerde, SszbSchema)]
+struct SmallTestStruct {
+    A: u16,
+    B: u16,
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode, TreeHash, SszbSerde, SszbSchema)]
+struct FixedTestStruct {
+    A: u8,
+    B: u64,
+    C: u32,
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode, TreeHash, SszbSerde, SszbSchema)]
+struct VarTestStruct {
+    A: u16,
+    B: ssz_types::VariableList<u16, U1024>,
+    C: u8,
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode, TreeHash, SszbSerde, SszbSchema)]
+struct ComplexTestStruct {
+    A: u16,
+    B: ssz_types::VariableList<u16, N128>,
+    C: u8,
+    D: ByteList<N256>,
+    E: VarTestStruct,
+    F: FixedVector<FixedTestStruct, U4>,
+    G: FixedVector<VarTestStruct, U2>,
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode, TreeHash, SszbSerde, SszbSchema)]
+struct BitsStruct {
+    A: Bitlist<U5>,
+    B: Bitvector<U2>,
+    C: Bitvector<U1>,
+    D: Bitlist<U6>,
+    E: Bitvector<U8>,
+}
+
+#[derive(Deserialize)]
+struct Roots {
+    root: String,
+}
+
+// the containers of the benches and the ones they hold, which must all have ssz_static cases
+const BENCH_CONTAINERS: [&str; 27] = [
+    "Fork",
+    "Checkpoint",
+    "BeaconBlockHeader",
+    "SignedBeaconBlockHeader",
+    "Eth1Data",
+    "ProposerSlashing",
+    "AttestationData",
+    "IndexedAttestation",
+    "AttesterSlashing",
+    "Attestation",
+    "DepositData",
+    "Deposit",
+    "VoluntaryExit",
+    "SignedVoluntaryExit",
+    "SyncAggregate",
+    "Withdrawal",
+    "ExecutionPayload",
+    "ExecutionPayloadHeader",
+    "BLSToExecutionChange",
+    "SignedBLSToExecutionChange",
+    "BeaconBlockBody",
+    "BeaconBlock",
+    "SignedBeaconBlock",
+    "Validator",
+    "SyncCommittee",
+    "HistoricalSummary",
+    "BeaconState",
+];
+
+fn spec_tests_dir() -> PathBuf {
+    let dir = match std::env::var_os("CONSENSUS_SPEC_TESTS") {
+        Some(dir) => PathBuf::from(dir),
+        None => Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/consensus-spec-tests"),
+    };
+    assert!(
+        dir.join("tests").is_dir(),
+        "no spec test vectors under {}, run tests/consensus-spec-tests/fetch.sh",
+        dir.display()
+    );
+    dir
+}
+
+// the sorted subdirectories of dir, none if it doesn't exist
+fn subdirs(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut dirs = entries
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.is_dir())
+        .collect::<Vec<_>>();
+    dirs.sort();
+    dirs
+}
+
+fn file_name(path: &Path) -> &str {
+    path.file_name().unwrap().to_str().unwrap()
+}
+
+fn read_serialized(case: &Path) -> Result<Vec<u8>, String> {
+    let compressed = fs::read(case.join("serialized.ssz_snappy")).map_err(|e| e.to_string())?;
+    snap::raw::Decoder::new()
+        .decompress_vec(&compressed)
+        .map_err(|e| format!("snappy: {}", e))
+}
+
+fn read_yaml<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T, String> {
+    let yaml = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    serde_yaml::from_str(&yaml).map_err(|e| format!("{}: {}", file_name(path), e))
+}
+
+// decoders must reject bad input with an error, a panic is a failure of its own
+fn try_decode<T: SszDecode>(bytes: &[u8]) -> Result<Result<T, String>, String> {
+    catch_unwind(AssertUnwindSafe(|| T::from_ssz_bytes(bytes)))
+        .map(|res| res.map_err(|e| format!("{:?}", e)))
+        .map_err(|_| "decoding panicked".to_string())
+}
+
+// decoding into an SszValue with the schema of the type must agree with the typed path: `bytes`
+// are rejected if invalid, and otherwise re-encode to themselves with the same root
+fn check_dynamic(schema: &SszSchema, bytes: &[u8], root: Option<&str>) -> Result<(), String> {
+    let decoded = catch_unwind(AssertUnwindSafe(|| SszValue::from_ssz_bytes(schema, bytes)))
+        .map_err(|_| "dynamic decoding panicked".to_string())?;
+    let Some(expected) = root else {
+        return match decoded {
+            Ok(value) => Err(format!("invalid input decoded dynamically as {:?}", value)),
+            Err(_) => Ok(()),
+        };
+    };
+    let value = decoded.map_err(|e| format!("dynamic decoding: {:?}", e))?;
+    if value.to_ssz(schema).map_err(|e| e.to_string())? != bytes {
+        return Err("dynamic re-encoding differs from serialized.ssz_snappy".to_string());
+    }
+    let root = format!(
+        "{:#x}",
+        value.tree_hash_root(schema).map_err(|e| e.to_string())?
+    );
+    if root != expected {
+        return Err(format!("dynamic root {} != {}", root, expected));
+    }
+    Ok(())
+}
+
+#[derive(Default)]
+struct Summary {
+    passed: usize,
+    skipped: usize,
+    failures: Vec<String>,
+}
+
+impl Summary {
+    fn record(&mut self, case: &Path, res: Option<Result<(), String>>) {
+        match res {
+            Some(Ok(())) => self.passed += 1,
+            Some(Err(e)) => self.failures.push(format!("{}: {}", case.display(), e)),
+            None => self.skipped += 1,
+        }
+    }
+
+    fn finish(self, suite: &str) {
+        eprintln!(
+            "{}: {} passed, {} failed, {} skipped",
+            suite,
+            self.passed,
+            self.failures.len(),
+            self.skipped
+        );
+        assert!(
+            self.failures.is_empty(),
+            "{} failures:\n{}",
+            suite,
+            self.failures.join("\n")
+        );
+        assert!(
+            self.passed > 0,
+            "no {} cases found, run tests/consensus-spec-tests/fetch.sh",
+            suite
+        );
+    }
+}
+
+// ssz_static: each case must decode, re-encode to the same bytes, match value.yaml and roots.yaml
+fn check_static(inspect: &dyn Inspect, case: &Path) -> Result<(), String> {
+    let bytes = read_serialized(case)?;
+    let decoded = catch_unwind(AssertUnwindSafe(|| inspect.decode(&bytes)))
+        .map_err(|_| "decoding panicked".to_string())?
+        .map_err(|e| e.to_string())?;
+    if inspect.encode(decoded).map_err(|e| e.to_string())? != bytes {
+        return Err("re-encoding differs from serialized.ssz_snappy".to_string());
+    }
+    let value = read_yaml::<serde_json::Value>(&case.join("value.yaml"))?;
+    if inspect.encode(value).map_err(|e| e.to_string())? != bytes {
+        return Err("value.yaml encodes differently from serialized.ssz_snappy".to_string());
+    }
+    let expected = read_yaml::<Roots>(&case.join("roots.yaml"))?.root;
+    let root = format!("{:#x}", inspect.root(&bytes).map_err(|e| e.to_string())?);
+    if root != expected {
+        return Err(format!("root {} != {}", root, expected));
+    }
+    check_dynamic(&inspect.schema(), &bytes, Some(&expected))
+}
+
+// the bench containers must decode the case themselves and re-encode it, and those with a
+// `TreeHash` implementation match roots.yaml too
+fn decode_bench<T: SszEncode + SszDecode>(case: &Path) -> Result<T, String> {
+    let bytes = read_serialized(case)?;
+    let decoded = try_decode::<T>(&bytes)??;
+    if decoded.to_ssz() != bytes {
+        return Err(
+            "bench container re-encodes differently from serialized.ssz_snappy".to_string(),
+        );
+    }
+    Ok(decoded)
+}
+
+fn check_bench<T: SszEncode + SszDecode>(case: &Path) -> Result<(), String> {
+    decode_bench::<T>(case).map(|_| ())
+}
+
+fn check_bench_root<T: SszEncode + SszDecode + TreeHash>(case: &Path) -> Result<(), String> {
+    let root = format!("{:#x}", decode_bench::<T>(case)?.tree_hash_root());
+    let expected = read_yaml::<Roots>(&case.join("roots.yaml"))?.root;
+    if root != expected {
+        return Err(format!("bench container root {} != {}", root, expected));
+    }
+    Ok(())
+}
+
+// both bench modules define some of the small containers
+fn check_bench_container(name: &str, case: &Path) -> Option<Result<(), String>> {
+    use beacon_block as block;
+    use beacon_state as state;
+    Some(match name {
+        "Fork" => check_bench_root::<state::Fork>(case),
+        "Checkpoint" => check_bench_root::<block::Checkpoint>(case)
+            .and_then(|()| check_bench_root::<state::Checkpoint>(case)),
+        "BeaconBlockHeader" => check_bench_root::<block::BeaconBlockHeader>(case)
+            .and_then(|()| check_bench_root::<state::BeaconBlockHeader>(case)),
+        "SignedBeaconBlockHeader" => check_bench_root::<block::SignedBeaconBlockHeader>(case),
+        "Eth1Data" => check_bench::<block::Eth1Data>(case)
+            .and_then(|()| check_bench_root::<state::Eth1Data>(case)),
+        "ProposerSlashing" => check_bench_root::<block::ProposerSlashing>(case),
+        "AttestationData" => check_bench_root::<block::AttestationData>(case),
+        "IndexedAttestation" => check_bench_root::<block::IndexedAttestation>(case),
+        "AttesterSlashing" => check_bench_root::<block::AttesterSlashing>(case),
+        "Attestation" => check_bench_root::<block::Attestation>(case),
+        "DepositData" => check_bench_root::<block::DepositData>(case),
+        "Deposit" => check_bench_root::<block::Deposit>(case),
+        "VoluntaryExit" => check_bench_root::<block::VoluntaryExit>(case),
+        "SignedVoluntaryExit" => check_bench_root::<block::SignedVoluntaryExit>(case),
+        "SyncAggregate" => check_bench::<block::SyncAggregate>(case),
+        "Withdrawal" => check_bench_root::<block::Withdrawal>(case),
+        "ExecutionPayload" => check_bench::<block::ExecutionPayload>(case),
+        "ExecutionPayloadHeader" => check_bench_root::<state::ExecutionPayloadHeader>(case),
+        "BLSToExecutionChange" => check_bench_root::<block::BlsToExecutionChange>(case),
+        "SignedBLSToExecutionChange" => check_bench_root::<block::SignedBlsToExecutionChange>(case),
+        "BeaconBlockBody" => check_bench::<block::BeaconBlockBody>(case),
+        "BeaconBlock" => check_bench::<block::BeaconBlock>(case),
+        "SignedBeaconBlock" => check_bench::<block::SignedBeaconBlock>(case),
+        "Validator" => check_bench_root::<state::Validator>(case),
+        "SyncCommittee" => check_bench_root::<state::SyncCommittee>(case),
+        "HistoricalSummary" => check_bench_root::<state::HistoricalSummary>(case),
+        "BeaconState" => check_bench_root::<state::BeaconState>(case),
+        _ => return None,
+    })
+}
+
+#[test]
+#[ignore = "needs the ssz_static vectors of a consensus-spec-tests release, see fetch.sh"]
+fn test_ssz_static() {
+    let registry = Registry::with_builtins();
+    let mut summary = Summary::default();
+    let mut cases_by_fork = BTreeMap::<&str, usize>::new();
+    // the registered types use the mainnet preset
+    let config = spec_tests_dir().join("tests/mainnet");
+    for (fork, name) in registry.names() {
+        let inspect = registry.get(fork, name).unwrap();
+        let type_dir = config.join(fork).join("ssz_static").join(name);
+        let cases = subdirs(&type_dir)
+            .iter()
+            .flat_map(|suite| subdirs(suite))
+            .collect::<Vec<_>>();
+        let bench = fork == "deneb" && BENCH_CONTAINERS.contains(&name);
+        if cases.is_empty() && bench {
+            summary
+                .failures
+                .push(format!("{}: no cases", type_dir.display()));
+        }
+        *cases_by_fork.entry(fork).or_default() += cases.len();
+        for case in cases {
+            let mut res = check_static(inspect, &case);
+            if bench {
+                res = res.and_then(|()| {
+                    check_bench_container(name, &case)
+                        .unwrap_or_else(|| Err("no bench container to check".to_string()))
+                });
+            }
+            summary.record(&case, Some(res));
+        }
+    }
+    for (fork, cases) in cases_by_fork {
+        if cases == 0 {
+            summary
+                .failures
+                .push(format!("{}: no ssz_static cases", fork));
+        }
+    }
+    summary.finish("ssz_static");
+}
+
+trait SpecType: SszEncode + SszDecode + SszSerde + SszReflect + TreeHash + PartialEq + Debug {}
+
+impl<T: SszEncode + SszDecode + SszSerde + SszReflect + TreeHash + PartialEq + Debug> SpecType
+    for T
+{
+}
+
+// ssz_generic: valid cases must round trip and match value.yaml and meta.yaml, invalid ones
+// must fail to decode
+fn check<T: SpecType>(case: &Path, valid: bool) -> Result<(), String> {
+    let bytes = read_serialized(case)?;
+    if !valid {
+        return match try_decode::<T>(&bytes)? {
+            Ok(value) => Err(format!("invalid input decoded as {:?}", value)),
+            Err(_) => check_dynamic(&T::ssz_schema(), &bytes, None),
+        };
+    }
+    let decoded = try_decode::<T>(&bytes)??;
+    if decoded.to_ssz() != bytes {
+        return Err("re-encoding differs from serialized.ssz_snappy".to_string());
+    }
+    let SszSerdeOwned(value) = read_yaml::<SszSerdeOwned<T>>(&case.join("value.yaml"))?;
+    if value != decoded {
+        return Err(format!("decoded {:?}, value.yaml has {:?}", decoded, value));
+    }
+    let expected = read_yaml::<Roots>(&case.join("meta.yaml"))?.root;
+    let root = format!("{:#x}", decoded.tree_hash_root());
+    if root != expected {
+        return Err(format!("root {} != {}", root, expected));
+    }
+    check_dynamic(&T::ssz_schema(), &bytes, Some(&expected))
+}
+
+fn check_uint(bits: &str, case: &Path, valid: bool) -> Option<Result<(), String>> {
+    Some(match bits {
+        "8" => check::<u8>(case, valid),
+        "16" => check::<u16>(case, valid),
+        "32" => check::<u32>(case, valid),
+        "64" => check::<u64>(case, valid),
+        "128" => check::<U128>(case, valid),
+        "256" => check::<U256>(case, valid),
+        _ => return None,
+    })
+}
+
+// the vector lengths used by the spec generators
+fn check_vector<T: SpecType>(len: &str, case: &Path, valid: bool) -> Option<Result<(), String>> {
+    Some(match len {
+        "1" => check::<FixedVector<T, U1>>(case, valid),
+        "2" => check::<FixedVector<T, U2>>(case, valid),
+        "3" => check::<FixedVector<T, U3>>(case, valid),
+        "4" => check::<FixedVector<T, U4>>(case, valid),
+        "5" => check::<FixedVector<T, U5>>(case, valid),
+        "8" => check::<FixedVector<T, U8>>(case, valid),
+        "16" => check::<FixedVector<T, U16>>(case, valid),
+        "31" => check::<FixedVector<T, U31>>(case, valid),
+        "512" => check::<FixedVector<T, U512>>(case, valid),
+        "513" => check::<FixedVector<T, U513>>(case, valid),
+        _ => return None,
+    })
+}
+
+fn check_basic_vector(
+    elem: &str,
+    len: &str,
+    case: &Path,
+    valid: bool,
+) -> Option<Result<(), String>> {
+    match elem {
+        "bool" => check_vector::<bool>(len, case, valid),
+        "uint8" => check_vector::<u8>(len, case, valid),
+        "uint16" => check_vector::<u16>(len, case, valid),
+        "uint32" => check_vector::<u32>(len, case, valid),
+        "uint64" => check_vector::<u64>(len, case, valid),
+        "uint128" => check_vector::<U128>(len, case, valid),
+        "uint256" => check_vector::<U256>(len, case, valid),
+        _ => None,
+    }
+}
+
+fn check_bitvector(len: &str, case: &Path, valid: bool) -> Option<Result<(), String>> {
+    Some(match len {
+        "1" => check::<Bitvector<U1>>(case, valid),
+        "2" => check::<Bitvector<U2>>(case, valid),
+        "3" => check::<Bitvector<U3>>(case, valid),
+        "4" => check::<Bitvector<U4>>(case, valid),
+        "5" => check::<Bitvector<U5>>(case, valid),
+        "8" => check::<Bitvector<U8>>(case, valid),
+        "9" => check::<Bitvector<typenum::U9>>(case, valid),
+        "16" => check::<Bitvector<U16>>(case, valid),
+        "31" => check::<Bitvector<U31>>(case, valid),
+        "512" => check::<Bitvector<U512>>(case, valid),
+        "513" => check::<Bitvector<U513>>(case, valid),
+        _ => return None,
+    })
+}
+
+fn check_bitlist(limit: &str, case: &Path, valid: bool) -> Option<Result<(), String>> {
+    Some(match limit {
+        "1" => check::<Bitlist<U1>>(case, valid),
+        "2" => check::<Bitlist<U2>>(case, valid),
+        "3" => check::<Bitlist<U3>>(case, valid),
+        "4" => check::<Bitlist<U4>>(case, valid),
+        "5" => check::<Bitlist<U5>>(case, valid),
+        "8" => check::<Bitlist<U8>>(case, valid),
+        "16" => check::<Bitlist<U16>>(case, valid),
+        "31" => check::<Bitlist<U31>>(case, valid),
+        "512" => check::<Bitlist<U512>>(case, valid),
+        "513" => check::<Bitlist<U513>>(case, valid),
+        _ => return None,
+    })
+}
+
+fn check_container(name: &str, case: &Path, valid: bool) -> Option<Result<(), String>> {
+    Some(match name {
+        "SingleFieldTestStruct" => check::<SingleFieldTestStruct>(case, valid),
+        "SmallTestStruct" => check::<SmallTestStruct>(case, valid),
+        "FixedTestStruct" => check::<FixedTestStruct>(case, valid),
+        "VarTestStruct" => check::<VarTestStruct>(case, valid),
+        "ComplexTestStruct" => check::<ComplexTestStruct>(case, valid),
+        "BitsStruct" => check::<BitsStruct>(case, valid),
+        _ => return None,
+    })
+}
+
+// case names start with the type: `uint_{bits}_`, `vec_{elem}_{len}_`, `bitvec_{len}_`,
+// `bitlist_{limit}_` and `{Container}_`, boolean cases are just named after the value
+fn check_generic(handler: &str, case: &Path, valid: bool) -> Option<Result<(), String>> {
+    let parts = file_name(case).split('_').collect::<Vec<_>>();
+    match handler {
+        "boolean" => Some(check::<bool>(case, valid)),
+        "uints" => check_uint(parts.get(1)?, case, valid),
+        "basic_vector" => check_basic_vector(parts.get(1)?, parts.get(2)?, case, valid),
+        "bitvector" => check_bitvector(parts.get(1)?, case, valid),
+        "bitlist" => check_bitlist(parts.get(1)?, case, valid),
+        "containers" => check_container(parts[0], case, valid),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_ssz_generic() {
+    let mut summary = Summary::default();
+    let generic = spec_tests_dir().join("tests/general/phase0/ssz_generic");
+    for handler in subdirs(&generic) {
+        for (kind, valid) in [("valid", true), ("invalid", false)] {
+            for case in subdirs(&handler.join(kind)) {
+                summary.record(&case, check_generic(file_name(&handler), &case, valid));
+            }
+        }
+    }
+    summary.finish("ssz_generic");
+}
@@ -0,0 +1,180 @@
+//! Evaluates the integer expressions of spec constants and type bounds, such as
+//! `uint64(2**13)` or `MAX_VALIDATORS_PER_COMMITTEE * MAX_COMMITTEES_PER_SLOT`.
+use crate::Error;
+
+#[derive(Clone, PartialEq, Debug)]
+enum Token {
+    Number(u128),
+    Name(String),
+    Pow,
+    Mul,
+    FloorDiv,
+    Add,
+    Sub,
+    Open,
+    Close,
+}
+
+fn tokenize(expr: &str) -> Option<Vec<Token>> {
+    let mut tokens = vec![];
+    let mut chars = expr.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            ' ' => {
+                chars.next();
+            }
+            '0'..='9' => {
+                let mut digits = String::new();
+                while let Some(&c) = chars.peek() {
+                    if !(c.is_ascii_alphanumeric() || c == '_') {
+                        break;
+                    }
+                    if c != '_' {
+                        digits.push(c);
+                    }
+                    chars.next();
+                }
+                let number = match digits.strip_prefix("0x") {
+                    Some(hex) => u128::from_str_radix(hex, 16),
+                    None => digits.parse(),
+                };
+                tokens.push(Token::Number(number.ok()?));
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut name = String::new();
+                while let Some(&c) = chars.peek() {
+                    if !(c.is_ascii_alphanumeric() || c == '_') {
+                        break;
+                    }
+                    name.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Name(name));
+            }
+            _ => {
+                chars.next();
+                let token = match c {
+                    '*' if chars.peek() == Some(&'*') => {
+                        chars.next();
+                        Token::Pow
+                    }
+                    '*' => Token::Mul,
+                    '/' if chars.peek() == Some(&'/') => {
+                        chars.next();
+                        Token::FloorDiv
+                    }
+                    '+' => Token::Add,
+                    '-' => Token::Sub,
+                    '(' => Token::Open,
+                    ')' => Token::Close,
+                    _ => return None,
+                };
+                tokens.push(token);
+            }
+        }
+    }
+    Some(tokens)
+}
+
+// a recursive descent over the tokens, with python's precedence: `**` binds tighter than `*` and
+// `//`, which bind tighter than `+` and `-`
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    position: usize,
+    expr: &'a str,
+    lookup: &'a mut dyn FnMut(&str) -> Result<u128, Error>,
+}
+
+impl Parser<'_> {
+    fn invalid(&self) -> Error {
+        Error::InvalidExpression(self.expr.to_string())
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn sum(&mut self) -> Result<u128, Error> {
+        let mut value = self.product()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Add) => u128::checked_add,
+                Some(Token::Sub) => u128::checked_sub,
+                _ => return Ok(value),
+            };
+            self.next();
+            value = op(value, self.product()?).ok_or_else(|| self.invalid())?;
+        }
+    }
+
+    fn product(&mut self) -> Result<u128, Error> {
+        let mut value = self.power()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Mul) => u128::checked_mul,
+                Some(Token::FloorDiv) => u128::checked_div,
+                _ => return Ok(value),
+            };
+            self.next();
+            value = op(value, self.power()?).ok_or_else(|| self.invalid())?;
+        }
+    }
+
+    // right associative, 2**2**3 is 2**8
+    fn power(&mut self) -> Result<u128, Error> {
+        let base = self.atom()?;
+        if self.peek() != Some(&Token::Pow) {
+            return Ok(base);
+        }
+        self.next();
+        let exponent = u32::try_from(self.power()?).map_err(|_| self.invalid())?;
+        base.checked_pow(exponent).ok_or_else(|| self.invalid())
+    }
+
+    fn atom(&mut self) -> Result<u128, Error> {
+        match self.next() {
+            Some(Token::Number(number)) => Ok(number),
+            Some(Token::Open) => self.group(),
+            // type conversions such as uint64(...) or Epoch(...) keep the value
+            Some(Token::Name(_)) if self.peek() == Some(&Token::Open) => {
+                self.next();
+                self.group()
+            }
+            Some(Token::Name(name)) => (self.lookup)(&name),
+            _ => Err(self.invalid()),
+        }
+    }
+
+    fn group(&mut self) -> Result<u128, Error> {
+        let value = self.sum()?;
+        match self.next() {
+            Some(Token::Close) => Ok(value),
+            _ => Err(self.invalid()),
+        }
+    }
+}
+
+/// Evaluates `expr`, looking up the constants it refers to with `lookup`.
+pub(crate) fn eval(
+    expr: &str,
+    lookup: &mut dyn FnMut(&str) -> Result<u128, Error>,
+) -> Result<u128, Error> {
+    let tokens = tokenize(expr).ok_or_else(|| Error::InvalidExpression(expr.to_string()))?;
+    let mut parser = Parser {
+        tokens,
+        position: 0,
+        expr,
+        lookup,
+    };
+    let value = parser.sum()?;
+    if parser.position != parser.tokens.len() {
+        return Err(parser.invalid());
+    }
+    Ok(value)
+}
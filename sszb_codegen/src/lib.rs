@@ -0,0 +1,449 @@
+//! Generates Rust containers from the consensus spec markdown, so they follow the spec instead of
+//! being transcribed by hand.
+//!
+//! The [`Generator`] reads the `class Name(Container):` definitions from the python code blocks of
+//! the spec documents, along with the custom types (`Slot`, `Root`...) and constants tables they
+//! rely on. Preset files (`presets/mainnet/*.yaml` or `presets/minimal/*.yaml`) set the values of
+//! the preset constants, and list and vector bounds are resolved into `typenum` constants. Each
+//! container becomes a struct deriving `SszbEncode` and `SszbDecode`, each custom type an alias.
+//!
+//! It is meant to run from a build script, writing to `OUT_DIR`:
+//!
+//! ```ignore
+//! // build.rs
+//! fn main() {
+//!     let out_dir = std::path::PathBuf::from(std::env::var("OUT_DIR").unwrap());
+//!     sszb_codegen::Generator::new()
+//!         .spec_file("specs/phase0/beacon-chain.md")
+//!         .preset_file("presets/mainnet/phase0.yaml")
+//!         .map_type("BLSPubkey", "sszb::PKBytes")
+//!         .write(out_dir.join("phase0.rs"))
+//!         .unwrap();
+//! }
+//!
+//! // src/lib.rs
+//! pub mod phase0 {
+//!     include!(concat!(env!("OUT_DIR"), "/phase0.rs"));
+//! }
+//! ```
+//!
+//! The generated code refers to `bytes`, `typenum`, `alloy_primitives`, `ssz_types`, `sszb` and
+//! `sszb_derive`, which the including crate depends on.
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+mod expr;
+mod markdown;
+
+use markdown::Container;
+
+#[derive(Debug)]
+pub enum Error {
+    Io(PathBuf, std::io::Error),
+    /// A type that is neither a basic type, a custom type nor a container of the specs.
+    UnknownType(String),
+    /// A type the generator has no Rust equivalent for, e.g. `Union[...]`.
+    UnsupportedType(String),
+    /// A constant that is neither in the specs nor in the presets.
+    UnknownConstant(String),
+    /// An expression that does not evaluate to an integer, or overflows.
+    InvalidExpression(String),
+    /// An error in the type of a container field, e.g. `BeaconState.validators`.
+    InField {
+        path: String,
+        error: Box<Error>,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(path, e) => write!(f, "{}: {}", path.display(), e),
+            Error::UnknownType(ty) => write!(f, "unknown type {}", ty),
+            Error::UnsupportedType(ty) => write!(f, "unsupported type {}", ty),
+            Error::UnknownConstant(name) => write!(f, "unknown constant {}", name),
+            Error::InvalidExpression(expr) => write!(f, "invalid expression {}", expr),
+            Error::InField { path, error } => write!(f, "{}: {}", path, error),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[derive(Clone, Debug)]
+enum Source {
+    File(PathBuf),
+    Text(String),
+}
+
+impl Source {
+    fn read(&self) -> Result<String, Error> {
+        match self {
+            Source::File(path) => {
+                std::fs::read_to_string(path).map_err(|e| Error::Io(path.clone(), e))
+            }
+            Source::Text(text) => Ok(text.clone()),
+        }
+    }
+}
+
+/// Generates the containers of a set of spec documents, see the crate docs.
+#[derive(Clone, Debug)]
+pub struct Generator {
+    specs: Vec<Source>,
+    presets: Vec<Source>,
+    derives: Vec<String>,
+    types: BTreeMap<String, String>,
+    list_type: String,
+    vector_type: String,
+}
+
+impl Default for Generator {
+    fn default() -> Self {
+        Self {
+            specs: vec![],
+            presets: vec![],
+            derives: ["Clone", "PartialEq", "Debug", "SszbEncode", "SszbDecode"]
+                .map(String::from)
+                .to_vec(),
+            types: BTreeMap::new(),
+            list_type: "ssz_types::VariableList".to_string(),
+            vector_type: "ssz_types::FixedVector".to_string(),
+        }
+    }
+}
+
+impl Generator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a spec document. A container, custom type or constant defined again by a later
+    /// document replaces the earlier definition, so the documents of a fork come after those of
+    /// the forks before it.
+    pub fn spec_file(&mut self, path: impl AsRef<Path>) -> &mut Self {
+        self.specs.push(Source::File(path.as_ref().to_path_buf()));
+        self
+    }
+
+    /// Adds the markdown of a spec document, see [`Generator::spec_file`].
+    pub fn spec(&mut self, markdown: &str) -> &mut Self {
+        self.specs.push(Source::Text(markdown.to_string()));
+        self
+    }
+
+    /// Adds a preset file, whose values take precedence over the spec documents.
+    pub fn preset_file(&mut self, path: impl AsRef<Path>) -> &mut Self {
+        self.presets.push(Source::File(path.as_ref().to_path_buf()));
+        self
+    }
+
+    /// Adds the YAML of a preset file, see [`Generator::preset_file`].
+    pub fn preset(&mut self, yaml: &str) -> &mut Self {
+        self.presets.push(Source::Text(yaml.to_string()));
+        self
+    }
+
+    /// Adds a derive to the generated containers, e.g. `tree_hash_derive::TreeHash`. They derive
+    /// `Clone`, `PartialEq`, `Debug`, `SszbEncode` and `SszbDecode` already.
+    pub fn derive(&mut self, path: &str) -> &mut Self {
+        self.derives.push(path.to_string());
+        self
+    }
+
+    /// Uses `rust_type` for a custom or basic type of the specs, e.g. `sszb::PKBytes` for
+    /// `BLSPubkey`. Custom types keep their alias, which then stands for `rust_type`. A
+    /// `ByteVector[N]` is mapped as `BytesN`.
+    pub fn map_type(&mut self, spec_type: &str, rust_type: &str) -> &mut Self {
+        self.types
+            .insert(spec_type.to_string(), rust_type.to_string());
+        self
+    }
+
+    /// The type of `List[T, N]`, generic over `T` and a `typenum` length,
+    /// `ssz_types::VariableList` by default.
+    pub fn list_type(&mut self, path: &str) -> &mut Self {
+        self.list_type = path.to_string();
+        self
+    }
+
+    /// The type of `Vector[T, N]`, generic over `T` and a `typenum` length,
+    /// `ssz_types::FixedVector` by default.
+    pub fn vector_type(&mut self, path: &str) -> &mut Self {
+        self.vector_type = path.to_string();
+        self
+    }
+
+    /// The Rust source of the custom types and containers of the spec documents.
+    pub fn generate(&self) -> Result<String, Error> {
+        let mut definitions = Definitions::default();
+        for spec in &self.specs {
+            definitions.add(markdown::parse(&spec.read()?));
+        }
+        for preset in &self.presets {
+            for (name, value) in markdown::parse_preset(&preset.read()?) {
+                definitions.presets.insert(name, value);
+            }
+        }
+        Emitter {
+            generator: self,
+            definitions: &definitions,
+        }
+        .emit()
+    }
+
+    /// Writes the generated source to `path`, and has cargo rerun the build script when a spec
+    /// or preset file changes. The file is left untouched if its contents are the same, so that
+    /// the including crate isn't rebuilt for nothing.
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        for source in self.specs.iter().chain(&self.presets) {
+            if let Source::File(file) = source {
+                println!("cargo:rerun-if-changed={}", file.display());
+            }
+        }
+        let source = self.generate()?;
+        let path = path.as_ref();
+        if std::fs::read_to_string(path).is_ok_and(|existing| existing == source) {
+            return Ok(());
+        }
+        std::fs::write(path, source).map_err(|e| Error::Io(path.to_path_buf(), e))
+    }
+}
+
+// the definitions of all documents, later ones replacing earlier ones of the same name
+#[derive(Default)]
+struct Definitions {
+    custom_types: Vec<(String, String)>,
+    constants: BTreeMap<String, String>,
+    presets: BTreeMap<String, String>,
+    containers: Vec<Container>,
+}
+
+impl Definitions {
+    fn add(&mut self, document: markdown::Document) {
+        for (name, ty) in document.custom_types {
+            match self
+                .custom_types
+                .iter_mut()
+                .find(|(other, _)| *other == name)
+            {
+                Some(existing) => existing.1 = ty,
+                None => self.custom_types.push((name, ty)),
+            }
+        }
+        self.constants.extend(document.constants);
+        for container in document.containers {
+            match self
+                .containers
+                .iter_mut()
+                .find(|other| other.name == container.name)
+            {
+                Some(existing) => *existing = container,
+                None => self.containers.push(container),
+            }
+        }
+    }
+
+    fn custom_type(&self, name: &str) -> Option<&str> {
+        self.custom_types
+            .iter()
+            .find(|(other, _)| other == name)
+            .map(|(_, ty)| ty.as_str())
+    }
+
+    // the value of a constant, presets first, following the constants it refers to
+    fn constant(&self, name: &str, depth: usize) -> Result<u128, Error> {
+        if depth > 16 {
+            return Err(Error::InvalidExpression(name.to_string()));
+        }
+        let expr = self
+            .presets
+            .get(name)
+            .or_else(|| self.constants.get(name))
+            .ok_or_else(|| Error::UnknownConstant(name.to_string()))?;
+        expr::eval(expr, &mut |name| self.constant(name, depth + 1))
+    }
+
+    fn eval(&self, expr: &str) -> Result<u128, Error> {
+        expr::eval(expr, &mut |name| self.constant(name, 0))
+    }
+}
+
+// `List[T, N]` is `("List", ["T", "N"])`
+fn split_type(ty: &str) -> Result<(&str, Vec<&str>), Error> {
+    let ty = ty.trim();
+    let Some((name, rest)) = ty.split_once('[') else {
+        return Ok((ty, vec![]));
+    };
+    let inner = rest
+        .strip_suffix(']')
+        .ok_or_else(|| Error::UnknownType(ty.to_string()))?;
+    let mut args = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in inner.char_indices() {
+        match c {
+            '[' | '(' => depth += 1,
+            ']' | ')' => depth -= 1,
+            ',' if depth == 0 => {
+                args.push(inner[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    args.push(inner[start..].trim());
+    Ok((name.trim(), args))
+}
+
+// the named typenum constant where there is one: every number up to 1024 and the powers of two,
+// otherwise the number spelled out in binary
+fn typenum(n: u128) -> Result<String, Error> {
+    if n > u64::MAX as u128 {
+        return Err(Error::InvalidExpression(n.to_string()));
+    }
+    if n <= 1024 || n.is_power_of_two() {
+        return Ok(format!("typenum::U{}", n));
+    }
+    let mut ty = "typenum::UTerm".to_string();
+    for bit in (0..128 - n.leading_zeros()).rev() {
+        ty = format!("typenum::UInt<{}, typenum::B{}>", ty, (n >> bit) & 1);
+    }
+    Ok(ty)
+}
+
+const KEYWORDS: [&str; 12] = [
+    "as", "fn", "impl", "in", "loop", "match", "mod", "move", "ref", "self", "type", "use",
+];
+
+fn field_name(name: &str) -> String {
+    if KEYWORDS.contains(&name) {
+        format!("r#{}", name)
+    } else {
+        name.to_string()
+    }
+}
+
+struct Emitter<'a> {
+    generator: &'a Generator,
+    definitions: &'a Definitions,
+}
+
+impl Emitter<'_> {
+    fn length(&self, expr: &str) -> Result<String, Error> {
+        typenum(self.definitions.eval(expr)?)
+    }
+
+    // the Rust type of a spec type, collecting the custom types it refers to
+    fn rust_type(&self, ty: &str, aliases: &mut BTreeSet<String>) -> Result<String, Error> {
+        let (name, args) = split_type(ty)?;
+        if !args.is_empty() {
+            return match (name, args.len()) {
+                ("List" | "Vector", 2) => {
+                    let path = if name == "List" {
+                        &self.generator.list_type
+                    } else {
+                        &self.generator.vector_type
+                    };
+                    Ok(format!(
+                        "{}<{}, {}>",
+                        path,
+                        self.rust_type(args[0], aliases)?,
+                        self.length(args[1])?
+                    ))
+                }
+                // the same type as `BytesN`
+                ("ByteVector", 1) => {
+                    let bytes = format!("Bytes{}", self.definitions.eval(args[0])?);
+                    self.rust_type(&bytes, aliases)
+                }
+                ("ByteList", 1) => Ok(format!("sszb::ByteList<{}>", self.length(args[0])?)),
+                ("Bitvector", 1) => Ok(format!("sszb::Bitvector<{}>", self.length(args[0])?)),
+                ("Bitlist", 1) => Ok(format!("sszb::Bitlist<{}>", self.length(args[0])?)),
+                _ => Err(Error::UnsupportedType(ty.to_string())),
+            };
+        }
+
+        if self.definitions.custom_type(name).is_some() {
+            aliases.insert(name.to_string());
+            return Ok(name.to_string());
+        }
+        if let Some(rust_type) = self.generator.types.get(name) {
+            return Ok(rust_type.clone());
+        }
+        if self.definitions.containers.iter().any(|c| c.name == name) {
+            return Ok(name.to_string());
+        }
+        let basic = match name {
+            "boolean" | "bit" => "bool",
+            "uint8" | "byte" => "u8",
+            "uint16" => "u16",
+            "uint32" => "u32",
+            "uint64" => "u64",
+            "uint128" => "u128",
+            "uint256" => "alloy_primitives::U256",
+            _ => match name.strip_prefix("Bytes").map(str::parse::<usize>) {
+                Some(Ok(len)) => return Ok(format!("alloy_primitives::FixedBytes<{}>", len)),
+                _ => return Err(Error::UnknownType(ty.to_string())),
+            },
+        };
+        Ok(basic.to_string())
+    }
+
+    fn emit(&self) -> Result<String, Error> {
+        let mut aliases = BTreeSet::new();
+        let mut containers = String::new();
+        for container in &self.definitions.containers {
+            containers.push_str(&format!(
+                "\n#[derive({})]\npub struct {} {{\n",
+                self.generator.derives.join(", "),
+                container.name
+            ));
+            for (name, ty) in &container.fields {
+                let rust_type =
+                    self.rust_type(ty, &mut aliases)
+                        .map_err(|error| Error::InField {
+                            path: format!("{}.{}", container.name, name),
+                            error: Box::new(error),
+                        })?;
+                containers.push_str(&format!("    pub {}: {},\n", field_name(name), rust_type));
+            }
+            containers.push_str("}\n");
+        }
+
+        // the custom types the containers use, and the ones those refer to in turn
+        let mut resolved = BTreeMap::new();
+        while let Some(name) = aliases.iter().find(|name| !resolved.contains_key(*name)) {
+            let name = name.clone();
+            let rust_type = match self.generator.types.get(&name) {
+                Some(rust_type) => rust_type.clone(),
+                None => {
+                    let ty = self.definitions.custom_type(&name).unwrap_or_default();
+                    self.rust_type(ty, &mut aliases)
+                        .map_err(|error| Error::InField {
+                            path: name.clone(),
+                            error: Box::new(error),
+                        })?
+                }
+            };
+            resolved.insert(name, rust_type);
+        }
+
+        let mut source = String::from(
+            "// @generated by sszb_codegen from the consensus specs, do not edit\n\
+             use bytes::buf::{Buf, BufMut};\n\
+             use sszb_derive::{SszbDecode, SszbEncode};\n",
+        );
+        if !resolved.is_empty() {
+            source.push('\n');
+        }
+        for (name, _) in &self.definitions.custom_types {
+            if let Some(rust_type) = resolved.get(name) {
+                source.push_str(&format!("pub type {} = {};\n", name, rust_type));
+            }
+        }
+        source.push_str(&containers);
+        Ok(source)
+    }
+}
@@ -0,0 +1,190 @@
+//! Extracts the SSZ definitions from a consensus spec markdown document.
+
+/// A `class Name(Container):` definition, with its fields and their spec types in order.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub(crate) struct Container {
+    pub name: String,
+    pub fields: Vec<(String, String)>,
+}
+
+/// The definitions of one document.
+#[derive(Default, Debug)]
+pub(crate) struct Document {
+    /// The rows of the custom types tables: the type and its SSZ equivalent.
+    pub custom_types: Vec<(String, String)>,
+    /// The rows of the constants, preset and configuration tables: the name and the expression of
+    /// its value, without the `(= ...)` note the spec adds to computed values.
+    pub constants: Vec<(String, String)>,
+    pub containers: Vec<Container>,
+}
+
+// a table cell without surrounding whitespace and backticks
+fn cell(cell: &str) -> String {
+    cell.trim().trim_matches('`').trim().to_string()
+}
+
+fn table_cells(line: &str) -> Option<Vec<String>> {
+    let line = line.trim();
+    let inner = line.strip_prefix('|')?.strip_suffix('|')?;
+    Some(inner.split('|').map(cell).collect())
+}
+
+fn is_separator(cells: &[String]) -> bool {
+    cells
+        .iter()
+        .all(|cell| !cell.is_empty() && cell.chars().all(|c| matches!(c, '-' | ':' | ' ')))
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+// `uint64(2**13) (= 8,192)` is `uint64(2**13)`
+fn strip_note(value: &str) -> String {
+    match value.find("(=") {
+        Some(i) => value[..i].trim().trim_matches('`').trim().to_string(),
+        None => value.to_string(),
+    }
+}
+
+// the kind of table being read, from its header
+#[derive(Clone, Copy, PartialEq)]
+enum Table {
+    CustomTypes,
+    Constants,
+    Other,
+}
+
+fn table_kind(header: &[String]) -> Table {
+    match (
+        header.first().map(String::as_str),
+        header.get(1).map(String::as_str),
+    ) {
+        (Some("Name"), Some("SSZ equivalent")) => Table::CustomTypes,
+        (Some("Name"), Some("Value")) => Table::Constants,
+        _ => Table::Other,
+    }
+}
+
+// `class Name(Container):`
+fn class_name(line: &str) -> Option<&str> {
+    let rest = line.strip_prefix("class ")?;
+    let (name, rest) = rest.split_once('(')?;
+    (rest.trim_end() == "Container):" && is_identifier(name)).then_some(name)
+}
+
+/// Reads the custom types and constants tables and the containers of the python code blocks of
+/// a spec document, everything else is skipped.
+pub(crate) fn parse(markdown: &str) -> Document {
+    let mut document = Document::default();
+    let mut lines = markdown.lines();
+    // the kind of the current table once its header and separator are read
+    let mut table = None;
+    let mut header: Option<Vec<String>> = None;
+
+    while let Some(line) = lines.next() {
+        if let Some(cells) = table_cells(line) {
+            match (&header, table) {
+                (None, _) => header = Some(cells),
+                (Some(header_cells), None) if is_separator(&cells) => {
+                    table = Some(table_kind(header_cells))
+                }
+                (Some(_), None) => header = Some(cells),
+                (Some(_), Some(kind)) => {
+                    let (Some(name), Some(value)) = (cells.first(), cells.get(1)) else {
+                        continue;
+                    };
+                    if !is_identifier(name) {
+                        continue;
+                    }
+                    match kind {
+                        Table::CustomTypes => {
+                            document.custom_types.push((name.clone(), value.clone()))
+                        }
+                        Table::Constants => {
+                            document.constants.push((name.clone(), strip_note(value)))
+                        }
+                        Table::Other => {}
+                    }
+                }
+            }
+            continue;
+        }
+        table = None;
+        header = None;
+
+        if line.trim_start().starts_with("```python") {
+            parse_code_block(&mut lines, &mut document.containers);
+        }
+    }
+    document
+}
+
+// reads the containers of a python code block, up to its closing fence
+fn parse_code_block<'a>(
+    lines: &mut impl Iterator<Item = &'a str>,
+    containers: &mut Vec<Container>,
+) {
+    let mut container: Option<Container> = None;
+    let mut in_docstring = false;
+    for line in lines.by_ref() {
+        if line.trim_start().starts_with("```") {
+            break;
+        }
+        let code = line.split('#').next().unwrap_or_default();
+        if code.trim().is_empty() {
+            continue;
+        }
+
+        // a line without indentation ends the current class
+        if !code.starts_with(char::is_whitespace) {
+            containers.extend(container.take());
+            if let Some(name) = class_name(code) {
+                container = Some(Container {
+                    name: name.to_string(),
+                    fields: vec![],
+                });
+            }
+            continue;
+        }
+
+        let Some(current) = container.as_mut() else {
+            continue;
+        };
+        let code = code.trim();
+        if code.starts_with("\"\"\"") {
+            // a docstring on one line opens and closes it
+            in_docstring = !(in_docstring || code.len() > 3 && code.ends_with("\"\"\""));
+            continue;
+        }
+        if in_docstring {
+            in_docstring = !code.ends_with("\"\"\"");
+            continue;
+        }
+        if let Some((name, ty)) = code.split_once(':') {
+            if is_identifier(name.trim()) {
+                current
+                    .fields
+                    .push((name.trim().to_string(), ty.trim().to_string()));
+            }
+        }
+    }
+    containers.extend(container);
+}
+
+/// Reads the `NAME: value` lines of a preset file, skipping comments.
+pub(crate) fn parse_preset(yaml: &str) -> Vec<(String, String)> {
+    yaml.lines()
+        .filter_map(|line| {
+            let line = line.split('#').next()?.trim();
+            let (name, value) = line.split_once(':')?;
+            let value = value.trim().trim_matches(|c| c == '\'' || c == '"');
+            (is_identifier(name.trim()) && !value.is_empty())
+                .then(|| (name.trim().to_string(), value.to_string()))
+        })
+        .collect()
+}
@@ -0,0 +1,249 @@
+use sszb::{SszDecode, SszEncode};
+use sszb_codegen::{Error, Generator};
+
+const SPEC: &str = "tests/specs/beacon-chain.md";
+
+// the checked in output of the generator, which has to compile
+#[allow(dead_code)]
+mod mainnet {
+    include!("specs/phase0_mainnet.rs");
+}
+
+#[allow(dead_code)]
+mod minimal {
+    include!("specs/phase0_minimal.rs");
+}
+
+fn phase0(preset: &str) -> Generator {
+    let mut generator = Generator::new();
+    generator
+        .spec_file(SPEC)
+        .preset_file(format!("tests/specs/{}.yaml", preset))
+        .map_type("CommitteeIndex", "u64");
+    generator
+}
+
+#[test]
+fn test_generated_sources() {
+    for preset in ["mainnet", "minimal"] {
+        let generated = phase0(preset).generate().unwrap();
+        let path = format!("tests/specs/phase0_{}.rs", preset);
+        let expected = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(generated, expected, "{} is out of date", path);
+    }
+}
+
+#[test]
+fn test_presets() {
+    let mainnet = phase0("mainnet").generate().unwrap();
+    let minimal = phase0("minimal").generate().unwrap();
+    for source in [&mainnet, &minimal] {
+        // constants of the spec tables, with their `(= ...)` notes dropped
+        assert!(source.contains(
+            "pub proof: ssz_types::FixedVector<alloy_primitives::FixedBytes<32>, typenum::U33>"
+        ));
+        assert!(source.contains("pub justification_bits: sszb::Bitvector<typenum::U4>"));
+        assert!(source.contains("ssz_types::VariableList<Validator, typenum::U1099511627776>"));
+    }
+    assert!(mainnet.contains("pub block_roots: ssz_types::FixedVector<Root, typenum::U8192>"));
+    assert!(minimal.contains("pub block_roots: ssz_types::FixedVector<Root, typenum::U64>"));
+    // MAX_ATTESTATIONS * SLOTS_PER_EPOCH
+    assert!(mainnet.contains("ssz_types::VariableList<PendingAttestation, typenum::U4096>"));
+    assert!(minimal.contains("ssz_types::VariableList<PendingAttestation, typenum::U1024>"));
+
+    // the spec values apply without a preset
+    let spec = Generator::new()
+        .spec_file(SPEC)
+        .map_type("CommitteeIndex", "u64")
+        .generate()
+        .unwrap();
+    assert!(spec.contains("pub block_roots: ssz_types::FixedVector<Root, typenum::U8192>"));
+}
+
+#[test]
+fn test_types() {
+    let source = Generator::new()
+        .spec(
+            "| Name | SSZ equivalent | Description |\n\
+             | - | - | - |\n\
+             | `Hash32` | `Bytes32` | a hash |\n\
+             | `Transaction` | `ByteList[MAX_BYTES_PER_TRANSACTION]` | a transaction |\n\
+             | `Unused` | `uint32` | not referred to |\n\
+             \n\
+             | Name | Value |\n\
+             | - | - |\n\
+             | `MAX_BYTES_PER_TRANSACTION` | `uint64(2**30)` (= 1,073,741,824) |\n\
+             | `ODD_LIMIT` | `3000` |\n\
+             \n\
+             ```python\n\
+             class Payload(Container):\n    \
+                 \"\"\"\n    \
+                 A docstring: with a colon.\n    \
+                 \"\"\"\n    \
+                 block_hash: Hash32\n    \
+                 transactions: List[Transaction, 2**20]\n    \
+                 extra: ByteVector[20]\n    \
+                 bits: Bitlist[ODD_LIMIT]\n    \
+                 large: List[uint256, ODD_LIMIT * 1000 + 1]\n    \
+                 type: boolean\n\
+             ```\n",
+        )
+        .list_type("milhouse::List")
+        .derive("Default")
+        .generate()
+        .unwrap();
+    let expected = "\
+// @generated by sszb_codegen from the consensus specs, do not edit
+use bytes::buf::{Buf, BufMut};
+use sszb_derive::{SszbDecode, SszbEncode};
+
+pub type Hash32 = alloy_primitives::FixedBytes<32>;
+pub type Transaction = sszb::ByteList<typenum::U1073741824>;
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode, Default)]
+pub struct Payload {
+    pub block_hash: Hash32,
+    pub transactions: milhouse::List<Transaction, typenum::U1048576>,
+    pub extra: alloy_primitives::FixedBytes<20>,
+    pub bits: sszb::Bitlist<typenum::UInt<typenum::UInt<typenum::UInt<typenum::UInt<typenum::UInt<typenum::UInt<typenum::UInt<typenum::UInt<typenum::UInt<typenum::UInt<typenum::UInt<typenum::UInt<typenum::UTerm, typenum::B1>, typenum::B0>, typenum::B1>, typenum::B1>, typenum::B1>, typenum::B0>, typenum::B1>, typenum::B1>, typenum::B1>, typenum::B0>, typenum::B0>, typenum::B0>>,
+    pub large: milhouse::List<alloy_primitives::U256, typenum::UInt<typenum::UInt<typenum::UInt<typenum::UInt<typenum::UInt<typenum::UInt<typenum::UInt<typenum::UInt<typenum::UInt<typenum::UInt<typenum::UInt<typenum::UInt<typenum::UInt<typenum::UInt<typenum::UInt<typenum::UInt<typenum::UInt<typenum::UInt<typenum::UInt<typenum::UInt<typenum::UInt<typenum::UInt<typenum::UTerm, typenum::B1>, typenum::B0>, typenum::B1>, typenum::B1>, typenum::B0>, typenum::B1>, typenum::B1>, typenum::B1>, typenum::B0>, typenum::B0>, typenum::B0>, typenum::B1>, typenum::B1>, typenum::B0>, typenum::B1>, typenum::B1>, typenum::B0>, typenum::B0>, typenum::B0>, typenum::B0>, typenum::B0>, typenum::B1>>,
+    pub r#type: bool,
+}
+";
+    assert_eq!(source, expected);
+
+    // ByteVector[N] is mapped as BytesN
+    let source = Generator::new()
+        .spec("```python\nclass A(Container):\n    a: ByteVector[4 * 5]\n    b: Bytes20\n```\n")
+        .map_type("Bytes20", "alloy_primitives::Address")
+        .generate()
+        .unwrap();
+    assert!(source.contains(
+        "    pub a: alloy_primitives::Address,\n    pub b: alloy_primitives::Address,\n"
+    ));
+}
+
+#[test]
+fn test_fork_overrides() {
+    let source = Generator::new()
+        .spec(
+            "| Name | Value |\n| - | - |\n| `LIMIT` | `4` |\n\n\
+             ```python\n\
+             class Header(Container):\n    slot: uint64\n\n\
+             class Block(Container):\n    header: Header\n    roots: List[Bytes32, LIMIT]\n\
+             ```\n",
+        )
+        .spec(
+            "| Name | Value |\n| - | - |\n| `LIMIT` | `8` |\n\n\
+             ```python\n\
+             class Block(Container):\n    header: Header\n    roots: List[Bytes32, LIMIT]\n    \
+                 # [New in Fork]\n    extra: uint8\n\
+             ```\n",
+        )
+        .generate()
+        .unwrap();
+    // Block keeps its place after Header, with the fields and constants of the later document
+    assert!(source.contains(
+        "pub struct Block {\n    \
+             pub header: Header,\n    \
+             pub roots: ssz_types::VariableList<alloy_primitives::FixedBytes<32>, typenum::U8>,\n    \
+             pub extra: u8,\n}"
+    ));
+    assert!(source.find("pub struct Header").unwrap() < source.find("pub struct Block").unwrap());
+    assert_eq!(source.matches("pub struct Block").count(), 1);
+}
+
+#[test]
+fn test_errors() {
+    let error = Generator::new().spec_file(SPEC).generate().unwrap_err();
+    assert!(matches!(
+        &error,
+        Error::InField { path, error } if path == "AttestationData.index"
+            && matches!(**error, Error::UnknownType(ref ty) if ty == "CommitteeIndex")
+    ));
+    assert_eq!(
+        error.to_string(),
+        "AttestationData.index: unknown type CommitteeIndex"
+    );
+
+    let error = Generator::new()
+        .spec("```python\nclass A(Container):\n    a: List[uint8, MISSING]\n```\n")
+        .generate()
+        .unwrap_err();
+    assert_eq!(error.to_string(), "A.a: unknown constant MISSING");
+
+    let error = Generator::new()
+        .spec("```python\nclass A(Container):\n    a: Union[None, uint8]\n```\n")
+        .generate()
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "A.a: unsupported type Union[None, uint8]"
+    );
+
+    let error = Generator::new()
+        .spec("```python\nclass A(Container):\n    a: List[uint8, 2**64 - ]\n```\n")
+        .generate()
+        .unwrap_err();
+    assert_eq!(error.to_string(), "A.a: invalid expression 2**64 -");
+
+    let error = Generator::new()
+        .spec_file("tests/specs/missing.md")
+        .generate()
+        .unwrap_err();
+    assert!(matches!(error, Error::Io(..)));
+}
+
+#[test]
+fn test_generated_containers() {
+    use mainnet::*;
+
+    let checkpoint = Checkpoint {
+        epoch: 3,
+        root: Root::repeat_byte(0xaa),
+    };
+    let data = AttestationData {
+        slot: 100,
+        index: 2,
+        beacon_block_root: Root::repeat_byte(0xbb),
+        source: checkpoint.clone(),
+        target: Checkpoint {
+            epoch: 4,
+            ..checkpoint.clone()
+        },
+    };
+    let mut bits = sszb::Bitlist::with_capacity(10).unwrap();
+    bits.set(3, true).unwrap();
+    let attestation = PendingAttestation {
+        aggregation_bits: bits,
+        data: data.clone(),
+        inclusion_delay: 1,
+        proposer_index: 7,
+    };
+    let bytes = attestation.to_ssz();
+    assert_eq!(
+        PendingAttestation::from_ssz_bytes(&bytes).unwrap(),
+        attestation
+    );
+
+    let indexed = IndexedAttestation {
+        attesting_indices: vec![1, 5, 9].into(),
+        data,
+        signature: BLSSignature::repeat_byte(0xcc),
+    };
+    let bytes = indexed.to_ssz();
+    assert_eq!(bytes.len(), 4 + 128 + 96 + 3 * 8);
+    assert_eq!(IndexedAttestation::from_ssz_bytes(&bytes).unwrap(), indexed);
+
+    // the minimal preset has shorter vectors
+    let batch = minimal::HistoricalBatch {
+        block_roots: vec![minimal::Root::ZERO; 64].into(),
+        state_roots: vec![minimal::Root::ZERO; 64].into(),
+    };
+    let bytes = batch.to_ssz();
+    assert_eq!(bytes.len(), 2 * 64 * 32);
+    assert_eq!(
+        minimal::HistoricalBatch::from_ssz_bytes(&bytes).unwrap(),
+        batch
+    );
+}
@@ -0,0 +1,95 @@
+// @generated by sszb_codegen from the consensus specs, do not edit
+use bytes::buf::{Buf, BufMut};
+use sszb_derive::{SszbDecode, SszbEncode};
+
+pub type Slot = u64;
+pub type Epoch = u64;
+pub type ValidatorIndex = u64;
+pub type Gwei = u64;
+pub type Root = alloy_primitives::FixedBytes<32>;
+pub type Version = alloy_primitives::FixedBytes<4>;
+pub type BLSPubkey = alloy_primitives::FixedBytes<48>;
+pub type BLSSignature = alloy_primitives::FixedBytes<96>;
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode)]
+pub struct Fork {
+    pub previous_version: Version,
+    pub current_version: Version,
+    pub epoch: Epoch,
+}
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode)]
+pub struct Checkpoint {
+    pub epoch: Epoch,
+    pub root: Root,
+}
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode)]
+pub struct Validator {
+    pub pubkey: BLSPubkey,
+    pub withdrawal_credentials: alloy_primitives::FixedBytes<32>,
+    pub effective_balance: Gwei,
+    pub slashed: bool,
+    pub activation_eligibility_epoch: Epoch,
+    pub activation_epoch: Epoch,
+    pub exit_epoch: Epoch,
+    pub withdrawable_epoch: Epoch,
+}
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode)]
+pub struct AttestationData {
+    pub slot: Slot,
+    pub index: u64,
+    pub beacon_block_root: Root,
+    pub source: Checkpoint,
+    pub target: Checkpoint,
+}
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode)]
+pub struct IndexedAttestation {
+    pub attesting_indices: ssz_types::VariableList<ValidatorIndex, typenum::U2048>,
+    pub data: AttestationData,
+    pub signature: BLSSignature,
+}
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode)]
+pub struct PendingAttestation {
+    pub aggregation_bits: sszb::Bitlist<typenum::U2048>,
+    pub data: AttestationData,
+    pub inclusion_delay: Slot,
+    pub proposer_index: ValidatorIndex,
+}
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode)]
+pub struct HistoricalBatch {
+    pub block_roots: ssz_types::FixedVector<Root, typenum::U8192>,
+    pub state_roots: ssz_types::FixedVector<Root, typenum::U8192>,
+}
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode)]
+pub struct Deposit {
+    pub proof: ssz_types::FixedVector<alloy_primitives::FixedBytes<32>, typenum::U33>,
+    pub data: DepositData,
+}
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode)]
+pub struct DepositData {
+    pub pubkey: BLSPubkey,
+    pub withdrawal_credentials: alloy_primitives::FixedBytes<32>,
+    pub amount: Gwei,
+    pub signature: BLSSignature,
+}
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode)]
+pub struct BeaconState {
+    pub genesis_time: u64,
+    pub slot: Slot,
+    pub fork: Fork,
+    pub block_roots: ssz_types::FixedVector<Root, typenum::U8192>,
+    pub historical_roots: ssz_types::VariableList<Root, typenum::U16777216>,
+    pub validators: ssz_types::VariableList<Validator, typenum::U1099511627776>,
+    pub balances: ssz_types::VariableList<Gwei, typenum::U1099511627776>,
+    pub previous_epoch_attestations: ssz_types::VariableList<PendingAttestation, typenum::U4096>,
+    pub justification_bits: sszb::Bitvector<typenum::U4>,
+    pub finalized_checkpoint: Checkpoint,
+}
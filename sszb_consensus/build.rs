@@ -0,0 +1,71 @@
+//! Generates the containers of every fork and preset from the spec documents under `specs` and
+//! the preset files under `presets`.
+use std::path::PathBuf;
+
+use sszb_codegen::Generator;
+
+// the forks in order, with the documents defining their containers
+const FORKS: [(&str, &[&str]); 7] = [
+    ("phase0", &["beacon-chain", "validator"]),
+    ("altair", &["beacon-chain", "validator"]),
+    ("bellatrix", &["beacon-chain"]),
+    ("capella", &["beacon-chain"]),
+    (
+        "deneb",
+        &["polynomial-commitments", "beacon-chain", "p2p-interface"],
+    ),
+    ("electra", &["beacon-chain", "validator"]),
+    (
+        "fulu",
+        &[
+            "polynomial-commitments-sampling",
+            "das-core",
+            "p2p-interface",
+            "beacon-chain",
+        ],
+    ),
+];
+
+const PRESETS: [&str; 2] = ["mainnet", "minimal"];
+
+// the byte vectors of the specs as the types sszb encodes and hashes them with
+const TYPES: [(&str, &str); 8] = [
+    ("Bytes4", "ssz_types::FixedVector<u8, typenum::U4>"),
+    ("Bytes20", "alloy_primitives::Address"),
+    ("Bytes32", "alloy_primitives::B256"),
+    ("Bytes48", "sszb::PKBytes"),
+    ("Bytes96", "sszb::Sig"),
+    ("Bytes256", "ssz_types::FixedVector<u8, typenum::U256>"),
+    ("Cell", "ssz_types::FixedVector<u8, typenum::U2048>"),
+    ("Blob", "ssz_types::FixedVector<u8, typenum::U131072>"),
+];
+
+fn main() {
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    for preset in PRESETS {
+        let mut generator = Generator::new();
+        generator
+            .derive("tree_hash_derive::TreeHash")
+            .derive("sszb_derive::SszbSchema");
+        // cargo sets these for the enabled features of the crate
+        if std::env::var_os("CARGO_FEATURE_SERDE").is_some() {
+            generator.derive("sszb_derive::SszbSerde");
+        }
+        if std::env::var_os("CARGO_FEATURE_ARBITRARY").is_some() {
+            generator.derive("sszb_derive::SszbArbitrary");
+        }
+        for (spec_type, rust_type) in TYPES {
+            generator.map_type(spec_type, rust_type);
+        }
+        // each fork is generated from its documents and those of the forks before it
+        for (fork, documents) in FORKS {
+            for document in documents {
+                generator.spec_file(format!("specs/{}/{}.md", fork, document));
+            }
+            generator.preset_file(format!("presets/{}/{}.yaml", preset, fork));
+            if let Err(e) = generator.write(out_dir.join(format!("{}_{}.rs", preset, fork))) {
+                panic!("generating {} {}: {}", preset, fork, e);
+            }
+        }
+    }
+}
@@ -0,0 +1,42 @@
+//! The consensus containers of every fork from phase0 to fulu, for the mainnet and minimal
+//! presets, encoded and decoded with sszb.
+//!
+//! The containers are generated by `sszb_codegen` from the spec documents under `specs` and the
+//! preset files under `presets`, so each fork module holds every container of the fork, the
+//! unchanged ones included, and the custom types (`Slot`, `Root`...) they use:
+//!
+//! ```ignore
+//! use sszb::{SszDecode, SszEncode};
+//! use sszb_consensus::mainnet::deneb::SignedBeaconBlock;
+//! use tree_hash::TreeHash;
+//!
+//! let block = SignedBeaconBlock::from_ssz_bytes(&bytes)?;
+//! assert_eq!(block.to_ssz(), bytes);
+//! let root = block.message.tree_hash_root();
+//! ```
+//!
+//! Besides `SszbEncode` and `SszbDecode`, the containers derive `TreeHash` and `SszbSchema`, and
+//! with the default `serde` and `arbitrary` features `SszbSerde` (the YAML and JSON format of the
+//! spec tests) and `SszbArbitrary`. Byte vectors are `alloy_primitives::B256` and `Address`,
+//! `sszb::PKBytes` for public keys and KZG commitments and proofs, and `sszb::Sig` for signatures.
+
+macro_rules! forks {
+    ($preset: literal, $($fork: ident),*) => {
+        $(
+            #[doc = concat!("The ", stringify!($fork), " containers of the ", $preset, " preset.")]
+            pub mod $fork {
+                include!(concat!(env!("OUT_DIR"), "/", $preset, "_", stringify!($fork), ".rs"));
+            }
+        )*
+    };
+}
+
+/// The containers with the lengths of the mainnet preset.
+pub mod mainnet {
+    forks!("mainnet", phase0, altair, bellatrix, capella, deneb, electra, fulu);
+}
+
+/// The containers with the lengths of the minimal preset, used by the spec tests.
+pub mod minimal {
+    forks!("minimal", phase0, altair, bellatrix, capella, deneb, electra, fulu);
+}
@@ -0,0 +1,14 @@
+//! Round trips arbitrary values of a few of the bigger containers. This checks that they decode what
+//! they encode, not that they match the consensus specs, which only the sszb_cli spec tests of the
+//! mainnet containers do.
+use sszb::arbitrary::assert_roundtrip;
+
+#[test]
+fn test_arbitrary_roundtrip() {
+    use sszb_consensus::minimal::{electra, fulu};
+
+    assert_roundtrip::<electra::Attestation>();
+    assert_roundtrip::<electra::ExecutionRequests>();
+    assert_roundtrip::<electra::SignedBeaconBlock>();
+    assert_roundtrip::<fulu::DataColumnSidecar>();
+}
@@ -0,0 +1,154 @@
+//! Decodes a `SignedBeaconBlock` and a `BeaconState` of every fork and preset from the snapshots
+//! under `tests/snapshots`, re-encodes them and compares their hash tree roots with `roots.yaml`.
+//!
+//! The snapshots are arbitrary values built from a fixed input, serialized by sszb and hashed by
+//! tree_hash, so they say nothing about whether the containers match the consensus specs: a
+//! container that diverged from the spec when they were taken passes all the same. They only catch
+//! changes to the encoding or roots of the generated containers, and
+//! `cargo test -p sszb_consensus --test snapshots -- --ignored` takes them again.
+use serde::Deserialize;
+use sszb::{SszDecode, SszEncode};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tree_hash::TreeHash;
+
+#[derive(Deserialize)]
+struct Roots {
+    root: String,
+}
+
+fn case_dir(preset: &str, fork: &str, name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(preset)
+        .join(fork)
+        .join(name)
+}
+
+fn check_case<T: SszEncode + SszDecode + TreeHash>(case: &Path) -> Result<(), String> {
+    let compressed = fs::read(case.join("serialized.ssz_snappy")).map_err(|e| e.to_string())?;
+    let bytes = snap::raw::Decoder::new()
+        .decompress_vec(&compressed)
+        .map_err(|e| format!("snappy: {}", e))?;
+    let decoded = T::from_ssz_bytes(&bytes).map_err(|e| format!("{:?}", e))?;
+    if decoded.to_ssz() != bytes {
+        return Err("re-encoding differs from serialized.ssz_snappy".to_string());
+    }
+    let yaml = fs::read_to_string(case.join("roots.yaml")).map_err(|e| e.to_string())?;
+    let expected = serde_yaml::from_str::<Roots>(&yaml)
+        .map_err(|e| e.to_string())?
+        .root;
+    let root = format!("{:#x}", decoded.tree_hash_root());
+    if root != expected {
+        return Err(format!("root {} != {}", root, expected));
+    }
+    Ok(())
+}
+
+type Case = fn(&str, &str, &str, u64) -> Result<(), String>;
+
+// calls `$f::<Type>(preset, fork, name, seed)` for both containers of every fork of the preset
+macro_rules! for_each_case {
+    ($f: ident, $preset: ident, $seed: expr) => {
+        for_each_case!(
+            $f, $preset, $seed; phase0, altair, bellatrix, capella, deneb, electra, fulu
+        )
+    };
+    ($f: ident, $preset: ident, $seed: expr; $($fork: ident),*) => {{
+        let mut seed = $seed;
+        let mut results = vec![];
+        $(
+            use sszb_consensus::$preset::$fork;
+            let cases: [(&str, Case); 2] = [
+                ("SignedBeaconBlock", $f::<$fork::SignedBeaconBlock>),
+                ("BeaconState", $f::<$fork::BeaconState>),
+            ];
+            for (name, case) in cases {
+                seed += 1;
+                results.push(case(stringify!($preset), stringify!($fork), name, seed));
+            }
+        )*
+        results
+    }};
+}
+
+fn check<T: SszEncode + SszDecode + TreeHash>(
+    preset: &str,
+    fork: &str,
+    name: &str,
+    _seed: u64,
+) -> Result<(), String> {
+    let case = case_dir(preset, fork, name);
+    check_case::<T>(&case).map_err(|e| format!("{}: {}", case.display(), e))
+}
+
+#[test]
+fn test_snapshots() {
+    let failures = for_each_case!(check, mainnet, 0)
+        .into_iter()
+        .chain(for_each_case!(check, minimal, 100))
+        .filter_map(Result::err)
+        .collect::<Vec<_>>();
+    assert!(failures.is_empty(), "failures:\n{}", failures.join("\n"));
+}
+
+// a fixed pseudo-random input for each case
+#[cfg(feature = "arbitrary")]
+fn input(seed: u64) -> Vec<u8> {
+    let mut x = seed.wrapping_mul(0x9e3779b97f4a7c15) | 1;
+    (0..3000)
+        .map(|_| {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            x as u8
+        })
+        .collect()
+}
+
+#[cfg(feature = "arbitrary")]
+fn generate<T>(preset: &str, fork: &str, name: &str, seed: u64) -> Result<(), String>
+where
+    T: sszb::SszArbitrary + SszEncode + SszDecode + sszb::SszReflect + TreeHash,
+{
+    use sszb::{arbitrary::Unstructured, SszValue};
+
+    let input = input(seed);
+    let value = T::ssz_arbitrary(&mut Unstructured::new(&input)).map_err(|e| e.to_string())?;
+    let bytes = value.to_ssz();
+    let root = value.tree_hash_root();
+    let schema = T::ssz_schema();
+    let dynamic_root = SszValue::from_ssz_bytes(&schema, &bytes)
+        .map_err(|e| format!("{:?}", e))?
+        .tree_hash_root(&schema)
+        .map_err(|e| e.to_string())?;
+    if dynamic_root != root {
+        return Err(format!(
+            "{} {} {}: the two roots differ",
+            preset, fork, name
+        ));
+    }
+    let case = case_dir(preset, fork, name);
+    fs::create_dir_all(&case).map_err(|e| e.to_string())?;
+    let compressed = snap::raw::Encoder::new()
+        .compress_vec(&bytes)
+        .map_err(|e| e.to_string())?;
+    fs::write(case.join("serialized.ssz_snappy"), compressed).map_err(|e| e.to_string())?;
+    fs::write(
+        case.join("roots.yaml"),
+        format!("{{root: '{:#x}'}}\n", root),
+    )
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(feature = "arbitrary")]
+#[test]
+#[ignore = "takes the snapshots again"]
+fn take_snapshots() {
+    let failures = for_each_case!(generate, mainnet, 0)
+        .into_iter()
+        .chain(for_each_case!(generate, minimal, 100))
+        .filter_map(Result::err)
+        .collect::<Vec<_>>();
+    assert!(failures.is_empty(), "failures:\n{}", failures.join("\n"));
+}
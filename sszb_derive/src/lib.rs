@@ -1,3 +1,4 @@
+use darling::util::{Override, PathList};
 use darling::FromMeta;
 use proc_macro::TokenStream;
 use quote::quote;
@@ -10,65 +11,328 @@ struct FieldOpts {
     skip_encode: bool,
     #[darling(default)]
     skip_decode: bool,
+    // shorthand for skip_encode and skip_decode
+    #[darling(default)]
+    skip: bool,
+    // the function that initializes a field skipped when decoding, instead of Default::default
+    #[darling(default)]
+    default: Option<Override<syn::Path>>,
+    // a function checking the decoded field, see ContainerOpts::validate
+    #[darling(default)]
+    validate: Option<syn::Path>,
+    // a module converting the field to and from the type it is encoded as
+    #[darling(default)]
+    with: Option<syn::Path>,
+    // maximum number of items for lists without a type-level bound, e.g. Vec<T>
+    #[darling(default)]
+    max: Option<syn::Expr>,
+    // the forks the field is part of, see #[derive(SszbForks)]
+    #[darling(default)]
+    forks: Option<PathList>,
+    // the field is another #[derive(SszbForks)] template, resolved to its container of each fork
+    #[darling(default)]
+    by_fork: bool,
 }
 
-fn parse_ssz_fields(
-    struct_data: &syn::DataStruct,
-) -> impl Iterator<Item = (&syn::Type, Option<&Ident>, Vec<FieldOpts>)> {
-    struct_data.fields.iter().map(|field| {
-        let ty = &field.ty;
-        let ident = field.ident.as_ref();
+/// Container-level configuration of #[derive(SszbDecode)].
+#[derive(Debug, Default, FromMeta)]
+struct ContainerOpts {
+    // a function checking the decoded container, `fn(&T) -> Result<(), E>` where E: ToString
+    #[darling(default)]
+    validate: Option<syn::Path>,
+}
 
-        // possible field options include skip_encode, skip_decode, skip_hash
-        let field_opts = field
-            .attrs
-            .iter()
-            .filter(|attr| {
-                attr.path()
-                    .get_ident()
-                    .map_or(false, |ident| *ident == "ssz")
-            })
-            .map(|attr| FieldOpts::from_meta(&attr.meta).unwrap())
-            .collect::<Vec<_>>();
+/// Container-level configuration of #[derive(SszbForks)], merged from all its #[ssz] attributes.
+#[derive(Debug, Default, FromMeta)]
+struct ForksOpts {
+    #[darling(default)]
+    forks: Option<PathList>,
+    #[darling(default)]
+    fork_name: Option<syn::Path>,
+    #[darling(default)]
+    enum_name: Option<Ident>,
+    #[darling(default)]
+    variant_derives: PathList,
+    // forwarded to the #[ssz] of every variant, see ContainerOpts
+    #[darling(default)]
+    validate: Option<syn::Path>,
+}
 
-        (ty, ident, field_opts)
-    })
+// the maximum length declared for a field with #[ssz(max = N)], if any
+fn field_max(field_opts: &[FieldOpts]) -> Option<&syn::Expr> {
+    field_opts.iter().find_map(|opt| opt.max.as_ref())
 }
 
-#[proc_macro_derive(SszbEncode)]
-pub fn derive_encode(input: TokenStream) -> TokenStream {
-    let derive_input = parse_macro_input!(input as DeriveInput);
-    let struct_data = match derive_input.data {
-        syn::Data::Struct(data) => data,
-        _ => panic!(), // TODO: fix
+// the type a field is encoded as: its own type, or the `Ssz` type of its #[ssz(with = "module")]
+fn field_ssz_type(ty: &syn::Type, field_opts: &[FieldOpts]) -> syn::Type {
+    match field_opts.iter().find_map(|opt| opt.with.as_ref()) {
+        Some(with) => syn::parse_quote! { #with::Ssz },
+        None => ty.clone(),
+    }
+}
+
+// the value of a field as its encoded type
+fn field_to_ssz(ident: &Ident, field_opts: &[FieldOpts]) -> proc_macro2::TokenStream {
+    match field_opts.iter().find_map(|opt| opt.with.as_ref()) {
+        Some(with) => quote! { (#with::to_ssz(&self.#ident)) },
+        None => quote! { self.#ident },
+    }
+}
+
+// converts a decoded value of the encoded type back to the field, returning early on failure
+fn field_from_ssz(
+    value: proc_macro2::TokenStream,
+    field_opts: &[FieldOpts],
+) -> proc_macro2::TokenStream {
+    match field_opts.iter().find_map(|opt| opt.with.as_ref()) {
+        Some(with) => quote! { #with::from_ssz(#value)? },
+        None => value,
+    }
+}
+
+// checks a decoded value with a #[ssz(validate = "path")] function, if there is one, failing
+// the decoding with the path of the value otherwise
+fn validate_stmt(
+    value: proc_macro2::TokenStream,
+    validate: Option<&syn::Path>,
+    path: &str,
+) -> proc_macro2::TokenStream {
+    match validate {
+        Some(validate) => quote! {
+            #validate(&#value).map_err(|e| sszb::DecodeError::ValidationFailed {
+                path: sszb::__private::ToString::to_string(#path),
+                reason: sszb::__private::ToString::to_string(&e),
+            })?;
+        },
+        None => quote! {},
+    }
+}
+
+fn skips_encode(field_opts: &[FieldOpts]) -> bool {
+    field_opts.iter().any(|opt| opt.skip || opt.skip_encode)
+}
+
+fn skips_decode(field_opts: &[FieldOpts]) -> bool {
+    field_opts.iter().any(|opt| opt.skip || opt.skip_decode)
+}
+
+// encoding and decoding share the layout of a container, so a field that is only encoded or
+// only decoded (`one_way`) is an error when the other direction is derived too. Decoded
+// containers are laid out by their decoded fields, encode-only ones by their encoded fields.
+fn check_one_way_skips(
+    fields: &[(&syn::Type, &Ident, Vec<FieldOpts>)],
+    one_way: fn(&[FieldOpts]) -> bool,
+    message: &str,
+) -> syn::Result<()> {
+    let mut errors = darling::Error::accumulator();
+    for (_, ident, field_opts) in fields {
+        if one_way(field_opts) {
+            errors.push(darling::Error::custom(message).with_span(*ident));
+        }
+    }
+    errors.finish()?;
+    Ok(())
+}
+
+// the value of a field that is not decoded, from #[ssz(default = "path")] or Default::default
+fn field_default(field_opts: &[FieldOpts]) -> proc_macro2::TokenStream {
+    match field_opts.iter().find_map(|opt| opt.default.as_ref()) {
+        Some(Override::Explicit(path)) => quote! { #path() },
+        _ => quote! { <_>::default() },
+    }
+}
+
+// the maximum length of a runtime-bounded list field in its container, offset included
+fn runtime_list_max_len(ty: &syn::Type, max: &syn::Expr) -> proc_macro2::TokenStream {
+    quote! {
+        {
+            let max_len: usize = #max;
+            sszb::ssz_element_max_len::<<#ty as sszb::RuntimeList>::Item>()
+                .saturating_mul(max_len)
+                .saturating_add(sszb::BYTES_PER_LENGTH_OFFSET)
+        }
+    }
+}
+
+// the fields of a container with their #[ssz(...)] options, or the errors to report for
+// anything the derives do not support
+fn parse_ssz_fields<'a>(
+    derive_input: &'a DeriveInput,
+    derive: &str,
+) -> syn::Result<Vec<(&'a syn::Type, &'a Ident, Vec<FieldOpts>)>> {
+    let fields = match &derive_input.data {
+        syn::Data::Struct(data) => &data.fields,
+        syn::Data::Enum(data) => {
+            return Err(syn::Error::new(
+                data.enum_token.span,
+                format!("#[derive({})] only supports structs", derive),
+            ))
+        }
+        syn::Data::Union(data) => {
+            return Err(syn::Error::new(
+                data.union_token.span,
+                format!("#[derive({})] only supports structs", derive),
+            ))
+        }
+    };
+    let fields = match fields {
+        syn::Fields::Named(fields) if fields.named.is_empty() => {
+            return Err(syn::Error::new_spanned(
+                fields,
+                "an SSZ container must have at least one field",
+            ))
+        }
+        syn::Fields::Named(fields) => &fields.named,
+        syn::Fields::Unnamed(fields) => {
+            return Err(syn::Error::new_spanned(
+                fields,
+                format!(
+                    "#[derive({})] only supports structs with named fields",
+                    derive
+                ),
+            ))
+        }
+        syn::Fields::Unit => {
+            return Err(syn::Error::new_spanned(
+                &derive_input.ident,
+                format!(
+                    "#[derive({})] only supports structs with named fields",
+                    derive
+                ),
+            ))
+        }
     };
+
+    // collect the errors of every field, so they are all reported at once
+    let mut errors = darling::Error::accumulator();
+    let fields = fields
+        .iter()
+        .map(|field| {
+            // possible field options include skip_encode, skip_decode, skip, default, with, validate,
+            // max, forks and by_fork
+            let field_opts = field
+                .attrs
+                .iter()
+                .filter(|attr| attr.path().is_ident("ssz"))
+                .filter_map(|attr| errors.handle(FieldOpts::from_meta(&attr.meta)))
+                .collect::<Vec<_>>();
+            let ident = field.ident.as_ref().unwrap();
+            // options that take a value can only be given once per field
+            let count =
+                |given: fn(&FieldOpts) -> bool| field_opts.iter().filter(|opt| given(opt)).count();
+            let counts = [
+                ("max", count(|opt| opt.max.is_some())),
+                ("default", count(|opt| opt.default.is_some())),
+                ("with", count(|opt| opt.with.is_some())),
+                ("validate", count(|opt| opt.validate.is_some())),
+            ];
+            for (option, count) in counts {
+                if count > 1 {
+                    errors.push(darling::Error::duplicate_field(option).with_span(ident));
+                }
+            }
+            if field_opts.iter().any(|opt| opt.default.is_some()) && !skips_decode(&field_opts) {
+                errors.push(
+                    darling::Error::custom(
+                        "`default` only applies to fields skipped with skip_decode or skip",
+                    )
+                    .with_span(ident),
+                );
+            }
+
+            (&field.ty, ident, field_opts)
+        })
+        .collect();
+    errors.finish()?;
+
+    Ok(fields)
+}
+
+// the container-level options of #[derive(SszbDecode)], which may be spread over several
+// #[ssz(...)] attributes
+fn parse_container_opts(derive_input: &DeriveInput) -> syn::Result<ContainerOpts> {
+    let mut errors = darling::Error::accumulator();
+    let mut opts = ContainerOpts::default();
+    for attr in derive_input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("ssz"))
+    {
+        let Some(parsed) = errors.handle(ContainerOpts::from_meta(&attr.meta)) else {
+            continue;
+        };
+        if parsed.validate.is_some() && opts.validate.is_some() {
+            errors.push(darling::Error::duplicate_field("validate").with_span(attr));
+        }
+        opts.validate = opts.validate.or(parsed.validate);
+    }
+    errors.finish()?;
+
+    Ok(opts)
+}
+
+// the initializer of a decoded field, checked by its #[ssz(validate = "path")] if it has one
+fn validated_field(
+    ident: &Ident,
+    read: proc_macro2::TokenStream,
+    field_opts: &[FieldOpts],
+) -> proc_macro2::TokenStream {
+    match field_opts.iter().find_map(|opt| opt.validate.as_ref()) {
+        Some(validate) => {
+            let check = validate_stmt(quote! { value }, Some(validate), &ident.to_string());
+            quote! {
+                #ident: {
+                    let value = #read;
+                    #check
+                    value
+                }
+            }
+        }
+        None => quote! { #ident: #read },
+    }
+}
+
+// implements SszType and SszLayout from the fields for which `skips` is false
+fn derive_type_impls(
+    derive_input: &DeriveInput,
+    fields: &[(&syn::Type, &Ident, Vec<FieldOpts>)],
+    skips: fn(&[FieldOpts]) -> bool,
+) -> proc_macro2::TokenStream {
     let name = &derive_input.ident;
     let (impl_generics, ty_generics, where_clause) = &derive_input.generics.split_for_impl();
 
     let fixed_len_stmts = &mut vec![];
     let static_stmts = &mut vec![];
-    let bytes_len_stmts = &mut vec![];
+    let min_len_stmts = &mut vec![];
     let max_len_stmts = &mut vec![];
-    let ssz_write_fixed_stmts = &mut vec![];
-    let write_fixed_stmts = &mut vec![];
-    let write_variable_stmts = &mut vec![];
+    let layout_stmts = &mut vec![];
 
-    for (ty, ident, field_opts) in parse_ssz_fields(&struct_data) {
-        if field_opts.iter().any(|opt| opt.skip_encode) {
+    for (ty, ident, field_opts) in fields {
+        if skips(field_opts) {
             continue;
         }
 
-        static_stmts.push(quote! { <#ty as sszb::SszEncode>::is_ssz_static() });
-        fixed_len_stmts.push(quote! { <#ty as sszb::SszEncode>::ssz_fixed_len() });
-        bytes_len_stmts.push(quote! { self.#ident.ssz_bytes_len() });
-        max_len_stmts.push(quote! { <#ty as sszb::SszEncode>::ssz_max_len() });
-        ssz_write_fixed_stmts.push(quote! { self.#ident.ssz_write_fixed(offset, buf) });
-        write_fixed_stmts.push(quote! { self.#ident.ssz_write_fixed(&mut offset, buf) });
-        write_variable_stmts.push(quote! { self.#ident.ssz_write_variable(buf) });
+        let ty = &field_ssz_type(ty, field_opts);
+        let field_name = ident.to_string();
+        layout_stmts.push(quote! {
+            sszb::SszField {
+                name: #field_name,
+                is_static: <#ty as sszb::SszType>::is_ssz_static(),
+                fixed_len: <#ty as sszb::SszType>::ssz_fixed_len(),
+            }
+        });
+
+        static_stmts.push(quote! { <#ty as sszb::SszType>::is_ssz_static() });
+        fixed_len_stmts.push(quote! { <#ty as sszb::SszType>::ssz_fixed_len() });
+        min_len_stmts.push(quote! { sszb::ssz_element_min_len::<#ty>() });
+        match field_max(field_opts) {
+            Some(max) => max_len_stmts.push(runtime_list_max_len(ty, max)),
+            None => max_len_stmts.push(quote! { sszb::ssz_element_max_len::<#ty>() }),
+        }
     }
 
-    let output = quote! {
-        impl #impl_generics sszb::SszEncode for #name #ty_generics #where_clause {
+    quote! {
+        impl #impl_generics sszb::SszType for #name #ty_generics #where_clause {
             fn is_ssz_static() -> bool {
                 #(
                     #static_stmts &&
@@ -77,12 +341,12 @@ pub fn derive_encode(input: TokenStream) -> TokenStream {
             }
 
             fn ssz_fixed_len() -> usize {
-                if <Self as sszb::SszEncode>::is_ssz_static() {
+                if <Self as sszb::SszType>::is_ssz_static() {
                     let mut len: usize = 0;
                     #(
                         len = len
                             .checked_add(#fixed_len_stmts)
-                            .expect("encode ssz_fixed_len length overflow");
+                            .expect("ssz_fixed_len length overflow");
                     )*
                     len
                 } else {
@@ -90,9 +354,101 @@ pub fn derive_encode(input: TokenStream) -> TokenStream {
                 }
             }
 
+            fn ssz_min_len() -> usize {
+                let mut len: usize = 0;
+                #(
+                    len = len
+                        .checked_add(#min_len_stmts)
+                        .expect("ssz_min_len length overflow");
+                )*
+                len
+            }
+
+            // saturates at usize::MAX for containers holding lists without a maximum length
+            fn ssz_max_len() -> usize {
+                let mut len: usize = 0;
+                #(
+                    len = len.saturating_add(#max_len_stmts);
+                )*
+                len
+            }
+        }
+
+        impl #impl_generics sszb::SszLayout for #name #ty_generics #where_clause {
+            fn ssz_fields() -> sszb::__private::Vec<sszb::SszField> {
+                sszb::__private::vec![
+                    #(
+                        #layout_stmts,
+                    )*
+                ]
+            }
+        }
+    }
+}
+
+/// Implements `sszb::SszType` and `sszb::SszLayout`, the layout of the container shared by
+/// `SszEncode` and `SszDecode`. `#[derive(SszbEncode)]` implements both already, this is for
+/// containers that only derive `SszbDecode`, and lays out the fields that are decoded.
+#[proc_macro_derive(SszbType, attributes(ssz))]
+pub fn derive_type(input: TokenStream) -> TokenStream {
+    let derive_input = parse_macro_input!(input as DeriveInput);
+    let fields = match parse_ssz_fields(&derive_input, "SszbType") {
+        Ok(fields) => fields,
+        Err(e) => return e.into_compile_error().into(),
+    };
+    derive_type_impls(&derive_input, &fields, skips_decode).into()
+}
+
+/// Implements `sszb::SszEncode`, along with `sszb::SszType` and `sszb::SszLayout` for the fields
+/// that are encoded.
+#[proc_macro_derive(SszbEncode, attributes(ssz))]
+pub fn derive_encode(input: TokenStream) -> TokenStream {
+    let derive_input = parse_macro_input!(input as DeriveInput);
+    let fields = match parse_ssz_fields(&derive_input, "SszbEncode").and_then(|fields| {
+        check_one_way_skips(
+            &fields,
+            |opts| skips_decode(opts) && !skips_encode(opts),
+            "this field is encoded but not decoded, which lays the two out differently: \
+                 skip it in both directions with `skip`",
+        )
+        .map(|()| fields)
+    }) {
+        Ok(fields) => fields,
+        Err(e) => return e.into_compile_error().into(),
+    };
+    let name = &derive_input.ident;
+    let (impl_generics, ty_generics, where_clause) = &derive_input.generics.split_for_impl();
+
+    let fixed_len_stmts = &mut vec![];
+    let static_stmts = &mut vec![];
+    let bytes_len_stmts = &mut vec![];
+    let ssz_write_fixed_stmts = &mut vec![];
+    let write_fixed_stmts = &mut vec![];
+    let write_variable_stmts = &mut vec![];
+
+    for (ty, ident, field_opts) in &fields {
+        if skips_encode(field_opts) {
+            continue;
+        }
+
+        let ty = &field_ssz_type(ty, field_opts);
+        let value = field_to_ssz(ident, field_opts);
+        static_stmts.push(quote! { <#ty as sszb::SszType>::is_ssz_static() });
+        fixed_len_stmts.push(quote! { <#ty as sszb::SszType>::ssz_fixed_len() });
+        bytes_len_stmts.push(quote! { #value.ssz_bytes_len() });
+        ssz_write_fixed_stmts.push(quote! { #value.ssz_write_fixed(offset, buf) });
+        write_fixed_stmts.push(quote! { #value.ssz_write_fixed(&mut offset, buf) });
+        write_variable_stmts.push(quote! { #value.ssz_write_variable(buf) });
+    }
+
+    let type_impls = derive_type_impls(&derive_input, &fields, skips_encode);
+    let output = quote! {
+        #type_impls
+
+        impl #impl_generics sszb::SszEncode for #name #ty_generics #where_clause {
             fn ssz_bytes_len(&self) -> usize {
-                if <Self as sszb::SszEncode>::is_ssz_static() {
-                    <Self as sszb::SszEncode>::ssz_fixed_len()
+                if <Self as sszb::SszType>::is_ssz_static() {
+                    <Self as sszb::SszType>::ssz_fixed_len()
                 } else {
                     let mut len: usize = 0;
                     #(
@@ -114,20 +470,10 @@ pub fn derive_encode(input: TokenStream) -> TokenStream {
                 }
             }
 
-            fn ssz_max_len() -> usize {
-                let mut len: usize = 0;
-                #(
-                    len = len
-                        .checked_add(#max_len_stmts)
-                        .expect("encode ssz_max_len length overflow");
-                )*
-                len
-            }
-
             fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl BufMut) {
                 // if self is fixed-sized then write the data outright
                 // or else we write the offset to the buffer and point to the end of the buffer
-                if <Self as sszb::SszEncode>::is_ssz_static() {
+                if <Self as sszb::SszType>::is_ssz_static() {
                     #(
                         #ssz_write_fixed_stmts;
                     )*
@@ -138,7 +484,7 @@ pub fn derive_encode(input: TokenStream) -> TokenStream {
             }
 
             fn ssz_write_variable(&self, buf: &mut impl BufMut) {
-                if !<Self as sszb::SszEncode>::is_ssz_static() {
+                if !<Self as sszb::SszType>::is_ssz_static() {
                     self.ssz_write(buf);
                 }
             }
@@ -172,191 +518,790 @@ pub fn derive_encode(input: TokenStream) -> TokenStream {
     output.into()
 }
 
-#[proc_macro_derive(SszbDecode)]
+/// Implements `sszb::SszDecode`. The container also needs `sszb::SszType`, from
+/// `#[derive(SszbEncode)]` or, for decode-only containers, `#[derive(SszbType)]`.
+#[proc_macro_derive(SszbDecode, attributes(ssz))]
 pub fn derive_decode(input: TokenStream) -> TokenStream {
     let derive_input = parse_macro_input!(input as DeriveInput);
-    let struct_data = match derive_input.data {
-        syn::Data::Struct(data) => data,
-        _ => panic!(), // TODO: fix
+    let fields = match parse_ssz_fields(&derive_input, "SszbDecode").and_then(|fields| {
+        check_one_way_skips(
+            &fields,
+            |opts| skips_encode(opts) && !skips_decode(opts),
+            "this field is decoded but not encoded, which lays the two out differently: skip \
+             it in both directions with `skip`",
+        )
+        .map(|()| fields)
+    }) {
+        Ok(fields) => fields,
+        Err(e) => return e.into_compile_error().into(),
+    };
+    let container_opts = match parse_container_opts(&derive_input) {
+        Ok(opts) => opts,
+        Err(e) => return e.into_compile_error().into(),
     };
     let name = &derive_input.ident;
     let (impl_generics, ty_generics, where_clause) = &derive_input.generics.split_for_impl();
 
     let fixed_len_stmts = &mut vec![];
-    let static_stmts = &mut vec![];
-    let max_len_stmts = &mut vec![];
-    let read_stmts = &mut vec![];
-    let read_stmts_var = &mut vec![];
-
-    for (ty, ident, field_opts) in parse_ssz_fields(&struct_data) {
-        let ident = match ident {
-            Some(ref ident) => ident,
-            _ => panic!(
-                "#[ssz(struct_behaviour = \"container\")] only supports named struct fields."
-            ),
-        };
+    let buf_fixed_stmts = &mut vec![];
+    let buf_variable_stmts = &mut vec![];
+    let buf_value_stmts = &mut vec![];
+    let validate_container = validate_stmt(quote! { value }, container_opts.validate.as_ref(), "");
 
-        if field_opts.iter().any(|opt| opt.skip_decode) {
-            // should deserialize default
-            read_stmts.push(quote! {
-                #ident = <_>::default();
-            });
-            read_stmts_var.push(quote! {
-                #ident = <_>::default();
-            });
-
-            continue;
+    for (ty, _, field_opts) in &fields {
+        if !skips_decode(field_opts) {
+            let ty = &field_ssz_type(ty, field_opts);
+            fixed_len_stmts.push(quote! { <#ty as sszb::SszType>::ssz_fixed_len() });
         }
+    }
+
+    // from_ssz_buf reads the fixed portion and then each variable field through one cursor,
+    // so a slice is never copied and a bytes::Bytes buffer is shared by the fields that can
+    let decoded = fields
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, _, field_opts))| !skips_decode(field_opts))
+        .map(|(i, (ty, ident, field_opts))| {
+            let value = quote::format_ident!("__value_{}", i);
+            let offset = quote::format_ident!("__offset_{}", i);
+            (
+                field_ssz_type(ty, field_opts),
+                ident,
+                field_opts,
+                value,
+                offset,
+            )
+        })
+        .collect::<Vec<_>>();
 
-        static_stmts.push(quote! { <#ty as sszb::SszDecode>::is_ssz_static() });
-        fixed_len_stmts.push(quote! { <#ty as sszb::SszDecode>::ssz_fixed_len() });
-        max_len_stmts.push(quote! { <#ty as sszb::SszDecode>::ssz_max_len() });
-        read_stmts.push(quote! {
-            #ident: <#ty as sszb::SszDecode>::ssz_read(fixed_bytes, variable_bytes)?
+    for (i, (ty, ident, field_opts, value, offset)) in decoded.iter().enumerate() {
+        let field_name = ident.to_string();
+        buf_fixed_stmts.push(quote! {
+            let mut #offset: usize = 0;
+            let mut #value = sszb::read_fixed_field::<#ty>(buf, &mut #offset, #field_name)?;
         });
-    }
 
-    for (ty, ident, field_opts) in parse_ssz_fields(&struct_data) {
-        let ident = match ident {
-            Some(ref ident) => ident,
-            _ => panic!(
-                "#[ssz(struct_behaviour = \"container\")] only supports named struct fields."
-            ),
+        let decode_variable = match field_max(field_opts) {
+            Some(max) => quote! {
+                sszb::read_contiguous(buf, field_len, |bytes| {
+                    sszb::ssz_decode_list_with_max::<#ty>(bytes, #max)
+                })
+                .map_err(|e| e.in_field(#field_name))?
+            },
+            None => quote! {
+                sszb::read_variable_field::<#ty>(buf, field_len, #field_name)?
+            },
         };
+        buf_variable_stmts.push(quote! {
+            if !<#ty as sszb::SszType>::is_ssz_static() {
+                let field_len = sszb::variable_field_len(offsets, #i, num_fixed_bytes, end_of_buffer, &mut previous_offset, buf.remaining())?;
+                #value = Some(#decode_variable);
+            }
+        });
+    }
 
-        if field_opts.iter().any(|opt| opt.skip_decode) {
-            read_stmts_var.push(quote! {
-                #ident = <_>::default();
-            });
-
-            continue;
+    let decoded_types = decoded.iter().map(|(ty, ..)| ty);
+    let decoded_offsets = decoded.iter().map(|(.., offset)| offset);
+    for (_, ident, field_opts) in &fields {
+        match decoded
+            .iter()
+            .find(|(_, decoded_ident, ..)| *decoded_ident == ident)
+        {
+            Some((.., value, _)) => {
+                let read = field_from_ssz(
+                    quote! { #value.expect("every decoded field is read from the fixed or variable portion") },
+                    field_opts,
+                );
+                buf_value_stmts.push(validated_field(ident, read, field_opts));
+            }
+            None => {
+                let default = field_default(field_opts);
+                buf_value_stmts.push(quote! { #ident: #default });
+            }
         }
+    }
 
-        read_stmts_var.push(quote! {
-            #ident: if <#ty as sszb::SszDecode>::is_ssz_static() {
-                fixed_cursor = fixed_cursor.checked_add(<#ty as sszb::SszDecode>::ssz_fixed_len()).expect("overflow");
-                <#ty as sszb::SszDecode>::ssz_read(fixed_bytes, variable_bytes)?
-            } else {
-                fixed_cursor = fixed_cursor.checked_add(sszb::BYTES_PER_LENGTH_OFFSET).expect("overflow");
-                let begin = sszb::read_offset_from_buf(fixed_bytes)?;
+    let output = quote! {
+        impl #impl_generics sszb::SszDecode for #name #ty_generics #where_clause {
+            // containers are decoded by from_ssz_buf, which the other entry points delegate to
+            fn ssz_read(fixed_bytes: &mut impl Buf, variable_bytes: &mut impl Buf) -> Result<Self, sszb::DecodeError>  {
+                if <Self as sszb::SszType>::is_ssz_static() {
+                    let len = <Self as sszb::SszType>::ssz_fixed_len();
+                    <Self as sszb::SszDecode>::from_ssz_buf(&mut (&mut *fixed_bytes).take(len))
+                } else {
+                    // a variable-size container spans the rest of both portions
+                    <Self as sszb::SszDecode>::from_ssz_buf(&mut (&mut *fixed_bytes).chain(&mut *variable_bytes))
+                }
+            }
 
-                let mut end = None;
-                let mut start: usize = 0;
+            fn from_ssz_bytes(mut bytes: &[u8]) -> Result<Self, sszb::DecodeError> {
+                <Self as sszb::SszDecode>::from_ssz_buf(&mut bytes)
+            }
+
+            fn from_ssz_buf(buf: &mut impl Buf) -> Result<Self, sszb::DecodeError> {
+                sszb::check_trailing_bytes::<Self>(buf.remaining())?;
+                let mut len: usize = 0;
                 #(
-                    if #static_stmts {
-                        start = start
-                            .checked_add(#fixed_len_stmts)
-                            .expect("ssz fixed length overflow");
-                    } else {
-                        if start >= fixed_cursor && end.is_none() {
-                            let index = start - fixed_cursor;
-                            end = Some(sszb::read_offset_from_slice(&fixed_bytes.chunk()[index..(index + sszb::BYTES_PER_LENGTH_OFFSET)])?);
-                        } else {
-                            start = start
-                                .checked_add(sszb::BYTES_PER_LENGTH_OFFSET)
-                                .expect("ssz fixed length overflow");
-                        }
-                    }
+                    len = len
+                        .checked_add(#fixed_len_stmts)
+                        .expect("decode ssz_fixed_len length overflow");
                 )*
-
-                let field_len = end.unwrap_or(end_of_buffer) - begin;
-                if field_len > variable_bytes.remaining() {
+                if len > buf.remaining() {
                     return Err(sszb::DecodeError::InvalidByteLength {
-                        len: field_len,
-                        expected: variable_bytes.remaining(),
+                        len: buf.remaining(),
+                        expected: len,
                     })
                 } else {
+                    let _nesting = sszb::enter_nested()?;
+                    let end_of_buffer: usize = buf.remaining();
+                    let num_fixed_bytes = len;
+                    let mut previous_offset: Option<usize> = None;
+
+                    #(
+                        #buf_fixed_stmts
+                    )*
+                    let offsets: &[(bool, usize)] = &[#((<#decoded_types as sszb::SszType>::is_ssz_static(), #decoded_offsets)),*];
+                    #(
+                        #buf_variable_stmts
+                    )*
 
-                    // let bytes = variable_bytes.copy_to_bytes(field_len);
-                    let bytes = variable_bytes.chunk();
-                    let bytes = &bytes[..field_len];
-                    // both the fixed and variable buffers are advanced at this point
-                    // even though we don't make a call to ssz_read with them
-                    let res = <#ty as sszb::SszDecode>::from_ssz_bytes(&bytes)?;
-                    variable_bytes.advance(field_len);
-                    res
+                    let value = Self {
+                        #(
+                            #buf_value_stmts,
+                        )*
+                    };
+                    #validate_container
+                    Ok(value)
                 }
             }
+        }
+    };
+    output.into()
+}
+
+/// Implements serde's `Serialize` and `Deserialize` with the consensus spec JSON/YAML
+/// representation of each field, see `sszb::spec_serde`. Fields skipped by SSZ are left out,
+/// and deserialize to their default value.
+#[proc_macro_derive(SszbSerde, attributes(ssz))]
+pub fn derive_serde(input: TokenStream) -> TokenStream {
+    let derive_input = parse_macro_input!(input as DeriveInput);
+    let fields = match parse_ssz_fields(&derive_input, "SszbSerde") {
+        Ok(fields) => fields,
+        Err(e) => return e.into_compile_error().into(),
+    };
+    let name = &derive_input.ident;
+    let name_str = name.to_string();
+    let (impl_generics, ty_generics, where_clause) = &derive_input.generics.split_for_impl();
+
+    let serialize_stmts = &mut vec![];
+    let helper_fields = &mut vec![];
+    let from_helper_stmts = &mut vec![];
+
+    for (ty, ident, field_opts) in &fields {
+        if skips_encode(field_opts) || skips_decode(field_opts) {
+            let default = field_default(field_opts);
+            from_helper_stmts.push(quote! { #ident: #default });
+            continue;
+        }
+
+        // fields with a codec are represented by their encoded type
+        let ty = &field_ssz_type(ty, field_opts);
+        let value = field_to_ssz(ident, field_opts);
+        let field_name = ident.to_string();
+        serialize_stmts.push(quote! {
+            state.serialize_field(#field_name, &sszb::spec_serde::SszSerdeRef(&#value))?;
+        });
+        helper_fields.push(quote! {
+            #[serde(with = "sszb::spec_serde")]
+            #ident: #ty
         });
+        match field_opts.iter().find_map(|opt| opt.with.as_ref()) {
+            Some(with) => from_helper_stmts.push(quote! {
+                #ident: #with::from_ssz(helper.#ident).map_err(|e| {
+                    <D::Error as sszb::spec_serde::serde::de::Error>::custom(format!("{:?}", e))
+                })?
+            }),
+            None => from_helper_stmts.push(quote! { #ident: helper.#ident }),
+        }
     }
 
+    let num_fields = serialize_stmts.len();
+
+    let mut de_generics = derive_input.generics.clone();
+    de_generics.params.insert(0, syn::parse_quote!('de));
+    let (de_impl_generics, _, _) = de_generics.split_for_impl();
+
     let output = quote! {
-        impl #impl_generics sszb::SszDecode for #name #ty_generics #where_clause {
-            fn is_ssz_static() -> bool {
+        sszb::__require_serde!();
+
+        impl #impl_generics sszb::spec_serde::serde::Serialize for #name #ty_generics #where_clause {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: sszb::spec_serde::serde::Serializer,
+            {
+                use sszb::spec_serde::serde::ser::SerializeStruct;
+
+                let mut state = serializer.serialize_struct(#name_str, #num_fields)?;
                 #(
-                    #static_stmts &&
+                    #serialize_stmts
                 )*
-                    true
+                state.end()
             }
+        }
 
-            fn ssz_fixed_len() -> usize {
-                if <Self as sszb::SszEncode>::is_ssz_static() {
-                    let mut len: usize = 0;
-                    #(
-                        len = len
-                            .checked_add(#fixed_len_stmts)
-                            .expect("encode ssz_fixed_len length overflow");
-                    )*
-                    len
-                } else {
-                    sszb::BYTES_PER_LENGTH_OFFSET
-                }
+        // lets the container be nested in other containers and lists
+        impl #impl_generics sszb::SszSerde for #name #ty_generics #where_clause {
+            fn ssz_serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: sszb::spec_serde::serde::Serializer,
+            {
+                sszb::spec_serde::serde::Serialize::serialize(self, serializer)
             }
 
-            fn ssz_max_len() -> usize {
-                let mut len: usize = 0;
+            fn ssz_deserialize<'de, D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: sszb::spec_serde::serde::Deserializer<'de>,
+            {
+                <Self as sszb::spec_serde::serde::Deserialize<'de>>::deserialize(deserializer)
+            }
+        }
+
+        const _: () = {
+            // deserialize into a mirror of the container, with each field going through spec_serde
+            #[derive(sszb::spec_serde::serde::Deserialize)]
+            #[serde(crate = "sszb::spec_serde::serde", bound = "")]
+            struct Helper #impl_generics #where_clause {
                 #(
-                    len = len
-                        .checked_add(#max_len_stmts)
-                        .expect("encode ssz_max_len length overflow");
+                    #helper_fields,
                 )*
-                len
             }
 
-            fn ssz_read(fixed_bytes: &mut impl Buf, variable_bytes: &mut impl Buf) -> Result<Self, sszb::DecodeError>  {
-                if <Self as sszb::SszDecode>::is_ssz_static() {
-                    if fixed_bytes.remaining() < <Self as sszb::SszDecode>::ssz_fixed_len() {
-                        return Err(sszb::DecodeError::InvalidByteLength {
-                            len: fixed_bytes.remaining(),
-                            expected: <Self as sszb::SszDecode>::ssz_fixed_len(),
-                        });
-                    }
-
+            impl #de_impl_generics sszb::spec_serde::serde::Deserialize<'de> for #name #ty_generics #where_clause {
+                fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+                where
+                    D: sszb::spec_serde::serde::Deserializer<'de>,
+                {
+                    let helper = <Helper #ty_generics as sszb::spec_serde::serde::Deserialize>::deserialize(deserializer)?;
                     Ok(Self {
                         #(
-                            #read_stmts,
+                            #from_helper_stmts,
                         )*
                     })
-                } else {
+                }
+            }
+        };
+    };
+    output.into()
+}
 
-                    let end_of_buffer: usize = fixed_bytes.remaining() + variable_bytes.remaining();
+/// Implements `sszb::SszReflect`, describing the container by its name and the schemas of the
+/// fields that are encoded. Lists annotated with `#[ssz(max = N)]` are `List[item, N]`.
+#[proc_macro_derive(SszbSchema, attributes(ssz))]
+pub fn derive_schema(input: TokenStream) -> TokenStream {
+    let derive_input = parse_macro_input!(input as DeriveInput);
+    let fields = match parse_ssz_fields(&derive_input, "SszbSchema") {
+        Ok(fields) => fields,
+        Err(e) => return e.into_compile_error().into(),
+    };
+    let name = &derive_input.ident;
+    let name_str = name.to_string();
+    let (impl_generics, ty_generics, where_clause) = &derive_input.generics.split_for_impl();
 
-                    let mut fixed_cursor: usize = 0;
-                    Ok(Self {
+    let field_stmts = &mut vec![];
+
+    for (ty, ident, field_opts) in &fields {
+        if skips_encode(field_opts) {
+            continue;
+        }
+
+        let ty = &field_ssz_type(ty, field_opts);
+        let field_name = ident.to_string();
+        let schema = match field_max(field_opts) {
+            Some(max) => quote! {
+                sszb::SszSchema::list(
+                    <<#ty as sszb::RuntimeList>::Item as sszb::SszReflect>::ssz_schema(),
+                    #max,
+                )
+            },
+            None => quote! { <#ty as sszb::SszReflect>::ssz_schema() },
+        };
+        field_stmts.push(quote! { (#field_name.to_string(), #schema) });
+    }
+
+    let output = quote! {
+        impl #impl_generics sszb::SszReflect for #name #ty_generics #where_clause {
+            fn ssz_schema() -> sszb::SszSchema {
+                sszb::SszSchema::Container {
+                    name: #name_str.to_string(),
+                    fields: vec![
                         #(
-                            #read_stmts_var,
+                            #field_stmts,
                         )*
-                    })
+                    ],
                 }
             }
+        }
+    };
+    output.into()
+}
 
-            fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, sszb::DecodeError> {
-                let mut len: usize = 0;
+/// Implements `sszb::SszArbitrary`, generating each field with its own `SszArbitrary` impl.
+/// Lists annotated with `#[ssz(max = N)]` hold at most `N` items, and fields skipped by SSZ are
+/// left at their default value so that generated values survive a round trip. For the same
+/// reason, values failing a `#[ssz(validate = "...")]` check are rejected as `IncorrectFormat`.
+#[proc_macro_derive(SszbArbitrary, attributes(ssz))]
+pub fn derive_arbitrary(input: TokenStream) -> TokenStream {
+    let derive_input = parse_macro_input!(input as DeriveInput);
+    let fields = match parse_ssz_fields(&derive_input, "SszbArbitrary") {
+        Ok(fields) => fields,
+        Err(e) => return e.into_compile_error().into(),
+    };
+    let container_opts = match parse_container_opts(&derive_input) {
+        Ok(opts) => opts,
+        Err(e) => return e.into_compile_error().into(),
+    };
+    let name = &derive_input.ident;
+    let (impl_generics, ty_generics, where_clause) = &derive_input.generics.split_for_impl();
+
+    let field_stmts = &mut vec![];
+
+    for (ty, ident, field_opts) in &fields {
+        if skips_encode(field_opts) || skips_decode(field_opts) {
+            let default = field_default(field_opts);
+            field_stmts.push(quote! { #ident: #default });
+            continue;
+        }
+
+        let ty = &field_ssz_type(ty, field_opts);
+        let value = match field_max(field_opts) {
+            Some(max) => quote! {
+                <#ty as sszb::RuntimeList>::from_vec(sszb::arbitrary::arbitrary_list(u, #max)?)
+            },
+            None => quote! { <#ty as sszb::SszArbitrary>::ssz_arbitrary(u)? },
+        };
+        // values that the codec or the validation rejects are not valid for the field
+        let value = match field_opts.iter().find_map(|opt| opt.with.as_ref()) {
+            Some(with) => quote! {
+                #with::from_ssz(#value).map_err(|_| sszb::arbitrary::Error::IncorrectFormat)?
+            },
+            None => value,
+        };
+        match field_opts.iter().find_map(|opt| opt.validate.as_ref()) {
+            Some(validate) => field_stmts.push(quote! {
+                #ident: {
+                    let value = #value;
+                    #validate(&value).map_err(|_| sszb::arbitrary::Error::IncorrectFormat)?;
+                    value
+                }
+            }),
+            None => field_stmts.push(quote! { #ident: #value }),
+        }
+    }
+    let validate_container = container_opts.validate.map(|validate| {
+        quote! {
+            #validate(&value).map_err(|_| sszb::arbitrary::Error::IncorrectFormat)?;
+        }
+    });
+
+    let output = quote! {
+        sszb::__require_arbitrary!();
+
+        impl #impl_generics sszb::SszArbitrary for #name #ty_generics #where_clause {
+            fn ssz_arbitrary(
+                u: &mut sszb::arbitrary::Unstructured<'_>,
+            ) -> sszb::arbitrary::Result<Self> {
+                let value = Self {
+                    #(
+                        #field_stmts,
+                    )*
+                };
+                #validate_container
+                Ok(value)
+            }
+        }
+    };
+    output.into()
+}
+
+// the container-level options of #[derive(SszbForks)], its forks, and whether each field is
+// part of each fork
+#[allow(clippy::type_complexity)]
+fn parse_forks(
+    derive_input: &DeriveInput,
+    fields: &[(&syn::Type, &Ident, Vec<FieldOpts>)],
+) -> syn::Result<(ForksOpts, syn::Path, Vec<Ident>, Vec<Vec<bool>>)> {
+    let mut errors = darling::Error::accumulator();
+    let mut opts = ForksOpts::default();
+    for attr in derive_input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("ssz"))
+    {
+        let Some(parsed) = errors.handle(ForksOpts::from_meta(&attr.meta)) else {
+            continue;
+        };
+        for (name, duplicate) in [
+            ("forks", parsed.forks.is_some() && opts.forks.is_some()),
+            (
+                "fork_name",
+                parsed.fork_name.is_some() && opts.fork_name.is_some(),
+            ),
+            (
+                "enum_name",
+                parsed.enum_name.is_some() && opts.enum_name.is_some(),
+            ),
+            (
+                "validate",
+                parsed.validate.is_some() && opts.validate.is_some(),
+            ),
+        ] {
+            if duplicate {
+                errors.push(darling::Error::duplicate_field(name).with_span(attr));
+            }
+        }
+        if let Some(forks) = &parsed.forks {
+            if forks.is_empty() {
+                errors.push(
+                    darling::Error::custom("#[derive(SszbForks)] requires at least one fork")
+                        .with_span(attr),
+                );
+            }
+        }
+        opts.forks = opts.forks.or(parsed.forks);
+        opts.fork_name = opts.fork_name.or(parsed.fork_name);
+        opts.enum_name = opts.enum_name.or(parsed.enum_name);
+        opts.variant_derives = opts
+            .variant_derives
+            .iter()
+            .chain(parsed.variant_derives.iter())
+            .cloned()
+            .collect::<Vec<_>>()
+            .into();
+        opts.validate = opts.validate.or(parsed.validate);
+    }
+    errors.finish()?;
+
+    let (Some(fork_paths), Some(fork_name)) = (&opts.forks, &opts.fork_name) else {
+        return Err(syn::Error::new_spanned(
+            &derive_input.ident,
+            "#[derive(SszbForks)] requires #[ssz(forks(...), fork_name = ...)]",
+        ));
+    };
+    let forks = fork_paths
+        .iter()
+        .map(|fork| {
+            fork.get_ident().cloned().ok_or_else(|| {
+                syn::Error::new_spanned(
+                    fork,
+                    "forks must be identifiers, e.g. forks(Phase0, Altair)",
+                )
+            })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let mut errors = darling::Error::accumulator();
+    let field_forks = fields
+        .iter()
+        .map(|(ty, _, field_opts)| {
+            if field_opts.iter().any(|opt| opt.by_fork) {
+                if let Err(e) = by_fork_type(ty, &forks[0]) {
+                    errors.push(e.into());
+                }
+            }
+            let field_forks = field_opts.iter().find_map(|opt| opt.forks.as_ref());
+            for path in field_forks.iter().flat_map(|paths| paths.iter()) {
+                if !forks.iter().any(|fork| path.is_ident(fork)) {
+                    errors.push(
+                        darling::Error::custom("this fork is not one of the container's forks")
+                            .with_span(path),
+                    );
+                }
+            }
+            forks
+                .iter()
+                .map(|fork| {
+                    // not is_none_or, which needs Rust 1.82
+                    #[allow(clippy::unnecessary_map_or)]
+                    field_forks.map_or(true, |field_forks| {
+                        field_forks.iter().any(|path| path.is_ident(fork))
+                    })
+                })
+                .collect()
+        })
+        .collect();
+    errors.finish()?;
+
+    let fork_name = fork_name.clone();
+    Ok((opts, fork_name, forks, field_forks))
+}
+
+// the type of a #[ssz(by_fork)] field in the container of `fork`: the container that its template
+// generates for the fork, e.g. BeaconBlockBodyDeneb for a BeaconBlockBody field
+fn by_fork_type(ty: &syn::Type, fork: &Ident) -> syn::Result<syn::Type> {
+    let mut fork_ty = ty.clone();
+    if let syn::Type::Path(syn::TypePath { qself: None, path }) = &mut fork_ty {
+        if let Some(last) = path.segments.last_mut() {
+            last.ident = Ident::new(&format!("{}{}", last.ident, fork), last.ident.span());
+            return Ok(fork_ty);
+        }
+    }
+    Err(syn::Error::new_spanned(
+        ty,
+        "a by_fork field must name the template of a #[derive(SszbForks)] container",
+    ))
+}
+
+// the fields of a struct, parse_ssz_fields having checked that it is one
+fn syn_fields(derive_input: &DeriveInput) -> impl Iterator<Item = &syn::Field> {
+    match &derive_input.data {
+        syn::Data::Struct(data) => data.fields.iter(),
+        _ => unreachable!("parse_ssz_fields only accepts structs"),
+    }
+}
+
+/// Generates a container per fork from a template with every field of every fork, and an enum
+/// wrapping them:
+///
+/// ```ignore
+/// #[derive(SszbForks)]
+/// #[ssz(forks(Altair, Deneb), fork_name = ForkName, variant_derives(Clone, PartialEq, Debug))]
+/// pub struct BeaconBlockBody {
+///     pub graffiti: B256,
+///     #[ssz(forks(Deneb))]
+///     pub blob_kzg_commitments: VariableList<KzgCommitment, U4096>,
+/// }
+/// ```
+///
+/// generates `BeaconBlockBodyAltair` and `BeaconBlockBodyDeneb`, each with the fields whose
+/// `#[ssz(forks(...))]` lists its fork (or that have none) and deriving `SszbEncode`, `SszbDecode`
+/// and `variant_derives`, as well as `enum BeaconBlockBodyVariant { Altair(..), Deneb(..) }`.
+/// `fork_name` is the fork identifier type, an enum with a variant named after each fork that
+/// implements `Clone` and `Debug`. The name of the wrapper can be set with `enum_name`. The
+/// options may be spread over several `#[ssz]` attributes, and a container `validate` applies
+/// to every variant.
+///
+/// The wrapper encodes as the container it holds, and is decoded with `from_ssz_bytes_by_fork`
+/// or `ssz_read_by_fork`. It has a getter for each field, returning an `Option` for the fields
+/// that are not part of every fork.
+///
+/// The wrapper is not an SSZ type itself: its containers may have different sizes, and its bytes
+/// do not say which fork they are. A container holding one is a `SszbForks` template too, whose
+/// field names the template and is marked `#[ssz(by_fork)]`:
+///
+/// ```ignore
+/// #[derive(SszbForks)]
+/// #[ssz(forks(Altair, Deneb), fork_name = ForkName)]
+/// pub struct BeaconBlock {
+///     pub slot: u64,
+///     #[ssz(by_fork)]
+///     pub body: BeaconBlockBody,
+/// }
+/// ```
+///
+/// so that `BeaconBlockDeneb` holds a `BeaconBlockBodyDeneb`, laid out as in the spec, and
+/// `BeaconBlockVariant::from_ssz_bytes_by_fork` passes the fork down. `by_fork` fields have no
+/// getter, as their type differs between forks.
+#[proc_macro_derive(SszbForks, attributes(ssz))]
+pub fn derive_forks(input: TokenStream) -> TokenStream {
+    let derive_input = parse_macro_input!(input as DeriveInput);
+    let fields = match parse_ssz_fields(&derive_input, "SszbForks") {
+        Ok(fields) => fields,
+        Err(e) => return e.into_compile_error().into(),
+    };
+    let name = &derive_input.ident;
+    let vis = &derive_input.vis;
+    let generics = &derive_input.generics;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let (opts, fork_name, forks, field_forks) = match parse_forks(&derive_input, &fields) {
+        Ok(parsed) => parsed,
+        Err(e) => return e.into_compile_error().into(),
+    };
+    let enum_name = opts
+        .enum_name
+        .clone()
+        .unwrap_or_else(|| quote::format_ident!("{}Variant", name));
+    let variant_derives = opts.variant_derives.iter().collect::<Vec<_>>();
+    let variants = forks
+        .iter()
+        .map(|fork| quote::format_ident!("{}{}", name, fork))
+        .collect::<Vec<_>>();
+    let variant_opts = opts.validate.as_ref().map(|validate| {
+        let span = syn::spanned::Spanned::span(validate);
+        let validate = syn::LitStr::new(&quote!(#validate).to_string(), span);
+        quote! { #[ssz(validate = #validate)] }
+    });
+
+    let variant_structs = forks.iter().enumerate().map(|(i, fork)| {
+        let variant = &variants[i];
+        // by_fork fields hold the container of this fork, which is laid out in place of them
+        let fields = syn_fields(&derive_input)
+            .zip(&fields)
+            .zip(&field_forks)
+            .filter(|(_, in_fork)| in_fork[i])
+            .map(|((field, (ty, _, field_opts)), _)| {
+                let mut field = field.clone();
+                if field_opts.iter().any(|opt| opt.by_fork) {
+                    field.ty = by_fork_type(ty, fork).expect("parse_forks checked the type");
+                }
+                field
+            });
+        let doc = format!("The `{}` container of the `{}` fork.", name, fork);
+        quote! {
+            #[doc = #doc]
+            #[derive(#(#variant_derives,)* sszb_derive::SszbEncode, sszb_derive::SszbDecode)]
+            #variant_opts
+            #vis struct #variant #generics #where_clause {
                 #(
-                    len = len
-                        .checked_add(#fixed_len_stmts)
-                        .expect("decode ssz_fixed_len length overflow");
+                    #fields,
                 )*
-                if len > bytes.len() {
-                    return Err(sszb::DecodeError::InvalidByteLength {
-                        len: bytes.len(),
-                        expected: len,
-                    })
-                } else {
-                    let (mut fixed_bytes, mut variable_bytes) = bytes.split_at(len);
-                    <Self as SszDecode>::ssz_read(&mut fixed_bytes, &mut variable_bytes)
+            }
+        }
+    });
+
+    // by_fork fields have a different type in each fork, and no getter
+    let getters = fields
+        .iter()
+        .zip(&field_forks)
+        .filter(|((_, _, field_opts), _)| !field_opts.iter().any(|opt| opt.by_fork))
+        .map(|((ty, ident, _), in_fork)| {
+            if in_fork.iter().all(|in_fork| *in_fork) {
+                quote! {
+                    pub fn #ident(&self) -> &#ty {
+                        match self {
+                            #(
+                                Self::#forks(inner) => &inner.#ident,
+                            )*
+                        }
+                    }
+                }
+            } else {
+                let arms = forks.iter().zip(in_fork).map(|(fork, in_fork)| {
+                    if *in_fork {
+                        quote! { Self::#fork(inner) => Some(&inner.#ident) }
+                    } else {
+                        quote! { Self::#fork(_) => None }
+                    }
+                });
+                quote! {
+                    pub fn #ident(&self) -> Option<&#ty> {
+                        match self {
+                            #(
+                                #arms,
+                            )*
+                        }
+                    }
+                }
+            }
+        });
+
+    let enum_name_str = enum_name.to_string();
+    let enum_doc = format!("A `{}` of any fork.", name);
+
+    let output = quote! {
+        #(
+            #variant_structs
+        )*
+
+        #[doc = #enum_doc]
+        #[derive(#(#variant_derives,)*)]
+        #vis enum #enum_name #generics #where_clause {
+            #(
+                #forks(#variants #ty_generics),
+            )*
+        }
+
+        #(
+            impl #impl_generics From<#variants #ty_generics> for #enum_name #ty_generics #where_clause {
+                fn from(inner: #variants #ty_generics) -> Self {
+                    Self::#forks(inner)
+                }
+            }
+        )*
+
+        impl #impl_generics #enum_name #ty_generics #where_clause {
+            pub fn fork_name(&self) -> #fork_name {
+                match self {
+                    #(
+                        Self::#forks(_) => #fork_name::#forks,
+                    )*
+                }
+            }
+
+            /// Decodes the container of the given fork from `bytes`.
+            pub fn from_ssz_bytes_by_fork(
+                bytes: &[u8],
+                fork: #fork_name,
+            ) -> Result<Self, sszb::DecodeError> {
+                #[allow(unreachable_patterns)]
+                match fork {
+                    #(
+                        #fork_name::#forks => {
+                            <#variants #ty_generics as sszb::SszDecode>::from_ssz_bytes(bytes)
+                                .map(Self::#forks)
+                        }
+                    )*
+                    fork => Err(sszb::DecodeError::BytesInvalid(sszb::__private::format!(
+                        "{} has no variant for fork {:?}",
+                        #enum_name_str, fork
+                    ))),
+                }
+            }
+
+            /// Reads the container of the given fork, see `SszDecode::ssz_read`.
+            pub fn ssz_read_by_fork(
+                fixed_bytes: &mut impl Buf,
+                variable_bytes: &mut impl Buf,
+                fork: #fork_name,
+            ) -> Result<Self, sszb::DecodeError> {
+                #[allow(unreachable_patterns)]
+                match fork {
+                    #(
+                        #fork_name::#forks => {
+                            <#variants #ty_generics as sszb::SszDecode>::ssz_read(
+                                fixed_bytes,
+                                variable_bytes,
+                            )
+                            .map(Self::#forks)
+                        }
+                    )*
+                    fork => Err(sszb::DecodeError::BytesInvalid(sszb::__private::format!(
+                        "{} has no variant for fork {:?}",
+                        #enum_name_str, fork
+                    ))),
+                }
+            }
+
+            /// The number of bytes the container it holds encodes to.
+            pub fn ssz_bytes_len(&self) -> usize {
+                match self {
+                    #(
+                        Self::#forks(inner) => sszb::SszEncode::ssz_bytes_len(inner),
+                    )*
+                }
+            }
+
+            /// Writes the container it holds, see `SszEncode::ssz_write`.
+            pub fn ssz_write(&self, buf: &mut impl BufMut) {
+                match self {
+                    #(
+                        Self::#forks(inner) => sszb::SszEncode::ssz_write(inner, buf),
+                    )*
                 }
             }
+
+            /// Encodes the container it holds.
+            pub fn to_ssz(&self) -> sszb::__private::Vec<u8> {
+                match self {
+                    #(
+                        Self::#forks(inner) => sszb::SszEncode::to_ssz(inner),
+                    )*
+                }
+            }
+
+            #(
+                #getters
+            )*
         }
     };
     output.into()
@@ -2,9 +2,11 @@ use bytes::buf::{Buf, BufMut};
 use itertools::Itertools as _;
 use milhouse::List;
 use ssz_types::BitList;
-use sszb::{DecodeError, SszDecode, SszEncode};
-use sszb_derive::{SszbDecode, SszbEncode};
+use sszb::{DecodeError, SszDecode, SszEncode, SszHash, SszReflect, SszSchema, SszType, SszValue};
+use sszb_derive::{SszbArbitrary, SszbDecode, SszbEncode, SszbForks, SszbSchema, SszbType};
 use std::fmt::Debug;
+use tree_hash::TreeHash;
+use tree_hash_derive::TreeHash;
 
 fn assert_encode<T: SszEncode>(item: &T, bytes: &[u8]) {
     assert_eq!(SszEncode::to_ssz(item), bytes);
@@ -19,7 +21,7 @@ fn assert_encode_decode<T: SszEncode + SszDecode + PartialEq + Debug>(item: &T,
     assert_eq!(<T as SszDecode>::from_ssz_bytes(bytes).unwrap(), *item);
 }
 
-#[derive(PartialEq, Debug, SszbDecode, SszbEncode)]
+#[derive(PartialEq, Debug, SszbDecode, SszbEncode, SszbArbitrary)]
 struct VariableA {
     a: u16,
     b: u32,
@@ -28,13 +30,13 @@ struct VariableA {
 type C = typenum::U10;
 const N: u16 = 10;
 
-#[derive(PartialEq, Debug, SszbDecode, SszbEncode)]
+#[derive(PartialEq, Debug, SszbDecode, SszbEncode, SszbArbitrary, SszbSchema)]
 struct VariableB {
     a: u16,
     b: List<u16, C>,
 }
 
-#[derive(PartialEq, Debug, SszbDecode, SszbEncode)]
+#[derive(PartialEq, Debug, SszbDecode, SszbEncode, SszbArbitrary)]
 struct VariableC {
     a: u16,
     c: BitList8,
@@ -113,6 +115,28 @@ fn test_bad_offset_var_b() {
     );
 }
 
+#[test]
+fn test_bad_offsets_nested_lists() {
+    type Inner = ssz_types::VariableList<u8, typenum::U8>;
+    type Lists = ssz_types::VariableList<Inner, typenum::U4>;
+    type Vectors = ssz_types::FixedVector<Inner, typenum::U2>;
+
+    // a list holding one empty list is just the one offset
+    let lists = Lists::new(vec![Inner::new(vec![]).unwrap()]).unwrap();
+    assert_encode_decode(&lists, &[4, 0, 0, 0]);
+
+    assert!(Lists::from_ssz_bytes(&[26]).is_err());
+    assert!(Vectors::from_ssz_bytes(&[234, 1, 186]).is_err());
+    assert_eq!(
+        Vectors::from_ssz_bytes(&[12, 0, 0, 0, 12, 0, 0, 0, 1, 2, 3, 4]),
+        Err(DecodeError::OffsetSkipsVariableBytes(12))
+    );
+    assert_eq!(
+        Vectors::from_ssz_bytes(&[8, 0, 0, 0, 7, 0, 0, 0, 1]),
+        Err(DecodeError::OffsetsAreDecreasing(7))
+    );
+}
+
 #[test]
 fn test_invalid_length_var_b() {
     let bytes = vec![0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 2, 0, 3, 0, 4, 0];
@@ -125,3 +149,1507 @@ fn test_invalid_length_var_b() {
         true
     );
 }
+
+#[derive(PartialEq, Debug, SszbDecode, SszbEncode, SszbArbitrary, SszbSchema)]
+struct OptionalFields {
+    a: u16,
+    b: Option<u64>,
+    c: Option<List<u16, C>>,
+}
+
+#[test]
+fn test_option_union() {
+    assert_encode_decode(&None::<u64>, &[0]);
+    assert_encode_decode(&Some(5u64), &[1, 5, 0, 0, 0, 0, 0, 0, 0]);
+
+    assert_eq!(
+        <Option<u64> as SszDecode>::from_ssz_bytes(&[0, 1]),
+        Err(DecodeError::InvalidByteLength {
+            len: 2,
+            expected: 1
+        })
+    );
+    assert_eq!(
+        <Option<u64> as SszDecode>::from_ssz_bytes(&[2]),
+        Err(DecodeError::UnionSelectorInvalid(2))
+    );
+
+    let opt = OptionalFields {
+        a: 7,
+        b: Some(42),
+        c: None,
+    };
+    let bytes = SszEncode::to_ssz(&opt);
+    assert_eq!(
+        bytes,
+        vec![7, 0, 10, 0, 0, 0, 19, 0, 0, 0, 1, 42, 0, 0, 0, 0, 0, 0, 0, 0]
+    );
+    assert_encode_decode(&opt, &bytes);
+
+    let opt = OptionalFields {
+        a: 7,
+        b: None,
+        c: Some(List::<u16, C>::try_from_iter(0..3).unwrap()),
+    };
+    let bytes = SszEncode::to_ssz(&opt);
+    assert_encode_decode(&opt, &bytes);
+
+    // the value of a union may be split across the chunks of the buffer
+    let bytes = SszEncode::to_ssz(&Some(42u64));
+    for split in 0..=bytes.len() {
+        let (head, tail) = bytes.split_at(split);
+        let mut chain = head.chain(tail);
+        let decoded = <Option<u64>>::ssz_read(&mut &[][..], &mut chain).unwrap();
+        assert_eq!(decoded, Some(42));
+        assert!(!chain.has_remaining());
+    }
+
+    // unions are hashed with their selector mixed into the root of the value
+    let root = tree_hash::Hash256::from(SszHash::hash_tree_root(&42u64).0);
+    assert_eq!(
+        SszHash::hash_tree_root(&Some(42u64)).0,
+        tree_hash::mix_in_selector(&root, 1).unwrap().0
+    );
+    assert_eq!(
+        SszHash::hash_tree_root(&None::<u64>).0,
+        tree_hash::mix_in_selector(&tree_hash::Hash256::ZERO, 0)
+            .unwrap()
+            .0
+    );
+    assert_eq!(
+        SszHash::hash_tree_root(&[Some(1u64), None]).0,
+        tree_hash::merkle_root(
+            &[
+                SszHash::hash_tree_root(&Some(1u64)).0,
+                SszHash::hash_tree_root(&None::<u64>).0
+            ]
+            .concat(),
+            2
+        )
+        .0
+    );
+}
+
+#[derive(PartialEq, Debug, SszbDecode, SszbEncode, SszbArbitrary, SszbSchema)]
+struct ArrayFields {
+    a: [u64; 4],
+    b: [List<u16, C>; 2],
+    c: (u8, List<u16, C>, u32),
+}
+
+#[derive(PartialEq, Debug, SszbDecode, SszbEncode, SszbSchema, TreeHash)]
+struct Pair {
+    a: u8,
+    b: u16,
+}
+
+#[test]
+fn test_arrays_and_tuples() {
+    assert_encode_decode(
+        &[1u64, 2],
+        &[1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0],
+    );
+    assert_encode_decode(&(1u8, 2u16), &[1, 2, 0]);
+
+    let list = List::<u16, C>::try_from_iter(0..2).unwrap();
+    assert_encode_decode(
+        &[list.clone(), List::empty()],
+        &[8, 0, 0, 0, 12, 0, 0, 0, 0, 0, 1, 0],
+    );
+    assert_encode_decode(
+        &(1u8, list.clone(), 2u16),
+        &[1, 7, 0, 0, 0, 2, 0, 0, 0, 1, 0],
+    );
+
+    let arrays = ArrayFields {
+        a: [1, 2, 3, 4],
+        b: [list.clone(), List::empty()],
+        c: (5, list, 6),
+    };
+    let bytes = SszEncode::to_ssz(&arrays);
+    assert_eq!(bytes.len(), arrays.ssz_bytes_len());
+    assert_encode_decode(&arrays, &bytes);
+
+    // byte arrays are copied at once, other arrays of single bytes still check each item
+    assert_encode_decode(&[1u8, 2, 3], &[1, 2, 3]);
+    assert_eq!(
+        <[u8; 3]>::from_ssz_bytes(&[1, 2]),
+        Err(DecodeError::InvalidByteLength {
+            len: 2,
+            expected: 3
+        })
+    );
+    assert_encode_decode(&[true, false], &[1, 0]);
+    assert!(<[bool; 2]>::from_ssz_bytes(&[1, 2]).is_err());
+
+    // SSZ has no empty vectors, which with variable-size items would not even have an offset
+    assert!(<[List<u16, C>; 0]>::from_ssz_bytes(&[]).is_err());
+    assert!(<[List<u16, C>; 0]>::from_ssz_bytes(&[4, 0, 0, 0]).is_err());
+
+    // arrays are hashed as vectors and tuples as containers with the same fields
+    let mut root = [0; 32];
+    root[..8].copy_from_slice(&[1, 0, 0, 0, 2, 0, 0, 0]);
+    assert_eq!(SszHash::hash_tree_root(&[1u32, 2]).0, root);
+    let roots = [
+        alloy_primitives::B256::repeat_byte(1),
+        alloy_primitives::B256::repeat_byte(2),
+    ];
+    assert_eq!(
+        SszHash::hash_tree_root(&roots).0,
+        ssz_types::FixedVector::<alloy_primitives::B256, typenum::U2>::from(roots.to_vec())
+            .tree_hash_root()
+            .0
+    );
+    assert_eq!(
+        SszHash::hash_tree_root(&(1u8, 2u16)).0,
+        Pair { a: 1, b: 2 }.tree_hash_root().0
+    );
+    let nested = [[1u64, 2, 3, 4], [5, 6, 7, 8]];
+    assert_eq!(
+        SszHash::hash_tree_root(&nested).0,
+        ssz_types::FixedVector::<ssz_types::FixedVector<u64, typenum::U4>, typenum::U2>::from(
+            nested
+                .iter()
+                .map(|items| items.to_vec().into())
+                .collect::<Vec<_>>()
+        )
+        .tree_hash_root()
+        .0
+    );
+    assert_eq!(
+        SszHash::hash_tree_root(&[(1u8, 2u16), (3, 4)]).0,
+        ssz_types::FixedVector::<Pair, typenum::U2>::from(vec![
+            Pair { a: 1, b: 2 },
+            Pair { a: 3, b: 4 }
+        ])
+        .tree_hash_root()
+        .0
+    );
+
+    // the first offset of a variable-size tuple must point to the end of its fixed portion
+    assert_eq!(
+        <(u8, List<u16, C>)>::from_ssz_bytes(&[1, 6, 0, 0, 0, 0]),
+        Err(DecodeError::OffsetSkipsVariableBytes(6))
+    );
+
+    // variable-size arrays and tuples read their variable bytes in several chunks, and consume them
+    let bytes = SszEncode::to_ssz(&arrays.b);
+    for split in 0..=bytes.len() {
+        let (head, tail) = bytes.split_at(split);
+        let mut chain = head.chain(tail);
+        let decoded = <[List<u16, C>; 2]>::ssz_read(&mut &[][..], &mut chain).unwrap();
+        assert_eq!(decoded, arrays.b);
+        assert!(!chain.has_remaining());
+    }
+    let bytes = SszEncode::to_ssz(&arrays.c);
+    for split in 0..=bytes.len() {
+        let (head, tail) = bytes.split_at(split);
+        let mut chain = head.chain(tail);
+        let decoded = <(u8, List<u16, C>, u32)>::ssz_read(&mut &[][..], &mut chain).unwrap();
+        assert_eq!(decoded, arrays.c);
+        assert!(!chain.has_remaining());
+    }
+}
+
+#[derive(PartialEq, Debug, SszbDecode, SszbEncode, SszbArbitrary, SszbSchema)]
+struct RuntimeLists {
+    a: Box<u16>,
+    #[ssz(max = 4)]
+    b: Vec<u32>,
+    #[ssz(max = 2)]
+    c: Box<[List<u16, C>]>,
+}
+
+#[test]
+fn test_pointers_and_runtime_lists() {
+    use std::borrow::Cow;
+    use std::rc::Rc;
+
+    assert_encode_decode(&Box::new(1u16), &[1, 0]);
+    assert_encode_decode(&Rc::new(1u16), &[1, 0]);
+    assert_encode_decode(&Cow::<u16>::Owned(1), &[1, 0]);
+
+    // lists without a declared maximum are encodable but refuse to decode
+    assert_encode(&vec![1u16, 2], &[1, 0, 2, 0]);
+    assert_eq!(
+        <Vec<u16> as SszDecode>::from_ssz_bytes(&[1, 0, 2, 0]),
+        Err(DecodeError::UnboundedList)
+    );
+    assert_eq!(
+        sszb::ssz_decode_list_with_max::<Vec<u16>>(&[1, 0, 2, 0], 2),
+        Ok(vec![1, 2])
+    );
+
+    let lists = RuntimeLists {
+        a: Box::new(3),
+        b: vec![1, 2, 3],
+        c: vec![List::<u16, C>::try_from_iter(0..2).unwrap()].into_boxed_slice(),
+    };
+    let bytes = SszEncode::to_ssz(&lists);
+    assert_encode_decode(&lists, &bytes);
+    assert_eq!(
+        <RuntimeLists as SszType>::ssz_max_len(),
+        2 + (4 + 4 * 4) + (4 + 2 * (4 + <List<u16, C> as SszType>::ssz_max_len()))
+    );
+
+    let too_long = RuntimeLists {
+        a: Box::new(3),
+        b: vec![1, 2, 3, 4, 5],
+        c: vec![].into_boxed_slice(),
+    };
+    assert!(RuntimeLists::from_ssz_bytes(&too_long.to_ssz()).is_err());
+}
+
+#[derive(PartialEq, Debug, SszbDecode, SszbEncode, SszbArbitrary, SszbSchema)]
+struct Payload {
+    a: u16,
+    extra_data: sszb::ByteList<typenum::U32>,
+    #[ssz(max = 8)]
+    el_data: alloy_primitives::Bytes,
+}
+
+#[test]
+fn test_byte_lists() {
+    let payload = Payload {
+        a: 1,
+        extra_data: sszb::ByteList::new(vec![1, 2, 3]).unwrap(),
+        el_data: alloy_primitives::Bytes::from(vec![4, 5]),
+    };
+    let bytes = SszEncode::to_ssz(&payload);
+    assert_eq!(bytes, vec![1, 0, 10, 0, 0, 0, 13, 0, 0, 0, 1, 2, 3, 4, 5]);
+    assert_encode_decode(&payload, &bytes);
+
+    // decoding from bytes::Bytes slices the input instead of copying it
+    let mut shared = bytes::Bytes::from(bytes);
+    let start = shared.as_ptr() as usize;
+    let decoded = Payload::from_ssz_buf(&mut shared).unwrap();
+    assert_eq!(decoded, payload);
+    assert_eq!(decoded.extra_data.as_bytes().as_ptr() as usize, start + 10);
+    assert!(!shared.has_remaining());
+
+    assert!(sszb::ByteList::<typenum::U2>::new(vec![1, 2, 3]).is_err());
+    assert!(sszb::ssz_decode_list_with_max::<alloy_primitives::Bytes>(&[0; 9], 8).is_err());
+}
+
+#[derive(PartialEq, Debug, SszbDecode, SszbEncode)]
+struct NestedPayload {
+    a: u8,
+    payload: Payload,
+    #[ssz(max = 4)]
+    list: Vec<u16>,
+}
+
+#[test]
+fn test_nested_from_ssz_buf() {
+    let nested = NestedPayload {
+        a: 7,
+        payload: Payload {
+            a: 1,
+            extra_data: sszb::ByteList::new(vec![1, 2, 3]).unwrap(),
+            el_data: alloy_primitives::Bytes::from(vec![4, 5]),
+        },
+        list: vec![6, 7],
+    };
+    let bytes = SszEncode::to_ssz(&nested);
+    assert_encode_decode(&nested, &bytes);
+
+    // nested containers still share memory with a bytes::Bytes input
+    let mut shared = bytes::Bytes::from(bytes.clone());
+    let start = shared.as_ptr() as usize;
+    let decoded = NestedPayload::from_ssz_buf(&mut shared).unwrap();
+    assert_eq!(decoded, nested);
+    assert_eq!(
+        decoded.payload.extra_data.as_bytes().as_ptr() as usize,
+        start + 9 + 10
+    );
+    assert!(!shared.has_remaining());
+
+    // a buffer in several chunks decodes like a slice, wherever it is split
+    for split in 0..=bytes.len() {
+        let (head, tail) = bytes.split_at(split);
+        let mut chain = head.chain(tail);
+        assert_eq!(NestedPayload::from_ssz_buf(&mut chain).unwrap(), nested);
+        assert!(!chain.has_remaining());
+    }
+}
+
+type Transaction = sszb::ByteList<typenum::U1073741824>;
+
+#[derive(PartialEq, Debug, SszbDecode, SszbEncode)]
+struct ExecutionPayload {
+    block_number: u64,
+    extra_data: sszb::ByteList<typenum::U32>,
+    transactions: ssz_types::VariableList<Transaction, typenum::U1048576>,
+    blob_hashes: [Transaction; 2],
+    parent_transaction: Option<Transaction>,
+}
+
+#[derive(PartialEq, Debug, SszbDecode, SszbEncode)]
+struct Block {
+    slot: u64,
+    execution_payload: ExecutionPayload,
+}
+
+#[test]
+fn test_list_items_from_ssz_buf() {
+    let transaction = |bytes: &[u8]| Transaction::new(bytes.to_vec()).unwrap();
+    let block = Block {
+        slot: 3,
+        execution_payload: ExecutionPayload {
+            block_number: 9,
+            extra_data: sszb::ByteList::new(vec![1]).unwrap(),
+            transactions: vec![transaction(&[2, 3]), transaction(&[]), transaction(&[4])].into(),
+            blob_hashes: [transaction(&[5]), transaction(&[6, 7])],
+            parent_transaction: Some(transaction(&[8, 9, 10])),
+        },
+    };
+    let bytes = SszEncode::to_ssz(&block);
+    assert_encode_decode(&block, &bytes);
+
+    // the items of lists, vectors and unions are split off a bytes::Bytes input rather than
+    // copied out of it
+    let mut shared = bytes::Bytes::from(bytes.clone());
+    let input = shared.as_ptr_range();
+    let decoded = Block::from_ssz_buf(&mut shared).unwrap();
+    assert_eq!(decoded, block);
+    assert!(!shared.has_remaining());
+    let payload = &decoded.execution_payload;
+    for item in [
+        &payload.transactions[0],
+        &payload.transactions[2],
+        &payload.blob_hashes[1],
+        payload.parent_transaction.as_ref().unwrap(),
+    ] {
+        assert!(input.contains(&item.as_bytes().as_ptr()));
+    }
+
+    for split in 0..=bytes.len() {
+        let (head, tail) = bytes.split_at(split);
+        let mut chain = head.chain(tail);
+        assert_eq!(Block::from_ssz_buf(&mut chain).unwrap(), block);
+        assert!(!chain.has_remaining());
+    }
+}
+
+// a static value decodes from exactly its fixed length, and not from more
+fn assert_rejects_trailing<T: SszDecode + Debug>() {
+    let len = <T as SszType>::ssz_fixed_len();
+    assert!(T::from_ssz_bytes(&vec![0; len]).is_ok());
+    assert_eq!(
+        T::from_ssz_bytes(&vec![0; len + 3]).unwrap_err(),
+        DecodeError::TrailingBytes {
+            len: len + 3,
+            expected: len
+        }
+    );
+    assert_eq!(
+        T::from_ssz_buf(&mut &vec![0; len + 1][..]).unwrap_err(),
+        DecodeError::TrailingBytes {
+            len: len + 1,
+            expected: len
+        }
+    );
+}
+
+#[test]
+fn test_trailing_bytes() {
+    assert_rejects_trailing::<u8>();
+    assert_rejects_trailing::<u16>();
+    assert_rejects_trailing::<u32>();
+    assert_rejects_trailing::<u64>();
+    assert_rejects_trailing::<u128>();
+    assert_rejects_trailing::<bool>();
+    assert_rejects_trailing::<alloy_primitives::Address>();
+    assert_rejects_trailing::<alloy_primitives::FixedBytes<4>>();
+    assert_rejects_trailing::<alloy_primitives::Bloom>();
+    assert_rejects_trailing::<alloy_primitives::U256>();
+    assert_rejects_trailing::<alloy_primitives::U128>();
+    assert_rejects_trailing::<ethereum_types::H32>();
+    assert_rejects_trailing::<ethereum_types::H160>();
+    assert_rejects_trailing::<ethereum_types::H256>();
+    assert_rejects_trailing::<ssz_types::BitVector<typenum::U10>>();
+    assert_rejects_trailing::<sszb::Bitvector<typenum::U10>>();
+    assert_rejects_trailing::<[u16; 3]>();
+    assert_rejects_trailing::<(u8, u16)>();
+    assert_rejects_trailing::<ssz_types::FixedVector<u16, typenum::U3>>();
+    assert_rejects_trailing::<milhouse::Vector<u16, typenum::U3>>();
+    assert_rejects_trailing::<Box<u64>>();
+    assert_rejects_trailing::<std::sync::Arc<u64>>();
+    assert_rejects_trailing::<std::borrow::Cow<u64>>();
+    assert_rejects_trailing::<VariableA>();
+    assert_rejects_trailing::<FixedSkipDecode>();
+
+    // a partial item at the end of a list of static items
+    assert_eq!(
+        List::<u16, C>::from_ssz_bytes(&[1, 0, 2]),
+        Err(DecodeError::TrailingBytes {
+            len: 3,
+            expected: 2
+        })
+    );
+    assert_eq!(
+        ssz_types::VariableList::<u16, C>::from_ssz_bytes(&[1, 0, 2]),
+        Err(DecodeError::TrailingBytes {
+            len: 3,
+            expected: 2
+        })
+    );
+    assert_eq!(
+        sszb::ssz_decode_list_with_max::<Vec<u16>>(&[1, 0, 2], 2),
+        Err(DecodeError::TrailingBytes {
+            len: 3,
+            expected: 2
+        })
+    );
+
+    // values nested in variable-size types are held to their exact length too
+    assert_eq!(
+        Option::<u64>::from_ssz_bytes(&[1, 2, 0, 0, 0, 0, 0, 0, 0, 0]),
+        Err(DecodeError::TrailingBytes {
+            len: 9,
+            expected: 8
+        })
+    );
+    assert!(VariableB::from_ssz_bytes(&[1, 0, 6, 0, 0, 0, 2, 0, 3]).is_err());
+
+    // vectors hold N items, so there is no empty vector to decode
+    assert!(milhouse::Vector::<u16, typenum::U3>::from_ssz_bytes(&[]).is_err());
+    assert!(ssz_types::FixedVector::<u16, typenum::U3>::from_ssz_bytes(&[]).is_err());
+    assert!(milhouse::Vector::<List<u16, C>, typenum::U3>::from_ssz_bytes(&[]).is_err());
+    assert!(ssz_types::FixedVector::<List<u16, C>, typenum::U3>::from_ssz_bytes(&[]).is_err());
+}
+
+#[test]
+fn test_decode_limits() {
+    use sszb::DecodeConfig;
+
+    let list = List::<u16, C>::try_from_iter(0..5).unwrap();
+    let bytes = SszEncode::to_ssz(&list);
+    let var_b = VariableB {
+        a: 1,
+        b: list.clone(),
+    };
+    let var_b_bytes = SszEncode::to_ssz(&var_b);
+
+    // the default config only holds decoding to the bounds of the types
+    let config = DecodeConfig::default();
+    assert_eq!(
+        List::<u16, C>::from_ssz_bytes_with_config(&bytes, &config),
+        Ok(list.clone())
+    );
+    assert_eq!(
+        VariableB::from_ssz_bytes_with_config(&var_b_bytes, &config),
+        Ok(var_b)
+    );
+
+    let config = DecodeConfig {
+        max_list_items: 4,
+        ..DecodeConfig::default()
+    };
+    assert_eq!(
+        List::<u16, C>::from_ssz_bytes_with_config(&bytes, &config),
+        Err(DecodeError::ListItemsLimitExceeded { len: 5, max: 4 })
+    );
+    assert_eq!(
+        VariableB::from_ssz_buf_with_config(&mut &var_b_bytes[..], &config),
+        Err(DecodeError::ListItemsLimitExceeded { len: 5, max: 4 })
+    );
+    assert!(<[u16; 5]>::from_ssz_bytes_with_config(&bytes, &config).is_err());
+    // limits only apply within the call they are given to
+    assert_eq!(List::<u16, C>::from_ssz_bytes(&bytes), Ok(list.clone()));
+
+    // the container is at depth 1 and its list at depth 2
+    let config = DecodeConfig {
+        max_depth: 1,
+        ..DecodeConfig::default()
+    };
+    assert_eq!(
+        VariableB::from_ssz_bytes_with_config(&var_b_bytes, &config),
+        Err(DecodeError::DepthLimitExceeded { max: 1 })
+    );
+    assert!(VariableA::from_ssz_bytes_with_config(&[0; 6], &config).is_ok());
+    let config = DecodeConfig {
+        max_depth: 2,
+        ..DecodeConfig::default()
+    };
+    assert!(VariableB::from_ssz_bytes_with_config(&var_b_bytes, &config).is_ok());
+    let nested = ssz_types::VariableList::<_, typenum::U2>::new(vec![VariableB {
+        a: 1,
+        b: list.clone(),
+    }])
+    .unwrap();
+    assert_eq!(
+        ssz_types::VariableList::<VariableB, typenum::U2>::from_ssz_bytes_with_config(
+            &nested.to_ssz(),
+            &config
+        ),
+        Err(DecodeError::DepthLimitExceeded { max: 2 })
+    );
+
+    // allocations add up over the whole value
+    let pair = (list.clone(), list.clone());
+    let config = DecodeConfig {
+        max_allocated_bytes: 10,
+        ..DecodeConfig::default()
+    };
+    assert_eq!(
+        List::<u16, C>::from_ssz_bytes_with_config(&bytes, &config),
+        Ok(list.clone())
+    );
+    assert_eq!(
+        <(List<u16, C>, List<u16, C>)>::from_ssz_bytes_with_config(&pair.to_ssz(), &config),
+        Err(DecodeError::AllocationLimitExceeded {
+            allocated: 20,
+            max: 10
+        })
+    );
+    let config = DecodeConfig {
+        max_allocated_bytes: 8,
+        ..DecodeConfig::default()
+    };
+    let bits = [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01];
+    assert_eq!(
+        sszb::Bitlist::<typenum::U70>::from_ssz_bytes_with_config(&bits, &config),
+        Err(DecodeError::AllocationLimitExceeded {
+            allocated: 9,
+            max: 8
+        })
+    );
+
+    // bitlists count their bits as items
+    let config = DecodeConfig {
+        max_list_items: 16,
+        ..DecodeConfig::default()
+    };
+    assert!(sszb::Bitlist::<typenum::U70>::from_ssz_bytes_with_config(
+        &[0xff, 0xff, 0x01],
+        &config
+    )
+    .is_ok());
+    assert_eq!(
+        sszb::Bitlist::<typenum::U70>::from_ssz_bytes_with_config(&[0xff, 0xff, 0x03], &config),
+        Err(DecodeError::ListItemsLimitExceeded { len: 17, max: 16 })
+    );
+    assert_eq!(
+        BitList::<typenum::U70>::from_ssz_bytes_with_config(&[0xff, 0xff, 0x03], &config),
+        Err(DecodeError::ListItemsLimitExceeded { len: 17, max: 16 })
+    );
+}
+
+// a decode-only view of a fixed container, with a field filled in by the caller, which takes
+// its layout from SszbType
+#[derive(PartialEq, Debug, SszbDecode, SszbType)]
+struct FixedSkipDecode {
+    a: u16,
+    #[ssz(skip_decode)]
+    cached: Option<u64>,
+    b: u32,
+}
+
+// an encode-only container
+#[allow(dead_code)]
+#[derive(SszbEncode)]
+struct EncodeOnly {
+    a: u16,
+    #[ssz(skip_encode)]
+    label: &'static str,
+    b: List<u16, C>,
+}
+
+fn unknown_root() -> [u8; 4] {
+    [0xff; 4]
+}
+
+#[derive(PartialEq, Debug, SszbDecode, SszbEncode, SszbArbitrary)]
+struct VariableSkip {
+    a: u16,
+    #[ssz(skip, default)]
+    cached: u64,
+    b: List<u16, C>,
+    #[ssz(skip, default = "unknown_root")]
+    root: [u8; 4],
+    #[ssz(max = 4)]
+    c: Vec<u8>,
+}
+
+#[test]
+fn test_skipped_fields() {
+    let fixed = FixedSkipDecode::from_ssz_bytes(&[1, 0, 2, 0, 0, 0]).unwrap();
+    assert_eq!(
+        fixed,
+        FixedSkipDecode {
+            a: 1,
+            cached: None,
+            b: 2
+        }
+    );
+    assert!(FixedSkipDecode::from_ssz_bytes(&[1, 0, 2, 0, 0]).is_err());
+    assert!(<FixedSkipDecode as SszType>::is_ssz_static());
+    assert_eq!(<FixedSkipDecode as SszType>::ssz_fixed_len(), 6);
+    assert_eq!(
+        <FixedSkipDecode as sszb::SszLayout>::ssz_fields()
+            .iter()
+            .map(|field| field.name)
+            .collect::<Vec<_>>(),
+        vec!["a", "b"]
+    );
+
+    let encode_only = EncodeOnly {
+        a: 1,
+        label: "unused",
+        b: List::new(vec![2]).unwrap(),
+    };
+    assert_eq!(
+        SszEncode::to_ssz(&encode_only),
+        vec![1, 0, 6, 0, 0, 0, 2, 0]
+    );
+    assert_eq!(encode_only.ssz_bytes_len(), 8);
+    assert_eq!(
+        <EncodeOnly as sszb::SszLayout>::ssz_fields()
+            .iter()
+            .map(|field| field.name)
+            .collect::<Vec<_>>(),
+        vec!["a", "b"]
+    );
+
+    let variable = VariableSkip {
+        a: 1,
+        cached: 7,
+        b: List::new(vec![2, 3]).unwrap(),
+        root: [1, 2, 3, 4],
+        c: vec![4],
+    };
+    let bytes = SszEncode::to_ssz(&variable);
+    assert_eq!(bytes, vec![1, 0, 10, 0, 0, 0, 14, 0, 0, 0, 2, 0, 3, 0, 4]);
+    assert_eq!(variable.ssz_bytes_len(), bytes.len());
+    assert_eq!(
+        VariableSkip::from_ssz_bytes(&bytes).unwrap(),
+        VariableSkip {
+            cached: 0,
+            root: [0xff; 4],
+            ..variable
+        }
+    );
+    assert_eq!(
+        <VariableSkip as sszb::SszLayout>::ssz_fields()
+            .iter()
+            .map(|field| field.name)
+            .collect::<Vec<_>>(),
+        vec!["a", "b", "c"]
+    );
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+struct Slot(u64);
+
+mod slot {
+    pub type Ssz = u64;
+
+    pub fn to_ssz(slot: &super::Slot) -> u64 {
+        slot.0
+    }
+
+    pub fn from_ssz(slot: u64) -> Result<super::Slot, sszb::DecodeError> {
+        Ok(super::Slot(slot))
+    }
+}
+
+// u64::MAX is the far future epoch, kept as None in memory
+mod far_future_epoch {
+    pub type Ssz = u64;
+
+    pub fn to_ssz(epoch: &Option<u64>) -> u64 {
+        epoch.unwrap_or(u64::MAX)
+    }
+
+    pub fn from_ssz(epoch: u64) -> Result<Option<u64>, sszb::DecodeError> {
+        Ok((epoch != u64::MAX).then_some(epoch))
+    }
+}
+
+mod utf8_graffiti {
+    pub type Ssz = sszb::ByteList<typenum::U32>;
+
+    pub fn to_ssz(graffiti: &str) -> Ssz {
+        sszb::ByteList::new(graffiti.as_bytes().to_vec()).expect("graffiti is at most 32 bytes")
+    }
+
+    pub fn from_ssz(graffiti: Ssz) -> Result<String, sszb::DecodeError> {
+        String::from_utf8(graffiti.to_vec())
+            .map_err(|e| sszb::DecodeError::BytesInvalid(e.to_string()))
+    }
+}
+
+#[derive(PartialEq, Debug, SszbDecode, SszbEncode, SszbArbitrary, sszb_derive::SszbSerde)]
+struct CodecFields {
+    #[ssz(with = "slot")]
+    slot: Slot,
+    #[ssz(with = "far_future_epoch")]
+    exit_epoch: Option<u64>,
+    #[ssz(with = "utf8_graffiti")]
+    graffiti: String,
+    balance: u64,
+}
+
+#[test]
+fn test_field_codecs() {
+    let value = CodecFields {
+        slot: Slot(3),
+        exit_epoch: None,
+        graffiti: "sszb".to_string(),
+        balance: 32,
+    };
+    let bytes = SszEncode::to_ssz(&value);
+    let mut expected = vec![3, 0, 0, 0, 0, 0, 0, 0];
+    expected.extend([0xff; 8]);
+    expected.extend([28, 0, 0, 0, 32, 0, 0, 0, 0, 0, 0, 0]);
+    expected.extend(b"sszb");
+    assert_eq!(bytes, expected);
+    assert_eq!(value.ssz_bytes_len(), bytes.len());
+    assert_encode_decode(&value, &bytes);
+
+    let value = CodecFields {
+        exit_epoch: Some(5),
+        ..value
+    };
+    assert_eq!(CodecFields::from_ssz_bytes(&value.to_ssz()).unwrap(), value);
+
+    // the layout and lengths are those of the encoded types
+    assert_eq!(
+        <CodecFields as sszb::SszLayout>::ssz_fields()
+            .iter()
+            .map(|field| field.fixed_len)
+            .collect::<Vec<_>>(),
+        vec![8, 8, 4, 8]
+    );
+    assert!(!<CodecFields as SszType>::is_ssz_static());
+
+    // errors of the codec fail the decoding
+    let mut invalid = bytes.clone();
+    *invalid.last_mut().unwrap() = 0xff;
+    assert!(matches!(
+        CodecFields::from_ssz_bytes(&invalid),
+        Err(DecodeError::BytesInvalid(_))
+    ));
+
+    let json = serde_json::to_value(&value).unwrap();
+    assert_eq!(json["exit_epoch"], serde_json::json!("5"));
+    assert_eq!(json["graffiti"], serde_json::json!("0x73737a62"));
+    assert_eq!(serde_json::from_value::<CodecFields>(json).unwrap(), value);
+}
+
+fn sorted_and_unique(indices: &List<u64, typenum::U8>) -> Result<(), String> {
+    if indices.iter().tuple_windows().all(|(a, b)| a < b) {
+        Ok(())
+    } else {
+        Err("indices are not sorted and unique".to_string())
+    }
+}
+
+#[derive(PartialEq, Debug, SszbDecode, SszbEncode, SszbArbitrary)]
+struct IndexedAttestation {
+    #[ssz(validate = "sorted_and_unique")]
+    attesting_indices: List<u64, typenum::U8>,
+    slot: u64,
+}
+
+#[derive(PartialEq, Debug, SszbDecode, SszbEncode, SszbArbitrary)]
+#[ssz(validate = "Withdrawal::check_indices")]
+struct Withdrawal {
+    index: u64,
+    validator_index: u64,
+}
+
+impl Withdrawal {
+    fn check_indices(&self) -> Result<(), &'static str> {
+        if self.validator_index < 1 << 40 && self.index < 1 << 40 {
+            Ok(())
+        } else {
+            Err("index out of range")
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, SszbDecode, SszbEncode, SszbArbitrary)]
+struct ValidatedBody {
+    attestations: ssz_types::VariableList<IndexedAttestation, typenum::U4>,
+    withdrawal: Withdrawal,
+    attestation: IndexedAttestation,
+}
+
+#[test]
+fn test_validation() {
+    let attestation = IndexedAttestation {
+        attesting_indices: List::new(vec![1, 5, 9]).unwrap(),
+        slot: 3,
+    };
+    assert_encode_decode(&attestation, &attestation.to_ssz());
+
+    let unsorted = IndexedAttestation {
+        attesting_indices: List::new(vec![5, 1]).unwrap(),
+        slot: 3,
+    };
+    assert_eq!(
+        IndexedAttestation::from_ssz_bytes(&unsorted.to_ssz()),
+        Err(DecodeError::ValidationFailed {
+            path: "attesting_indices".to_string(),
+            reason: "indices are not sorted and unique".to_string(),
+        })
+    );
+
+    let withdrawal = Withdrawal {
+        index: 1,
+        validator_index: 1 << 40,
+    };
+    assert_eq!(
+        Withdrawal::from_ssz_bytes(&withdrawal.to_ssz()),
+        Err(DecodeError::ValidationFailed {
+            path: String::new(),
+            reason: "index out of range".to_string(),
+        })
+    );
+
+    // failures in nested containers carry the path from the outermost one
+    let body = ValidatedBody {
+        attestations: ssz_types::VariableList::new(vec![]).unwrap(),
+        withdrawal,
+        attestation,
+    };
+    assert_eq!(
+        ValidatedBody::from_ssz_bytes(&body.to_ssz()),
+        Err(DecodeError::ValidationFailed {
+            path: "withdrawal".to_string(),
+            reason: "index out of range".to_string(),
+        })
+    );
+    let body = ValidatedBody {
+        attestations: ssz_types::VariableList::new(vec![]).unwrap(),
+        withdrawal: Withdrawal {
+            index: 1,
+            validator_index: 2,
+        },
+        attestation: unsorted,
+    };
+    assert!(matches!(
+        ValidatedBody::from_ssz_bytes(&body.to_ssz()),
+        Err(DecodeError::ValidationFailed { path, .. }) if path == "attestation.attesting_indices"
+    ));
+    let body = ValidatedBody {
+        attestations: ssz_types::VariableList::new(vec![body.attestation]).unwrap(),
+        attestation: IndexedAttestation {
+            attesting_indices: List::new(vec![]).unwrap(),
+            slot: 0,
+        },
+        ..body
+    };
+    assert!(matches!(
+        ValidatedBody::from_ssz_bytes(&body.to_ssz()),
+        Err(DecodeError::ValidationFailed { path, .. }) if path == "attestations.attesting_indices"
+    ));
+
+    // generated values that fail the checks are rejected
+    sszb::arbitrary::assert_roundtrip::<IndexedAttestation>();
+}
+
+#[derive(PartialEq, Debug, SszbDecode, SszbEncode, SszbArbitrary, SszbSchema)]
+struct Bitfields {
+    a: sszb::Bitvector<typenum::U10>,
+    b: sszb::Bitlist<typenum::U70>,
+}
+
+#[test]
+fn test_bitfields() {
+    let mut a = sszb::Bitvector::<typenum::U10>::new();
+    a.set(0, true).unwrap();
+    a.set(9, true).unwrap();
+    assert!(a.set(10, true).is_err());
+    assert_encode_decode(&a, &[0b0000_0001, 0b0000_0010]);
+
+    let mut b = sszb::Bitlist::<typenum::U70>::with_capacity(66).unwrap();
+    b.set(1, true).unwrap();
+    b.set(64, true).unwrap();
+    let mut b_bytes = vec![0b0000_0010, 0, 0, 0, 0, 0, 0, 0, 0b0000_0101];
+    assert_encode_decode(&b, &b_bytes);
+    assert_eq!(b.iter_set_indices().collect::<Vec<_>>(), vec![1, 64]);
+    assert_eq!(b.num_set_bits(), 2);
+    assert_eq!(b.highest_set_bit(), Some(64));
+
+    let fields = Bitfields {
+        a: a.clone(),
+        b: b.clone(),
+    };
+    assert_encode_decode(&fields, &SszEncode::to_ssz(&fields));
+
+    // a buffer in several chunks decodes like a slice, also when it is split within a bitfield
+    let fields_bytes = SszEncode::to_ssz(&fields);
+    for split in 0..=b_bytes.len() {
+        let (head, tail) = b_bytes.split_at(split);
+        let mut chain = head.chain(tail);
+        assert_eq!(
+            sszb::Bitlist::<typenum::U70>::from_ssz_buf(&mut chain).unwrap(),
+            b
+        );
+        assert!(!chain.has_remaining());
+    }
+    for split in 0..=fields_bytes.len() {
+        let (head, tail) = fields_bytes.split_at(split);
+        let mut chain = head.chain(tail);
+        assert_eq!(Bitfields::from_ssz_buf(&mut chain).unwrap(), fields);
+    }
+    let mut chain = [0b0000_0001][..].chain(&[0b0000_0010][..]);
+    assert_eq!(
+        sszb::Bitvector::<typenum::U10>::ssz_read(&mut chain, &mut &[][..]).unwrap(),
+        a
+    );
+    assert!(!chain.has_remaining());
+
+    // set operations
+    let mut c = sszb::Bitlist::<typenum::U70>::with_capacity(3).unwrap();
+    c.set(1, true).unwrap();
+    c.set(2, true).unwrap();
+    assert_eq!(b.union(&c).len(), 66);
+    assert_eq!(
+        b.union(&c).iter_set_indices().collect::<Vec<_>>(),
+        vec![1, 2, 64]
+    );
+    assert_eq!(b.intersection(&c).len(), 3);
+    assert_eq!(
+        b.intersection(&c).iter_set_indices().collect::<Vec<_>>(),
+        vec![1]
+    );
+    assert_eq!(
+        b.difference(&c).iter_set_indices().collect::<Vec<_>>(),
+        vec![64]
+    );
+    assert!(!b.is_disjoint(&c));
+    assert!(b.intersection(&c).is_subset(&c));
+
+    // zero-copy views
+    let view = sszb::BitlistView::<typenum::U70>::from_ssz_bytes(&b_bytes).unwrap();
+    assert_eq!(view.len(), 66);
+    assert_eq!(view.num_set_bits(), 2);
+    assert_eq!(view.iter_set_indices().collect::<Vec<_>>(), vec![1, 64]);
+    assert_eq!(view.to_bitlist(), b);
+
+    // strict validation
+    assert!(sszb::Bitvector::<typenum::U10>::from_ssz_bytes(&[0, 0b0000_0100]).is_err());
+    assert!(sszb::Bitvector::<typenum::U10>::from_ssz_bytes(&[0]).is_err());
+    assert!(sszb::Bitlist::<typenum::U70>::from_ssz_bytes(&[]).is_err());
+    assert!(sszb::Bitlist::<typenum::U70>::from_ssz_bytes(&[1, 0]).is_err());
+    b_bytes.push(1);
+    assert!(sszb::Bitlist::<typenum::U70>::from_ssz_bytes(&b_bytes).is_err());
+
+    // interop with ssz_types
+    let ssz_types_b: BitList<typenum::U70> = b.clone().into();
+    assert_eq!(ssz_types_b.len(), 66);
+    assert!(ssz_types_b.get(64).unwrap());
+    assert_eq!(sszb::Bitlist::from(ssz_types_b), b);
+    let ssz_types_a: ssz_types::BitVector<typenum::U10> = a.clone().into();
+    assert_eq!(sszb::Bitvector::from(ssz_types_a), a);
+}
+
+#[derive(PartialEq, Debug, SszbDecode, SszbEncode, sszb_derive::SszbSerde)]
+struct SpecJson {
+    slot: u64,
+    root: alloy_primitives::B256,
+    pubkey: sszb::PKBytes,
+    bits: BitList<typenum::U16>,
+    flags: sszb::Bitvector<typenum::U4>,
+    extra_data: sszb::ByteList<typenum::U32>,
+    balances: List<u64, typenum::U8>,
+    union: Option<u16>,
+}
+
+#[test]
+fn test_spec_serde() {
+    let mut bits = BitList::<typenum::U16>::with_capacity(3).unwrap();
+    bits.set(0, true).unwrap();
+    let mut flags = sszb::Bitvector::<typenum::U4>::new();
+    flags.set(1, true).unwrap();
+    let value = SpecJson {
+        slot: 12,
+        root: alloy_primitives::B256::repeat_byte(0xab),
+        pubkey: sszb::PKBytes::from_ssz_bytes(&[0x11; 48]).unwrap(),
+        bits,
+        flags,
+        extra_data: sszb::ByteList::new(vec![1, 2]).unwrap(),
+        balances: List::try_from_iter([32_000_000_000, 1]).unwrap(),
+        union: None,
+    };
+
+    let json = serde_json::to_value(&value).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "slot": "12",
+            "root": format!("0x{}", "ab".repeat(32)),
+            "pubkey": format!("0x{}", "11".repeat(48)),
+            "bits": "0x09",
+            "flags": "0x02",
+            "extra_data": "0x0102",
+            "balances": ["32000000000", "1"],
+            "union": null,
+        })
+    );
+    assert_eq!(serde_json::from_value::<SpecJson>(json).unwrap(), value);
+
+    // YAML round-trips too, and plain integers are accepted for uints
+    let yaml = serde_yaml::to_string(&value).unwrap();
+    assert_eq!(serde_yaml::from_str::<SpecJson>(&yaml).unwrap(), value);
+    let mut json = serde_json::to_value(&value).unwrap();
+    json["slot"] = serde_json::json!(12);
+    json["union"] = serde_json::json!("7");
+    let decoded = serde_json::from_value::<SpecJson>(json).unwrap();
+    assert_eq!(decoded.slot, 12);
+    assert_eq!(decoded.union, Some(7));
+
+    // bounds and bitfield validity are checked
+    let mut json = serde_json::to_value(&value).unwrap();
+    json["bits"] = serde_json::json!("0x00");
+    assert!(serde_json::from_value::<SpecJson>(json).is_err());
+    let mut json = serde_json::to_value(&value).unwrap();
+    json["balances"] = serde_json::json!(vec!["1"; 9]);
+    assert!(serde_json::from_value::<SpecJson>(json).is_err());
+}
+
+// phase0 containers, for checking length bounds against the values of the consensus specs
+mod phase0 {
+    use bytes::buf::{Buf, BufMut};
+    use ssz_types::{BitList, VariableList};
+    use sszb_derive::{SszbDecode, SszbEncode, SszbSchema};
+    use typenum::{U1024, U2048};
+
+    #[derive(SszbEncode, SszbDecode, SszbSchema)]
+    pub struct Checkpoint {
+        pub epoch: u64,
+        pub root: [u8; 32],
+    }
+
+    #[derive(SszbEncode, SszbDecode, SszbSchema)]
+    pub struct AttestationData {
+        pub slot: u64,
+        pub index: u64,
+        pub beacon_block_root: [u8; 32],
+        pub source: Checkpoint,
+        pub target: Checkpoint,
+    }
+
+    #[derive(SszbEncode, SszbDecode, SszbSchema)]
+    pub struct Attestation {
+        pub aggregation_bits: BitList<U2048>,
+        pub data: AttestationData,
+        pub signature: [u8; 96],
+    }
+
+    #[derive(SszbEncode, SszbDecode, SszbSchema)]
+    pub struct PendingAttestation {
+        pub aggregation_bits: BitList<U2048>,
+        pub data: AttestationData,
+        pub inclusion_delay: u64,
+        pub proposer_index: u64,
+    }
+
+    #[derive(SszbEncode, SszbDecode, SszbSchema)]
+    pub struct IndexedAttestation {
+        pub attesting_indices: VariableList<u64, U2048>,
+        pub data: AttestationData,
+        pub signature: [u8; 96],
+    }
+
+    #[derive(SszbEncode, SszbDecode, SszbSchema)]
+    pub struct AttesterSlashing {
+        pub attestation_1: IndexedAttestation,
+        pub attestation_2: IndexedAttestation,
+    }
+
+    #[derive(SszbEncode, SszbDecode, SszbSchema)]
+    pub struct Status {
+        pub fork_digest: [u8; 4],
+        pub finalized_root: [u8; 32],
+        pub finalized_epoch: u64,
+        pub head_root: [u8; 32],
+        pub head_slot: u64,
+    }
+
+    pub type BeaconBlocksByRootRequest = VariableList<[u8; 32], U1024>;
+}
+
+fn len_bounds<T: SszType>() -> (usize, usize) {
+    (T::ssz_min_len(), T::ssz_max_len())
+}
+
+#[test]
+fn test_len_bounds() {
+    // phase0 containers
+    assert_eq!(len_bounds::<phase0::AttestationData>(), (128, 128));
+    assert_eq!(len_bounds::<phase0::Attestation>(), (229, 485));
+    assert_eq!(len_bounds::<phase0::PendingAttestation>(), (149, 405));
+    assert_eq!(len_bounds::<phase0::IndexedAttestation>(), (228, 16612));
+    assert_eq!(len_bounds::<phase0::AttesterSlashing>(), (464, 33232));
+    assert_eq!(len_bounds::<phase0::Status>(), (84, 84));
+    assert_eq!(
+        len_bounds::<phase0::BeaconBlocksByRootRequest>(),
+        (0, 32768)
+    );
+
+    // bitlists hold their length bit even when empty or full
+    assert_eq!(len_bounds::<BitList<typenum::U2048>>(), (1, 257));
+    assert_eq!(len_bounds::<BitList8>(), (1, 2));
+    assert_eq!(len_bounds::<sszb::Bitlist<typenum::U70>>(), (1, 9));
+    assert_eq!(len_bounds::<sszb::Bitvector<typenum::U10>>(), (2, 2));
+
+    // variable items take up an offset each, in vectors, lists, arrays and tuples
+    assert_eq!(len_bounds::<List<u16, C>>(), (0, 20));
+    assert_eq!(
+        len_bounds::<ssz_types::FixedVector<List<u16, C>, typenum::U3>>(),
+        (12, 72)
+    );
+    assert_eq!(
+        len_bounds::<ssz_types::VariableList<List<u16, C>, typenum::U3>>(),
+        (0, 72)
+    );
+    assert_eq!(len_bounds::<[List<u16, C>; 2]>(), (8, 48));
+    assert_eq!(len_bounds::<(u8, List<u16, C>, u32)>(), (9, 29));
+    assert_eq!(len_bounds::<Option<List<u16, C>>>(), (1, 21));
+    assert_eq!(len_bounds::<Box<Option<u64>>>(), (1, 9));
+
+    // containers add up the offsets of their variable fields
+    assert_eq!(len_bounds::<VariableB>(), (6, 26));
+    assert_eq!(len_bounds::<VariableC>(), (7, 8));
+    assert_eq!(len_bounds::<OptionalFields>(), (12, 2 + 4 + 9 + 4 + 21));
+    assert_eq!(
+        len_bounds::<ArrayFields>(),
+        (32 + 4 + 8 + 4 + 9, 32 + 4 + 48 + 4 + 29)
+    );
+    assert_eq!(len_bounds::<Payload>(), (10, 2 + (4 + 32) + (4 + 8)));
+
+    // lists without a maximum length saturate
+    assert_eq!(len_bounds::<Vec<u8>>(), (0, usize::MAX));
+    assert_eq!(len_bounds::<(u8, Vec<u8>)>(), (5, usize::MAX));
+    assert_eq!(len_bounds::<[Vec<u8>; 2]>(), (8, usize::MAX));
+    assert_eq!(
+        <ssz_types::VariableList<Vec<u8>, typenum::U4> as SszType>::ssz_max_len(),
+        usize::MAX
+    );
+}
+
+#[test]
+fn test_roundtrip_properties() {
+    use sszb::arbitrary::assert_roundtrip;
+
+    assert_roundtrip::<VariableA>();
+    assert_roundtrip::<alloy_primitives::U256>();
+    assert_roundtrip::<ssz_types::VariableList<u64, typenum::U16>>();
+    assert_roundtrip::<ssz_types::BitVector<typenum::U70>>();
+}
+
+#[test]
+fn test_roundtrip_properties_variable_items() {
+    use sszb::arbitrary::assert_roundtrip;
+
+    assert_roundtrip::<VariableB>();
+    assert_roundtrip::<VariableC>();
+    assert_roundtrip::<OptionalFields>();
+    assert_roundtrip::<ArrayFields>();
+    assert_roundtrip::<RuntimeLists>();
+    assert_roundtrip::<Payload>();
+    assert_roundtrip::<Bitfields>();
+    assert_roundtrip::<ssz_types::FixedVector<List<u16, C>, typenum::U3>>();
+    assert_roundtrip::<BitList<typenum::U2048>>();
+    assert_roundtrip::<CodecFields>();
+}
+
+// values at their maximum length, which arbitrary ones rarely reach: the offsets of variable
+// items and the length bit of bitlists count towards ssz_max_len
+#[test]
+fn test_roundtrip_at_max_len() {
+    use sszb::arbitrary::assert_value_roundtrips;
+    type Lists = ssz_types::FixedVector<List<u16, C>, typenum::U3>;
+
+    let full = List::<u16, C>::try_from_iter(0..N).unwrap();
+    let lists = Lists::new(vec![full.clone(), full.clone(), full.clone()]).unwrap();
+    assert_eq!(lists.ssz_bytes_len(), <Lists as SszType>::ssz_max_len());
+    assert_value_roundtrips(&lists);
+
+    let var_b = VariableB { a: 1, b: full };
+    assert_eq!(var_b.ssz_bytes_len(), <VariableB as SszType>::ssz_max_len());
+    assert_value_roundtrips(&var_b);
+
+    let mut bits = BitList::<typenum::U2048>::with_capacity(2048).unwrap();
+    bits.set(2047, true).unwrap();
+    assert_eq!(
+        bits.ssz_bytes_len(),
+        <BitList<typenum::U2048> as SszType>::ssz_max_len()
+    );
+    assert_value_roundtrips(&bits);
+}
+
+#[test]
+fn test_arbitrary_respects_bounds() {
+    use sszb::arbitrary::Unstructured;
+    use sszb::SszArbitrary;
+
+    // plenty of input, so only the bounds limit the lengths
+    let input = (0..=255).cycle().take(1 << 16).collect::<Vec<u8>>();
+    let mut u = Unstructured::new(&input);
+    for _ in 0..64 {
+        let value = RuntimeLists::ssz_arbitrary(&mut u).unwrap();
+        assert!(value.b.len() <= 4);
+        assert!(value.c.len() <= 2);
+        assert!(value.c.iter().all(|list| list.len() <= 10));
+
+        let bits = sszb::Bitlist::<typenum::U70>::ssz_arbitrary(&mut u).unwrap();
+        assert!(bits.len() <= 70);
+    }
+
+    // running out of input gives the smallest values rather than failing
+    let value = Bitfields::ssz_arbitrary(&mut Unstructured::new(&[])).unwrap();
+    assert!(value.a.is_zero());
+    assert!(value.b.is_empty());
+}
+
+fn field_schemas<T: SszReflect>() -> Vec<String> {
+    match T::ssz_schema() {
+        SszSchema::Container { fields, .. } => fields
+            .iter()
+            .map(|(name, schema)| format!("{}: {}", name, schema))
+            .collect(),
+        schema => panic!("{} is not a container", schema),
+    }
+}
+
+// arbitrary values of T decode into an SszValue that encodes back to the same bytes
+fn assert_dynamic_roundtrip<T: SszEncode + SszReflect + sszb::SszArbitrary>() {
+    let schema = T::ssz_schema();
+    let input = (0..1u32 << 16)
+        .map(|i| (i.wrapping_mul(2654435761) >> 13) as u8)
+        .collect::<Vec<_>>();
+    let mut u = sszb::arbitrary::Unstructured::new(&input);
+    for _ in 0..32 {
+        let bytes = T::ssz_arbitrary(&mut u).unwrap().to_ssz();
+        let value = SszValue::from_ssz_bytes(&schema, &bytes).unwrap();
+        assert_eq!(value.to_ssz(&schema).unwrap(), bytes);
+    }
+}
+
+#[test]
+fn test_schema() {
+    assert_eq!(
+        field_schemas::<phase0::Attestation>(),
+        [
+            "aggregation_bits: Bitlist[2048]",
+            "data: AttestationData",
+            "signature: Vector[uint8, 96]",
+        ]
+    );
+    assert_eq!(
+        field_schemas::<phase0::IndexedAttestation>()[0],
+        "attesting_indices: List[uint64, 2048]"
+    );
+    assert_eq!(
+        field_schemas::<OptionalFields>(),
+        [
+            "a: uint16",
+            "b: Union[None, uint64]",
+            "c: Union[None, List[uint16, 10]]",
+        ]
+    );
+    assert_eq!(
+        field_schemas::<ArrayFields>(),
+        [
+            "a: Vector[uint64, 4]",
+            "b: Vector[List[uint16, 10], 2]",
+            "c: Container[uint8, List[uint16, 10], uint32]",
+        ]
+    );
+    // lists bounded by #[ssz(max = N)] and pointers
+    assert_eq!(
+        field_schemas::<RuntimeLists>(),
+        [
+            "a: uint16",
+            "b: List[uint32, 4]",
+            "c: List[List[uint16, 10], 2]",
+        ]
+    );
+    assert_eq!(
+        field_schemas::<Payload>(),
+        [
+            "a: uint16",
+            "extra_data: List[uint8, 32]",
+            "el_data: List[uint8, 8]",
+        ]
+    );
+
+    // the schema agrees with the typed layout
+    let schema = phase0::AttesterSlashing::ssz_schema();
+    assert!(!schema.is_static());
+    assert_eq!(phase0::AttestationData::ssz_schema().fixed_len(), 128);
+    assert_eq!(Bitfields::ssz_schema().fixed_len(), 4);
+}
+
+// the derived schemas decode and encode values like the derived impls
+#[test]
+fn test_schema_roundtrip() {
+    assert_dynamic_roundtrip::<VariableB>();
+    assert_dynamic_roundtrip::<OptionalFields>();
+    assert_dynamic_roundtrip::<ArrayFields>();
+    assert_dynamic_roundtrip::<RuntimeLists>();
+    assert_dynamic_roundtrip::<Payload>();
+    assert_dynamic_roundtrip::<Bitfields>();
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum ForkName {
+    Phase0,
+    Altair,
+    Bellatrix,
+    Deneb,
+    Electra,
+}
+
+// only the per-fork containers are used
+#[allow(dead_code)]
+#[derive(SszbForks)]
+#[ssz(
+    forks(Phase0, Altair, Deneb, Electra),
+    fork_name = ForkName,
+    variant_derives(Clone, PartialEq, Debug)
+)]
+struct Body {
+    slot: u64,
+    #[ssz(forks(Altair, Deneb, Electra))]
+    sync_bits: sszb::Bitvector<typenum::U8>,
+    #[ssz(forks(Deneb, Electra))]
+    commitments: List<u16, C>,
+    #[ssz(forks(Electra))]
+    requests: List<u32, C>,
+}
+
+// the options of a forks container may be spread over several attributes
+#[allow(dead_code)]
+#[derive(SszbForks)]
+#[ssz(forks(Phase0, Altair), fork_name = ForkName)]
+#[ssz(variant_derives(Clone, PartialEq, Debug), validate = "nonzero_slot")]
+struct Header {
+    slot: u64,
+    #[ssz(forks(Altair))]
+    proposer: u16,
+}
+
+fn nonzero_slot<T: SszEncode>(header: &T) -> Result<(), &'static str> {
+    match header.to_ssz()[..8] == [0; 8] {
+        true => Err("zero slot"),
+        false => Ok(()),
+    }
+}
+
+// the containers of each fork hold the body and header of that fork
+#[allow(dead_code)]
+#[derive(SszbForks)]
+#[ssz(
+    forks(Phase0, Altair),
+    fork_name = ForkName,
+    variant_derives(Clone, PartialEq, Debug)
+)]
+struct SignedBody {
+    #[ssz(by_fork)]
+    body: Body,
+    #[ssz(by_fork)]
+    header: Header,
+    signature: [u8; 2],
+}
+
+#[test]
+fn test_forks() {
+    let phase0 = BodyPhase0 { slot: 1 };
+    assert_encode_decode(&phase0, &[1, 0, 0, 0, 0, 0, 0, 0]);
+
+    let deneb = BodyDeneb {
+        slot: 2,
+        sync_bits: sszb::Bitvector::new(),
+        commitments: List::try_from_iter([5, 6]).unwrap(),
+    };
+    let bytes = SszEncode::to_ssz(&deneb);
+    assert_eq!(
+        bytes,
+        vec![2, 0, 0, 0, 0, 0, 0, 0, 0, 13, 0, 0, 0, 5, 0, 6, 0]
+    );
+    assert_encode_decode(&deneb, &bytes);
+
+    // the wrapper encodes as the container it holds, and decodes by fork
+    let body = BodyVariant::from(deneb.clone());
+    assert_eq!(body.fork_name(), ForkName::Deneb);
+    assert_eq!(body.to_ssz(), bytes);
+    assert_eq!(body.ssz_bytes_len(), bytes.len());
+    assert_eq!(
+        BodyVariant::from_ssz_bytes_by_fork(&bytes, ForkName::Deneb),
+        Ok(body.clone())
+    );
+    assert_eq!(
+        BodyVariant::from_ssz_bytes_by_fork(&bytes[..9], ForkName::Altair),
+        Ok(BodyVariant::Altair(BodyAltair {
+            slot: 2,
+            sync_bits: sszb::Bitvector::new(),
+        }))
+    );
+    assert!(BodyVariant::from_ssz_bytes_by_fork(&bytes, ForkName::Electra).is_err());
+    assert!(matches!(
+        BodyVariant::from_ssz_bytes_by_fork(&bytes, ForkName::Bellatrix),
+        Err(DecodeError::BytesInvalid(_))
+    ));
+
+    // fields of every fork have plain getters, the others return an Option
+    assert_eq!(*body.slot(), 2);
+    assert_eq!(body.commitments(), Some(&deneb.commitments));
+    assert_eq!(body.requests(), None);
+    assert_eq!(BodyVariant::Phase0(phase0).sync_bits(), None);
+
+    // by_fork fields hold the container of their fork, which is laid out in place even though
+    // the containers of the other forks have different sizes
+    let altair = SignedBodyAltair {
+        body: BodyAltair {
+            slot: 2,
+            sync_bits: sszb::Bitvector::new(),
+        },
+        header: HeaderAltair {
+            slot: 3,
+            proposer: 4,
+        },
+        signature: [7, 8],
+    };
+    assert!(<SignedBodyAltair as SszType>::is_ssz_static());
+    let bytes = SszEncode::to_ssz(&altair);
+    assert_eq!(
+        bytes,
+        vec![2, 0, 0, 0, 0, 0, 0, 0, 0, 3, 0, 0, 0, 0, 0, 0, 0, 4, 0, 7, 8]
+    );
+    let signed = SignedBodyVariant::from_ssz_bytes_by_fork(&bytes, ForkName::Altair);
+    assert_eq!(signed, Ok(SignedBodyVariant::Altair(altair)));
+    assert_eq!(signed.unwrap().signature(), &[7, 8]);
+    let phase0 = SignedBodyPhase0 {
+        body: BodyPhase0 { slot: 2 },
+        header: HeaderPhase0 { slot: 3 },
+        signature: [7, 8],
+    };
+    let bytes = SszEncode::to_ssz(&phase0);
+    assert_eq!(bytes.len(), 8 + 8 + 2);
+    assert_eq!(
+        SignedBodyVariant::from_ssz_bytes_by_fork(&bytes, ForkName::Phase0),
+        Ok(SignedBodyVariant::Phase0(phase0))
+    );
+    assert_eq!(
+        SignedBodyVariant::from_ssz_bytes_by_fork(&bytes, ForkName::Deneb),
+        Err(DecodeError::BytesInvalid(
+            "SignedBodyVariant has no variant for fork Deneb".to_string()
+        ))
+    );
+
+    // the container validate applies to every variant
+    assert_eq!(
+        HeaderVariant::from_ssz_bytes_by_fork(&[0; 10], ForkName::Altair),
+        Err(DecodeError::ValidationFailed {
+            path: String::new(),
+            reason: "zero slot".to_string(),
+        })
+    );
+}
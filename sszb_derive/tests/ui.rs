@@ -0,0 +1,7 @@
+// locks in the diagnostics of the derives for inputs they do not support,
+// run with TRYBUILD=overwrite to update the expected output in tests/ui
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
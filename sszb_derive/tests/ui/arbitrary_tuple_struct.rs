@@ -0,0 +1,6 @@
+use sszb_derive::SszbArbitrary;
+
+#[derive(SszbArbitrary)]
+struct Root([u8; 32]);
+
+fn main() {}
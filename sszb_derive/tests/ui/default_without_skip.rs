@@ -0,0 +1,14 @@
+use sszb_derive::SszbDecode;
+
+fn genesis_root() -> [u8; 32] {
+    [0; 32]
+}
+
+#[derive(SszbDecode)]
+struct Checkpoint {
+    epoch: u64,
+    #[ssz(default = "genesis_root")]
+    root: [u8; 32],
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use sszb_derive::SszbEncode;
+
+#[derive(SszbEncode)]
+struct Payload {
+    #[ssz(max = 4)]
+    #[ssz(max = 8)]
+    transactions: Vec<u8>,
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+use sszb_derive::SszbDecode;
+
+#[derive(SszbDecode)]
+struct Empty {}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use sszb_derive::SszbEncode;
+
+#[derive(SszbEncode)]
+enum Fork {
+    Phase0,
+    Altair,
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use sszb_derive::SszbForks;
+
+enum ForkName {
+    Phase0,
+}
+
+#[derive(SszbForks)]
+#[ssz(forks(Phase0), fork_name = ForkName)]
+struct Block {
+    slot: u64,
+    #[ssz(by_fork)]
+    body: [u8; 4],
+}
+
+fn main() {}
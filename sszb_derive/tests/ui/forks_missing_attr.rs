@@ -0,0 +1,8 @@
+use sszb_derive::SszbForks;
+
+#[derive(SszbForks)]
+struct Body {
+    slot: u64,
+}
+
+fn main() {}
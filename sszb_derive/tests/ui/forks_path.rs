@@ -0,0 +1,9 @@
+use sszb_derive::SszbForks;
+
+#[derive(SszbForks)]
+#[ssz(forks(forks::Phase0), fork_name = ForkName)]
+struct Body {
+    slot: u64,
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+use sszb_derive::SszbForks;
+
+enum ForkName {
+    Phase0,
+    Altair,
+}
+
+#[derive(SszbForks)]
+#[ssz(forks(Phase0, Altair), fork_name = ForkName)]
+struct Body {
+    slot: u64,
+    #[ssz(forks(Altiar))]
+    sync_bits: u8,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use sszb_derive::SszbDecode;
+
+#[derive(SszbDecode)]
+struct Payload {
+    #[ssz(max)]
+    transactions: Vec<u8>,
+    #[ssz(skip_decode = "yes")]
+    extra_data: u64,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use sszb_derive::{SszbDecode, SszbEncode};
+
+#[derive(SszbEncode, SszbDecode)]
+struct Checkpoint {
+    epoch: u64,
+    #[ssz(skip_encode)]
+    root: [u8; 32],
+    #[ssz(skip_decode)]
+    cached: u64,
+}
+
+fn main() {}
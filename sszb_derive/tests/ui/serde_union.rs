@@ -0,0 +1,9 @@
+use sszb_derive::SszbSerde;
+
+#[derive(SszbSerde)]
+union Word {
+    a: u64,
+    b: [u8; 8],
+}
+
+fn main() {}
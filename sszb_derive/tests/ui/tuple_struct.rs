@@ -0,0 +1,6 @@
+use sszb_derive::SszbDecode;
+
+#[derive(SszbDecode)]
+struct Checkpoint(u64, [u8; 32]);
+
+fn main() {}
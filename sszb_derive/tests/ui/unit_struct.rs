@@ -0,0 +1,6 @@
+use sszb_derive::SszbEncode;
+
+#[derive(SszbEncode)]
+struct Empty;
+
+fn main() {}
@@ -0,0 +1,13 @@
+use sszb_derive::SszbDecode;
+
+fn check(_: &Withdrawal) -> Result<(), String> {
+    Ok(())
+}
+
+#[derive(SszbDecode)]
+#[ssz(validator = "check")]
+struct Withdrawal {
+    index: u64,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use sszb_derive::SszbEncode;
+
+#[derive(SszbEncode)]
+struct Checkpoint {
+    #[ssz(skip_encod)]
+    epoch: u64,
+    #[ssz(maximum = 4)]
+    roots: Vec<[u8; 32]>,
+}
+
+fn main() {}
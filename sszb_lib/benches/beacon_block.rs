@@ -7,7 +7,6 @@ use sszb_derive::{SszbDecode, SszbEncode};
 use tree_hash::*;
 use tree_hash_derive::TreeHash;
 
-type ByteList<N> = List<u8, N>;
 type ByteVector<N> = FixedVector<u8, N>;
 pub type SignatureBytes = Sig; // ByteVector<typenum::U96>;
 type PublicKeyBytes = PKBytes; // [u8; 48];
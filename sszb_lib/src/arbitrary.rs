@@ -0,0 +1,381 @@
+//! Generation of arbitrary valid SSZ values, for property-based and fuzz testing.
+//!
+//! [`SszArbitrary`] builds a value of a type from unstructured bytes (see the `arbitrary` crate).
+//! Unlike `arbitrary::Arbitrary`, the values it builds are always valid SSZ: lists never hold
+//! more than their maximum length `N`, and vectors and bitvectors always hold exactly `N` items.
+//! `#[derive(SszbArbitrary)]` implements it for a container, bounding fields annotated with
+//! `#[ssz(max = N)]` by `N` and leaving fields skipped by SSZ at their default value.
+//!
+//! [`strategy`] turns any `SszArbitrary` type into a proptest strategy, and [`assert_roundtrip`]
+//! runs the encode/decode properties every type should satisfy against it.
+use crate::{Bitlist, Bitvector, ByteList, SszDecode, SszEncode, SszType};
+#[cfg(feature = "bls")]
+use crate::{PKBytes, Sig};
+#[cfg(feature = "alloy")]
+use alloy_primitives::{Address, Bloom, Bytes as AlloyBytes, FixedBytes, U128, U256};
+#[cfg(feature = "ethereum-types")]
+use ethereum_types::{H160, H256, H32};
+#[cfg(feature = "milhouse")]
+use milhouse::{List as PersistentList, Value, Vector as PersistentVector};
+use proptest::prelude::*;
+use proptest::test_runner::{Config, TestCaseError, TestRunner};
+use ssz_types::{BitList, BitVector, FixedVector, VariableList};
+use std::borrow::Cow;
+use std::fmt::Debug;
+use std::rc::Rc;
+use std::sync::Arc;
+use typenum::Unsigned;
+
+pub use ::arbitrary::{Error, Result, Unstructured};
+
+/// The most input bytes [`strategy`] hands to [`SszArbitrary::ssz_arbitrary`]. Lists are bounded
+/// by the input left, so this also caps their length.
+pub const MAX_INPUT_LEN: usize = 4096;
+
+/// A type whose arbitrary values are valid SSZ values.
+pub trait SszArbitrary: Sized {
+    fn ssz_arbitrary(u: &mut Unstructured<'_>) -> Result<Self>;
+}
+
+/// An arbitrary list length of at most `max`, further bounded by the input left so that large
+/// limits such as the validator registry's do not exhaust memory.
+pub fn arbitrary_len(u: &mut Unstructured<'_>, max: usize) -> Result<usize> {
+    let max = max.min(u.len());
+    if max == 0 {
+        Ok(0)
+    } else {
+        u.int_in_range(0..=max)
+    }
+}
+
+/// An arbitrary list of at most `max` items, used for lists bounded by `#[ssz(max = N)]`.
+pub fn arbitrary_list<T: SszArbitrary>(u: &mut Unstructured<'_>, max: usize) -> Result<Vec<T>> {
+    let len = arbitrary_len(u, max)?;
+    arbitrary_vec(u, len)
+}
+
+/// Exactly `len` arbitrary items.
+pub fn arbitrary_vec<T: SszArbitrary>(u: &mut Unstructured<'_>, len: usize) -> Result<Vec<T>> {
+    (0..len).map(|_| T::ssz_arbitrary(u)).collect()
+}
+
+/// A proptest strategy generating arbitrary valid values of `T`.
+///
+/// Values are built from up to [`MAX_INPUT_LEN`] random bytes, so proptest shrinks failing
+/// values by shrinking those bytes.
+pub fn strategy<T: SszArbitrary + Debug>() -> impl Strategy<Value = T> {
+    proptest::collection::vec(any::<u8>(), 0..MAX_INPUT_LEN)
+        .prop_filter_map("not enough input to build a value", |bytes| {
+            T::ssz_arbitrary(&mut Unstructured::new(&bytes)).ok()
+        })
+}
+
+/// Checks that `value` survives an encode/decode round trip and that its encoded length agrees
+/// with `ssz_bytes_len` and lies within `ssz_min_len` and `ssz_max_len`.
+pub fn check_roundtrip<T>(value: &T) -> std::result::Result<(), String>
+where
+    T: SszEncode + SszDecode + PartialEq + Debug,
+{
+    let bytes = value.to_ssz();
+    if value.ssz_bytes_len() != bytes.len() {
+        return Err(format!(
+            "ssz_bytes_len is {} but the encoding is {} bytes",
+            value.ssz_bytes_len(),
+            bytes.len()
+        ));
+    }
+    if value.ssz_bytes_len() < <T as SszType>::ssz_min_len() {
+        return Err(format!(
+            "ssz_bytes_len {} is below ssz_min_len {}",
+            value.ssz_bytes_len(),
+            <T as SszType>::ssz_min_len()
+        ));
+    }
+    if value.ssz_bytes_len() > <T as SszType>::ssz_max_len() {
+        return Err(format!(
+            "ssz_bytes_len {} exceeds ssz_max_len {}",
+            value.ssz_bytes_len(),
+            <T as SszType>::ssz_max_len()
+        ));
+    }
+    match T::from_ssz_bytes(&bytes) {
+        Ok(decoded) if decoded == *value => Ok(()),
+        Ok(decoded) => Err(format!("decoded {:?}", decoded)),
+        Err(e) => Err(format!("decoding failed with {:?}", e)),
+    }
+}
+
+/// Panics if `value` fails [`check_roundtrip`].
+pub fn assert_value_roundtrips<T>(value: &T)
+where
+    T: SszEncode + SszDecode + PartialEq + Debug,
+{
+    if let Err(e) = check_roundtrip(value) {
+        panic!("round trip of {:?} failed: {}", value, e);
+    }
+}
+
+/// Runs [`check_roundtrip`] against arbitrary values of `T`, panicking with the smallest
+/// failing value if any fails.
+pub fn assert_roundtrip<T>()
+where
+    T: SszArbitrary + SszEncode + SszDecode + PartialEq + Debug,
+{
+    // failures are reported by the panic, there is no source file to persist them next to
+    let config = Config {
+        failure_persistence: None,
+        ..Config::default()
+    };
+    let result = TestRunner::new(config).run(&strategy::<T>(), |value| {
+        check_roundtrip(&value).map_err(TestCaseError::fail)
+    });
+    if let Err(e) = result {
+        panic!("{}", e);
+    }
+}
+
+macro_rules! via_arbitrary {
+    ($($type: ty),*) => {
+        $(
+            impl SszArbitrary for $type {
+                fn ssz_arbitrary(u: &mut Unstructured<'_>) -> Result<Self> {
+                    u.arbitrary()
+                }
+            }
+        )*
+    };
+}
+
+via_arbitrary!(u8, u16, u32, u64, u128, bool);
+
+// fixed-size byte types are built from byte arrays of their size
+#[cfg(any(feature = "alloy", feature = "ethereum-types"))]
+macro_rules! from_bytes_arbitrary {
+    ($type: ty, $len: expr) => {
+        impl SszArbitrary for $type {
+            fn ssz_arbitrary(u: &mut Unstructured<'_>) -> Result<Self> {
+                u.arbitrary::<[u8; $len]>().map(<$type>::from)
+            }
+        }
+    };
+}
+
+#[cfg(feature = "alloy")]
+from_bytes_arbitrary!(Address, 20);
+#[cfg(feature = "alloy")]
+from_bytes_arbitrary!(Bloom, 256);
+#[cfg(feature = "ethereum-types")]
+from_bytes_arbitrary!(H32, 4);
+#[cfg(feature = "ethereum-types")]
+from_bytes_arbitrary!(H160, 20);
+#[cfg(feature = "ethereum-types")]
+from_bytes_arbitrary!(H256, 32);
+
+#[cfg(feature = "alloy")]
+impl<const N: usize> SszArbitrary for FixedBytes<N> {
+    fn ssz_arbitrary(u: &mut Unstructured<'_>) -> Result<Self> {
+        u.arbitrary::<[u8; N]>().map(Self::from)
+    }
+}
+
+#[cfg(feature = "alloy")]
+impl SszArbitrary for U128 {
+    fn ssz_arbitrary(u: &mut Unstructured<'_>) -> Result<Self> {
+        u.arbitrary().map(Self::from_le_bytes::<16>)
+    }
+}
+
+#[cfg(feature = "alloy")]
+impl SszArbitrary for U256 {
+    fn ssz_arbitrary(u: &mut Unstructured<'_>) -> Result<Self> {
+        u.arbitrary().map(Self::from_le_bytes::<32>)
+    }
+}
+
+// public key bytes are not validated, any 48 bytes will do
+#[cfg(feature = "bls")]
+impl SszArbitrary for PKBytes {
+    fn ssz_arbitrary(u: &mut Unstructured<'_>) -> Result<Self> {
+        let bytes = u.arbitrary::<[u8; 48]>()?;
+        Self::from_ssz_bytes(&bytes).map_err(|_| Error::IncorrectFormat)
+    }
+}
+
+// random bytes are almost never a valid signature, so this is always the empty signature
+#[cfg(feature = "bls")]
+impl SszArbitrary for Sig {
+    fn ssz_arbitrary(_u: &mut Unstructured<'_>) -> Result<Self> {
+        Self::from_ssz_bytes(&[0; 96]).map_err(|_| Error::IncorrectFormat)
+    }
+}
+
+impl<N: Unsigned> SszArbitrary for ByteList<N> {
+    fn ssz_arbitrary(u: &mut Unstructured<'_>) -> Result<Self> {
+        let bytes = arbitrary_list::<u8>(u, N::to_usize())?;
+        Self::new(bytes).map_err(|_| Error::IncorrectFormat)
+    }
+}
+
+// runtime-bounded lists are only bounded by the input left, the derive bounds them by their
+// #[ssz(max = N)] instead
+#[cfg(feature = "alloy")]
+impl SszArbitrary for AlloyBytes {
+    fn ssz_arbitrary(u: &mut Unstructured<'_>) -> Result<Self> {
+        arbitrary_list::<u8>(u, usize::MAX).map(Self::from)
+    }
+}
+
+impl<T: SszArbitrary> SszArbitrary for Vec<T> {
+    fn ssz_arbitrary(u: &mut Unstructured<'_>) -> Result<Self> {
+        arbitrary_list(u, usize::MAX)
+    }
+}
+
+impl<T: SszArbitrary> SszArbitrary for Box<[T]> {
+    fn ssz_arbitrary(u: &mut Unstructured<'_>) -> Result<Self> {
+        arbitrary_list(u, usize::MAX).map(Vec::into_boxed_slice)
+    }
+}
+
+impl<N: Unsigned> SszArbitrary for Bitvector<N> {
+    fn ssz_arbitrary(u: &mut Unstructured<'_>) -> Result<Self> {
+        let mut bits = Self::new();
+        for i in 0..N::to_usize() {
+            bits.set(i, u.arbitrary()?)
+                .map_err(|_| Error::IncorrectFormat)?;
+        }
+        Ok(bits)
+    }
+}
+
+impl<N: Unsigned> SszArbitrary for Bitlist<N> {
+    fn ssz_arbitrary(u: &mut Unstructured<'_>) -> Result<Self> {
+        let len = arbitrary_len(u, N::to_usize())?;
+        let mut bits = Self::with_capacity(len).map_err(|_| Error::IncorrectFormat)?;
+        for i in 0..len {
+            bits.set(i, u.arbitrary()?)
+                .map_err(|_| Error::IncorrectFormat)?;
+        }
+        Ok(bits)
+    }
+}
+
+impl<N: Unsigned + Clone> SszArbitrary for BitVector<N> {
+    fn ssz_arbitrary(u: &mut Unstructured<'_>) -> Result<Self> {
+        let mut bits = Self::new();
+        for i in 0..N::to_usize() {
+            bits.set(i, u.arbitrary()?)
+                .map_err(|_| Error::IncorrectFormat)?;
+        }
+        Ok(bits)
+    }
+}
+
+impl<N: Unsigned + Clone> SszArbitrary for BitList<N> {
+    fn ssz_arbitrary(u: &mut Unstructured<'_>) -> Result<Self> {
+        let len = arbitrary_len(u, N::to_usize())?;
+        let mut bits = Self::with_capacity(len).map_err(|_| Error::IncorrectFormat)?;
+        for i in 0..len {
+            bits.set(i, u.arbitrary()?)
+                .map_err(|_| Error::IncorrectFormat)?;
+        }
+        Ok(bits)
+    }
+}
+
+impl<T: SszArbitrary, N: Unsigned> SszArbitrary for VariableList<T, N> {
+    fn ssz_arbitrary(u: &mut Unstructured<'_>) -> Result<Self> {
+        Self::new(arbitrary_list(u, N::to_usize())?).map_err(|_| Error::IncorrectFormat)
+    }
+}
+
+impl<T: SszArbitrary, N: Unsigned> SszArbitrary for FixedVector<T, N> {
+    fn ssz_arbitrary(u: &mut Unstructured<'_>) -> Result<Self> {
+        Self::new(arbitrary_vec(u, N::to_usize())?).map_err(|_| Error::IncorrectFormat)
+    }
+}
+
+#[cfg(feature = "milhouse")]
+impl<T: SszArbitrary + Value, N: Unsigned> SszArbitrary for PersistentList<T, N> {
+    fn ssz_arbitrary(u: &mut Unstructured<'_>) -> Result<Self> {
+        Self::new(arbitrary_list(u, N::to_usize())?).map_err(|_| Error::IncorrectFormat)
+    }
+}
+
+#[cfg(feature = "milhouse")]
+impl<T: SszArbitrary + Value, N: Unsigned> SszArbitrary for PersistentVector<T, N> {
+    fn ssz_arbitrary(u: &mut Unstructured<'_>) -> Result<Self> {
+        Self::new(arbitrary_vec(u, N::to_usize())?).map_err(|_| Error::IncorrectFormat)
+    }
+}
+
+#[cfg(feature = "ghilhouse")]
+impl<T: SszArbitrary + ghilhouse::Value, N: Unsigned> SszArbitrary for ghilhouse::List<T, N> {
+    fn ssz_arbitrary(u: &mut Unstructured<'_>) -> Result<Self> {
+        Self::new(arbitrary_list(u, N::to_usize())?).map_err(|_| Error::IncorrectFormat)
+    }
+}
+
+#[cfg(feature = "ghilhouse")]
+impl<T: SszArbitrary + ghilhouse::Value, N: Unsigned> SszArbitrary for ghilhouse::Vector<T, N> {
+    fn ssz_arbitrary(u: &mut Unstructured<'_>) -> Result<Self> {
+        Self::new(arbitrary_vec(u, N::to_usize())?).map_err(|_| Error::IncorrectFormat)
+    }
+}
+
+impl<T: SszArbitrary, const N: usize> SszArbitrary for [T; N] {
+    fn ssz_arbitrary(u: &mut Unstructured<'_>) -> Result<Self> {
+        arbitrary_vec(u, N)?
+            .try_into()
+            .map_err(|_| Error::IncorrectFormat)
+    }
+}
+
+impl<T: SszArbitrary> SszArbitrary for Option<T> {
+    fn ssz_arbitrary(u: &mut Unstructured<'_>) -> Result<Self> {
+        if u.arbitrary()? {
+            T::ssz_arbitrary(u).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+impl<T: SszArbitrary + Clone> SszArbitrary for Cow<'_, T> {
+    fn ssz_arbitrary(u: &mut Unstructured<'_>) -> Result<Self> {
+        T::ssz_arbitrary(u).map(Cow::Owned)
+    }
+}
+
+macro_rules! pointer_arbitrary {
+    ($pointer: ident) => {
+        impl<T: SszArbitrary> SszArbitrary for $pointer<T> {
+            fn ssz_arbitrary(u: &mut Unstructured<'_>) -> Result<Self> {
+                T::ssz_arbitrary(u).map($pointer::new)
+            }
+        }
+    };
+}
+
+pointer_arbitrary!(Arc);
+pointer_arbitrary!(Box);
+pointer_arbitrary!(Rc);
+
+macro_rules! tuple_arbitrary {
+    ($($T: ident),+) => {
+        impl<$($T: SszArbitrary),+> SszArbitrary for ($($T,)+) {
+            fn ssz_arbitrary(u: &mut Unstructured<'_>) -> Result<Self> {
+                Ok(($($T::ssz_arbitrary(u)?,)+))
+            }
+        }
+    };
+}
+
+tuple_arbitrary!(A);
+tuple_arbitrary!(A, B);
+tuple_arbitrary!(A, B, C);
+tuple_arbitrary!(A, B, C, D);
+tuple_arbitrary!(A, B, C, D, E);
+tuple_arbitrary!(A, B, C, D, E, F);
+tuple_arbitrary!(A, B, C, D, E, F, G);
+tuple_arbitrary!(A, B, C, D, E, F, G, H);
@@ -0,0 +1,719 @@
+use crate::{
+    read_contiguous, reserve_bits, DecodeError, SszDecode, SszEncode, SszType,
+    BYTES_PER_LENGTH_OFFSET,
+};
+use alloc::format;
+use alloc::string::ToString;
+#[cfg(feature = "std")]
+use alloc::vec::Vec;
+use bytes::buf::{Buf, BufMut};
+use core::marker::PhantomData;
+#[cfg(feature = "std")]
+use smallvec::ToSmallVec;
+use smallvec::{smallvec, SmallVec};
+#[cfg(feature = "std")]
+use ssz_types::{BitList, BitVector};
+#[cfg(feature = "std")]
+use tree_hash::TreeHash;
+use typenum::Unsigned;
+
+const BITS_PER_WORD: usize = 64;
+
+// bits are stored in little-endian u64 words, so bit i lives in word i / 64 and
+// the SSZ byte encoding is the little-endian bytes of the words, truncated.
+// Up to 512 bits (e.g. sync committee bits) are stored inline.
+type Words = SmallVec<[u64; 8]>;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum BitfieldError {
+    /// The bit index is outside of the bitfield.
+    OutOfBounds { i: usize, len: usize },
+}
+
+// Word-based storage and set operations shared by Bitvector and Bitlist.
+// Bits at index >= len are always zero.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+struct Bits {
+    words: Words,
+    len: usize,
+}
+
+impl Bits {
+    fn zeroed(len: usize) -> Self {
+        Self {
+            words: smallvec![0; len.div_ceil(BITS_PER_WORD)],
+            len,
+        }
+    }
+
+    // bytes must not have any bits set at index >= len
+    fn from_bytes(bytes: &[u8], len: usize) -> Self {
+        let mut words: Words = bytes
+            .chunks(8)
+            .map(|chunk| {
+                let mut word = [0u8; 8];
+                word[..chunk.len()].copy_from_slice(chunk);
+                u64::from_le_bytes(word)
+            })
+            .collect();
+        words.resize(len.div_ceil(BITS_PER_WORD), 0);
+        Self { words, len }
+    }
+
+    fn byte(&self, i: usize) -> u8 {
+        self.words
+            .get(i / 8)
+            .map_or(0, |word| (word >> ((i % 8) * 8)) as u8)
+    }
+
+    // writes the first num_bytes bytes of the little-endian encoding
+    fn write_bytes(&self, num_bytes: usize, buf: &mut impl BufMut) {
+        let mut remaining = num_bytes;
+        for word in &self.words {
+            let n = core::cmp::min(8, remaining);
+            buf.put_slice(&word.to_le_bytes()[..n]);
+            remaining -= n;
+        }
+        buf.put_bytes(0, remaining);
+    }
+
+    fn get(&self, i: usize) -> Result<bool, BitfieldError> {
+        if i < self.len {
+            Ok(self.words[i / BITS_PER_WORD] >> (i % BITS_PER_WORD) & 1 == 1)
+        } else {
+            Err(BitfieldError::OutOfBounds { i, len: self.len })
+        }
+    }
+
+    fn set(&mut self, i: usize, value: bool) -> Result<(), BitfieldError> {
+        if i < self.len {
+            let mask = 1 << (i % BITS_PER_WORD);
+            if value {
+                self.words[i / BITS_PER_WORD] |= mask;
+            } else {
+                self.words[i / BITS_PER_WORD] &= !mask;
+            }
+            Ok(())
+        } else {
+            Err(BitfieldError::OutOfBounds { i, len: self.len })
+        }
+    }
+
+    fn num_set_bits(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    fn is_zero(&self) -> bool {
+        self.words.iter().all(|word| *word == 0)
+    }
+
+    fn highest_set_bit(&self) -> Option<usize> {
+        self.words.iter().enumerate().rev().find_map(|(i, word)| {
+            (*word != 0).then(|| i * BITS_PER_WORD + (63 - word.leading_zeros() as usize))
+        })
+    }
+
+    // combines self and other word by word, into a bitfield of length len
+    fn zip_words(&self, other: &Self, len: usize, f: impl Fn(u64, u64) -> u64) -> Self {
+        let words = (0..len.div_ceil(BITS_PER_WORD))
+            .map(|i| {
+                f(
+                    self.words.get(i).copied().unwrap_or(0),
+                    other.words.get(i).copied().unwrap_or(0),
+                )
+            })
+            .collect();
+        Self { words, len }
+    }
+
+    fn is_disjoint(&self, other: &Self) -> bool {
+        self.words
+            .iter()
+            .zip(other.words.iter())
+            .all(|(a, b)| a & b == 0)
+    }
+
+    fn is_subset(&self, other: &Self) -> bool {
+        self.words
+            .iter()
+            .enumerate()
+            .all(|(i, a)| a & !other.words.get(i).copied().unwrap_or(0) == 0)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = bool> + '_ {
+        (0..self.len).map(|i| self.words[i / BITS_PER_WORD] >> (i % BITS_PER_WORD) & 1 == 1)
+    }
+
+    fn iter_set_indices(&self) -> SetBits<'_> {
+        SetBits {
+            words: &self.words,
+            index: 0,
+            current: self.words.first().copied().unwrap_or(0),
+        }
+    }
+}
+
+/// Iterator over the indices of the set bits of a bitfield, in increasing order.
+pub struct SetBits<'a> {
+    words: &'a [u64],
+    index: usize,
+    current: u64,
+}
+
+impl Iterator for SetBits<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        loop {
+            if self.current != 0 {
+                let bit = self.current.trailing_zeros() as usize;
+                // clear the lowest set bit
+                self.current &= self.current - 1;
+                return Some(self.index * BITS_PER_WORD + bit);
+            }
+            self.index += 1;
+            self.current = *self.words.get(self.index)?;
+        }
+    }
+}
+
+// Checks that bytes hold exactly a Bitvector of len bits, with all unused high bits zero.
+pub(crate) fn validate_bitvector_bytes(bytes: &[u8], len: usize) -> Result<(), DecodeError> {
+    let expected = core::cmp::max(1, len.div_ceil(8));
+    if bytes.len() != expected {
+        return Err(DecodeError::InvalidByteLength {
+            len: bytes.len(),
+            expected,
+        });
+    }
+
+    let used_bits = len - (expected - 1) * 8;
+    if used_bits < 8 && bytes[expected - 1] >> used_bits != 0 {
+        return Err(DecodeError::BytesInvalid(format!(
+            "Bitvector of length {} has bits set beyond its length",
+            len
+        )));
+    }
+
+    Ok(())
+}
+
+// Reads the length of a Bitlist from the position of its trailing delimiter bit.
+pub(crate) fn bitlist_len_from_bytes(bytes: &[u8], max_len: usize) -> Result<usize, DecodeError> {
+    let last = *bytes.last().ok_or(DecodeError::InvalidByteLength {
+        len: 0,
+        expected: 1,
+    })?;
+
+    // the delimiter is the highest set bit, so the last byte can't be zero
+    if last == 0 {
+        return Err(DecodeError::BytesInvalid(
+            "Bitlist is missing its length delimiter bit".to_string(),
+        ));
+    }
+
+    let len = (bytes.len() - 1) * 8 + (7 - last.leading_zeros() as usize);
+    if len > max_len {
+        return Err(DecodeError::BytesInvalid(format!(
+            "Bitlist of {} bits exceeds maximum of {}",
+            len, max_len
+        )));
+    }
+
+    Ok(len)
+}
+
+/// An SSZ `Bitvector[N]`.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Bitvector<N> {
+    bits: Bits,
+    _phantom: PhantomData<N>,
+}
+
+impl<N: Unsigned> Bitvector<N> {
+    /// A bitvector with all bits unset.
+    pub fn new() -> Self {
+        Self {
+            bits: Bits::zeroed(N::to_usize()),
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        N::to_usize()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        N::to_usize() == 0
+    }
+
+    pub fn get(&self, i: usize) -> Result<bool, BitfieldError> {
+        self.bits.get(i)
+    }
+
+    pub fn set(&mut self, i: usize, value: bool) -> Result<(), BitfieldError> {
+        self.bits.set(i, value)
+    }
+
+    /// The number of set bits (popcount).
+    pub fn num_set_bits(&self) -> usize {
+        self.bits.num_set_bits()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.bits.is_zero()
+    }
+
+    pub fn highest_set_bit(&self) -> Option<usize> {
+        self.bits.highest_set_bit()
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a | b)
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a & b)
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a & !b)
+    }
+
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.bits.is_disjoint(&other.bits)
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.bits.is_subset(&other.bits)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = bool> + '_ {
+        self.bits.iter()
+    }
+
+    pub fn iter_set_indices(&self) -> SetBits<'_> {
+        self.bits.iter_set_indices()
+    }
+
+    fn zip_with(&self, other: &Self, f: impl Fn(u64, u64) -> u64) -> Self {
+        Self {
+            bits: self.bits.zip_words(&other.bits, N::to_usize(), f),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<N: Unsigned> Default for Bitvector<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An SSZ `Bitlist[N]`.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Bitlist<N> {
+    bits: Bits,
+    _phantom: PhantomData<N>,
+}
+
+impl<N: Unsigned> Bitlist<N> {
+    /// A bitlist of `len` unset bits, failing if `len` exceeds `N`.
+    pub fn with_capacity(len: usize) -> Result<Self, BitfieldError> {
+        if len <= N::to_usize() {
+            Ok(Self {
+                bits: Bits::zeroed(len),
+                _phantom: PhantomData,
+            })
+        } else {
+            Err(BitfieldError::OutOfBounds {
+                i: len,
+                len: N::to_usize(),
+            })
+        }
+    }
+
+    pub fn max_len() -> usize {
+        N::to_usize()
+    }
+
+    pub fn len(&self) -> usize {
+        self.bits.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.len == 0
+    }
+
+    pub fn get(&self, i: usize) -> Result<bool, BitfieldError> {
+        self.bits.get(i)
+    }
+
+    pub fn set(&mut self, i: usize, value: bool) -> Result<(), BitfieldError> {
+        self.bits.set(i, value)
+    }
+
+    /// The number of set bits (popcount).
+    pub fn num_set_bits(&self) -> usize {
+        self.bits.num_set_bits()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.bits.is_zero()
+    }
+
+    pub fn highest_set_bit(&self) -> Option<usize> {
+        self.bits.highest_set_bit()
+    }
+
+    /// The union of both bitlists, as long as the longer of the two.
+    pub fn union(&self, other: &Self) -> Self {
+        self.zip_with(other, core::cmp::max(self.len(), other.len()), |a, b| a | b)
+    }
+
+    /// The intersection of both bitlists, as long as the shorter of the two.
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.zip_with(other, core::cmp::min(self.len(), other.len()), |a, b| a & b)
+    }
+
+    /// The bits of self that are not set in other, as long as self.
+    pub fn difference(&self, other: &Self) -> Self {
+        self.zip_with(other, self.len(), |a, b| a & !b)
+    }
+
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.bits.is_disjoint(&other.bits)
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.bits.is_subset(&other.bits)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = bool> + '_ {
+        self.bits.iter()
+    }
+
+    pub fn iter_set_indices(&self) -> SetBits<'_> {
+        self.bits.iter_set_indices()
+    }
+
+    fn zip_with(&self, other: &Self, len: usize, f: impl Fn(u64, u64) -> u64) -> Self {
+        Self {
+            bits: self.bits.zip_words(&other.bits, len, f),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<N: Unsigned> Default for Bitlist<N> {
+    fn default() -> Self {
+        Self {
+            bits: Bits::zeroed(0),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+/// A validated, zero-copy view of an SSZ encoded `Bitvector[N]`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BitvectorView<'a, N> {
+    bytes: &'a [u8],
+    _phantom: PhantomData<N>,
+}
+
+impl<'a, N: Unsigned> BitvectorView<'a, N> {
+    pub fn from_ssz_bytes(bytes: &'a [u8]) -> Result<Self, DecodeError> {
+        validate_bitvector_bytes(bytes, N::to_usize())?;
+        Ok(Self {
+            bytes,
+            _phantom: PhantomData,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        N::to_usize()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        N::to_usize() == 0
+    }
+
+    pub fn get(&self, i: usize) -> Result<bool, BitfieldError> {
+        if i < self.len() {
+            Ok(self.bytes[i / 8] >> (i % 8) & 1 == 1)
+        } else {
+            Err(BitfieldError::OutOfBounds { i, len: self.len() })
+        }
+    }
+
+    pub fn num_set_bits(&self) -> usize {
+        self.bytes
+            .iter()
+            .map(|byte| byte.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn iter_set_indices(&self) -> impl Iterator<Item = usize> + 'a {
+        let bytes = self.bytes;
+        (0..N::to_usize()).filter(move |i| bytes[i / 8] >> (i % 8) & 1 == 1)
+    }
+
+    pub fn to_bitvector(&self) -> Bitvector<N> {
+        Bitvector {
+            bits: Bits::from_bytes(self.bytes, N::to_usize()),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+/// A validated, zero-copy view of an SSZ encoded `Bitlist[N]`, including its delimiter bit.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BitlistView<'a, N> {
+    bytes: &'a [u8],
+    len: usize,
+    _phantom: PhantomData<N>,
+}
+
+impl<'a, N: Unsigned> BitlistView<'a, N> {
+    pub fn from_ssz_bytes(bytes: &'a [u8]) -> Result<Self, DecodeError> {
+        let len = bitlist_len_from_bytes(bytes, N::to_usize())?;
+        Ok(Self {
+            bytes,
+            len,
+            _phantom: PhantomData,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, i: usize) -> Result<bool, BitfieldError> {
+        if i < self.len {
+            Ok(self.bytes[i / 8] >> (i % 8) & 1 == 1)
+        } else {
+            Err(BitfieldError::OutOfBounds { i, len: self.len })
+        }
+    }
+
+    pub fn num_set_bits(&self) -> usize {
+        // don't count the delimiter bit
+        self.bytes
+            .iter()
+            .map(|byte| byte.count_ones() as usize)
+            .sum::<usize>()
+            - 1
+    }
+
+    pub fn iter_set_indices(&self) -> impl Iterator<Item = usize> + 'a {
+        let bytes = self.bytes;
+        (0..self.len).filter(move |i| bytes[i / 8] >> (i % 8) & 1 == 1)
+    }
+
+    pub fn to_bitlist(&self) -> Bitlist<N> {
+        let mut bits = Bits::from_bytes(self.bytes, self.len + 1);
+        // clear the delimiter bit, then drop it
+        bits.set(self.len, false)
+            .expect("delimiter is within bounds");
+        bits.len = self.len;
+        bits.words.truncate(self.len.div_ceil(BITS_PER_WORD));
+        Bitlist {
+            bits,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<N: Unsigned> SszType for Bitvector<N> {
+    fn is_ssz_static() -> bool {
+        true
+    }
+
+    fn ssz_fixed_len() -> usize {
+        core::cmp::max(1, N::to_usize().div_ceil(8))
+    }
+
+    fn ssz_max_len() -> usize {
+        core::cmp::max(1, N::to_usize().div_ceil(8))
+    }
+}
+
+impl<N: Unsigned> SszEncode for Bitvector<N> {
+    fn ssz_bytes_len(&self) -> usize {
+        <Self as SszType>::ssz_fixed_len()
+    }
+
+    fn ssz_write_fixed(&self, _offset: &mut usize, buf: &mut impl BufMut) {
+        self.ssz_write(buf);
+    }
+
+    fn ssz_write_variable(&self, _buf: &mut impl BufMut) {}
+
+    fn ssz_write(&self, buf: &mut impl BufMut) {
+        self.bits
+            .write_bytes(<Self as SszType>::ssz_fixed_len(), buf);
+    }
+}
+
+impl<N: Unsigned> SszDecode for Bitvector<N> {
+    fn ssz_read(
+        fixed_bytes: &mut impl Buf,
+        _variable_bytes: &mut impl Buf,
+    ) -> Result<Self, DecodeError> {
+        let len = fixed_bytes.remaining();
+        let expected = <Self as SszType>::ssz_fixed_len();
+
+        if len < expected {
+            Err(DecodeError::InvalidByteLength { len, expected })
+        } else {
+            // the bytes are only copied when fixed_bytes holds them in several chunks
+            read_contiguous(fixed_bytes, expected, |bytes| {
+                BitvectorView::<N>::from_ssz_bytes(bytes).map(|view| view.to_bitvector())
+            })
+        }
+    }
+}
+
+impl<N: Unsigned> SszType for Bitlist<N> {
+    fn is_ssz_static() -> bool {
+        false
+    }
+
+    fn ssz_fixed_len() -> usize {
+        BYTES_PER_LENGTH_OFFSET
+    }
+
+    // an empty bitlist is just its length bit
+    fn ssz_min_len() -> usize {
+        1
+    }
+
+    fn ssz_max_len() -> usize {
+        N::to_usize() / 8 + 1
+    }
+}
+
+impl<N: Unsigned> SszEncode for Bitlist<N> {
+    fn ssz_bytes_len(&self) -> usize {
+        self.len() / 8 + 1
+    }
+
+    fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl BufMut) {
+        buf.put_slice(&offset.to_le_bytes()[0..BYTES_PER_LENGTH_OFFSET]);
+        *offset += self.ssz_bytes_len();
+    }
+
+    fn ssz_write_variable(&self, buf: &mut impl BufMut) {
+        self.ssz_write(buf);
+    }
+
+    fn ssz_write(&self, buf: &mut impl BufMut) {
+        // the full bytes before the delimiter, then the byte holding the delimiter bit
+        let len = self.len();
+        self.bits.write_bytes(len / 8, buf);
+        buf.put_u8(self.bits.byte(len / 8) | (1 << (len % 8)));
+    }
+}
+
+impl<N: Unsigned> SszDecode for Bitlist<N> {
+    fn ssz_read(
+        _fixed_bytes: &mut impl Buf,
+        variable_bytes: &mut impl Buf,
+    ) -> Result<Self, DecodeError> {
+        let len = variable_bytes.remaining();
+        read_contiguous(variable_bytes, len, |bytes| {
+            let view = BitlistView::<N>::from_ssz_bytes(bytes)?;
+            reserve_bits(view.len(), len)?;
+            Ok(view.to_bitlist())
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl<N: Unsigned> TreeHash for Bitvector<N> {
+    fn tree_hash_type() -> tree_hash::TreeHashType {
+        tree_hash::TreeHashType::Vector
+    }
+
+    fn tree_hash_packed_encoding(&self) -> tree_hash::PackedEncoding {
+        unreachable!("Vector should never be packed.")
+    }
+
+    fn tree_hash_packing_factor() -> usize {
+        unreachable!("Vector should never be packed.")
+    }
+
+    fn tree_hash_root(&self) -> tree_hash::Hash256 {
+        let minimum_chunk_count = N::to_usize()
+            .div_ceil(8)
+            .div_ceil(tree_hash::BYTES_PER_CHUNK);
+        tree_hash::merkle_root(&self.to_ssz(), minimum_chunk_count)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<N: Unsigned> TreeHash for Bitlist<N> {
+    fn tree_hash_type() -> tree_hash::TreeHashType {
+        tree_hash::TreeHashType::List
+    }
+
+    fn tree_hash_packed_encoding(&self) -> tree_hash::PackedEncoding {
+        unreachable!("List should never be packed.")
+    }
+
+    fn tree_hash_packing_factor() -> usize {
+        unreachable!("List should never be packed.")
+    }
+
+    fn tree_hash_root(&self) -> tree_hash::Hash256 {
+        // the delimiter bit is not part of the tree, the length is mixed in instead
+        let mut bytes = Vec::with_capacity(self.len().div_ceil(8));
+        self.bits.write_bytes(self.len().div_ceil(8), &mut bytes);
+        let minimum_chunk_count = N::to_usize()
+            .div_ceil(8)
+            .div_ceil(tree_hash::BYTES_PER_CHUNK);
+        let root = tree_hash::merkle_root(&bytes, minimum_chunk_count);
+        tree_hash::mix_in_length(&root, self.len())
+    }
+}
+
+// interop with the ssz_types bitfields goes through their (always valid) SSZ encoding
+#[cfg(feature = "std")]
+impl<N: Unsigned + Clone> From<BitVector<N>> for Bitvector<N> {
+    fn from(bitvector: BitVector<N>) -> Self {
+        BitvectorView::<N>::from_ssz_bytes(&bitvector.into_bytes())
+            .expect("ssz_types BitVector is always valid")
+            .to_bitvector()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<N: Unsigned + Clone> From<Bitvector<N>> for BitVector<N> {
+    fn from(bitvector: Bitvector<N>) -> Self {
+        BitVector::from_bytes(bitvector.to_ssz().to_smallvec())
+            .expect("sszb Bitvector is always valid")
+    }
+}
+
+#[cfg(feature = "std")]
+impl<N: Unsigned + Clone> From<BitList<N>> for Bitlist<N> {
+    fn from(bitlist: BitList<N>) -> Self {
+        BitlistView::<N>::from_ssz_bytes(&bitlist.into_bytes())
+            .expect("ssz_types BitList is always valid")
+            .to_bitlist()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<N: Unsigned + Clone> From<Bitlist<N>> for BitList<N> {
+    fn from(bitlist: Bitlist<N>) -> Self {
+        BitList::from_bytes(bitlist.to_ssz().to_smallvec()).expect("sszb Bitlist is always valid")
+    }
+}
@@ -0,0 +1,175 @@
+use crate::{reserve_items, DecodeError, SszDecode, SszEncode, SszType, BYTES_PER_LENGTH_OFFSET};
+use alloc::format;
+#[cfg(all(feature = "serde", feature = "alloy"))]
+use alloc::string::String;
+#[cfg(all(feature = "serde", feature = "alloy"))]
+use alloy_primitives::hex;
+use bytes::buf::{Buf, BufMut};
+use bytes::Bytes;
+use core::marker::PhantomData;
+use core::ops::Deref;
+#[cfg(all(feature = "serde", feature = "alloy"))]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+#[cfg(feature = "std")]
+use tree_hash::TreeHash;
+use typenum::Unsigned;
+
+/// An SSZ `ByteList[N]` backed by `bytes::Bytes`.
+///
+/// Unlike `VariableList<u8, N>`, decoding a `ByteList` from a `Bytes` buffer (through `ssz_read`
+/// or `from_ssz_buf`) slices the input instead of copying it.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct ByteList<N> {
+    bytes: Bytes,
+    _phantom: PhantomData<N>,
+}
+
+impl<N: Unsigned> ByteList<N> {
+    /// Wraps `bytes`, failing if it holds more than `N` bytes.
+    pub fn new(bytes: impl Into<Bytes>) -> Result<Self, DecodeError> {
+        let bytes = bytes.into();
+        if bytes.len() > N::to_usize() {
+            Err(DecodeError::BytesInvalid(format!(
+                "ByteList of {} bytes exceeds maximum of {}",
+                bytes.len(),
+                N::to_usize()
+            )))
+        } else {
+            Ok(Self {
+                bytes,
+                _phantom: PhantomData,
+            })
+        }
+    }
+
+    pub fn empty() -> Self {
+        Self {
+            bytes: Bytes::new(),
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn max_len() -> usize {
+        N::to_usize()
+    }
+
+    pub fn as_bytes(&self) -> &Bytes {
+        &self.bytes
+    }
+
+    pub fn into_bytes(self) -> Bytes {
+        self.bytes
+    }
+}
+
+impl<N> Deref for ByteList<N> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl<N> From<ByteList<N>> for Bytes {
+    fn from(list: ByteList<N>) -> Bytes {
+        list.bytes
+    }
+}
+
+impl<N: Unsigned> SszType for ByteList<N> {
+    fn is_ssz_static() -> bool {
+        false
+    }
+
+    fn ssz_fixed_len() -> usize {
+        BYTES_PER_LENGTH_OFFSET
+    }
+
+    fn ssz_max_len() -> usize {
+        N::to_usize()
+    }
+}
+
+impl<N: Unsigned> SszEncode for ByteList<N> {
+    fn ssz_bytes_len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl BufMut) {
+        buf.put_slice(&offset.to_le_bytes()[0..BYTES_PER_LENGTH_OFFSET]);
+        *offset += self.ssz_bytes_len();
+    }
+
+    fn ssz_write_variable(&self, buf: &mut impl BufMut) {
+        self.ssz_write(buf);
+    }
+
+    fn ssz_write(&self, buf: &mut impl BufMut) {
+        buf.put_slice(&self.bytes);
+    }
+}
+
+impl<N: Unsigned> SszDecode for ByteList<N> {
+    fn ssz_read(
+        _fixed_bytes: &mut impl Buf,
+        variable_bytes: &mut impl Buf,
+    ) -> Result<Self, DecodeError> {
+        Self::from_ssz_buf(variable_bytes)
+    }
+
+    fn from_ssz_buf(buf: &mut impl Buf) -> Result<Self, DecodeError> {
+        let len = buf.remaining();
+        if len > N::to_usize() {
+            return Err(DecodeError::BytesInvalid(format!(
+                "ByteList of {} bytes exceeds maximum of {}",
+                len,
+                N::to_usize()
+            )));
+        }
+        reserve_items::<u8>(len)?;
+
+        // copy_to_bytes is zero-copy when buf is (or wraps) Bytes, and copies otherwise
+        Ok(Self {
+            bytes: buf.copy_to_bytes(len),
+            _phantom: PhantomData,
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl<N: Unsigned> TreeHash for ByteList<N> {
+    fn tree_hash_type() -> tree_hash::TreeHashType {
+        tree_hash::TreeHashType::List
+    }
+
+    fn tree_hash_packed_encoding(&self) -> tree_hash::PackedEncoding {
+        unreachable!("List should never be packed.")
+    }
+
+    fn tree_hash_packing_factor() -> usize {
+        unreachable!("List should never be packed.")
+    }
+
+    fn tree_hash_root(&self) -> tree_hash::Hash256 {
+        let minimum_chunk_count = N::to_usize().div_ceil(tree_hash::BYTES_PER_CHUNK);
+        let root = tree_hash::merkle_root(&self.bytes, minimum_chunk_count);
+        tree_hash::mix_in_length(&root, self.bytes.len())
+    }
+}
+
+// byte lists are represented as 0x-prefixed hex strings
+#[cfg(all(feature = "serde", feature = "alloy"))]
+impl<N> Serialize for ByteList<N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&hex::encode_prefixed(&self.bytes))
+    }
+}
+
+#[cfg(all(feature = "serde", feature = "alloy"))]
+impl<'de, N: Unsigned> Deserialize<'de> for ByteList<N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        let bytes = hex::decode(&s).map_err(serde::de::Error::custom)?;
+        Self::new(bytes).map_err(|e| serde::de::Error::custom(format!("{:?}", e)))
+    }
+}
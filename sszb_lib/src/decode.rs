@@ -1,14 +1,25 @@
-use crate::BYTES_PER_LENGTH_OFFSET;
+use crate::{SszType, BYTES_PER_LENGTH_OFFSET};
+use alloc::format;
+use alloc::string::{String, ToString};
 use bytes::buf::Buf;
+use decode_impls::TryFromIter;
+use itertools::process_results;
 
+pub mod config;
 pub mod decode_impls;
 
+#[cfg(feature = "std")]
+use config::DecodeConfig;
+
 // error types and offset decoding code borrowed from the sigma prime team:
 // https://github.com/sigp/ethereum_ssz/blob/main/ssz/src/decode.rs#L12
 #[derive(Debug, PartialEq, Clone)]
 pub enum DecodeError {
     /// The bytes supplied were too short to be decoded into the specified type.
     InvalidByteLength { len: usize, expected: usize },
+    /// The input holds more bytes than the value decoded from it: `len` bytes, where the encoding
+    /// of the value takes up `expected`.
+    TrailingBytes { len: usize, expected: usize },
     /// The given bytes were too short to be read as a length prefix.
     InvalidLengthPrefix { len: usize, expected: usize },
     /// A length offset pointed to a byte that was out-of-bounds (OOB).
@@ -17,7 +28,7 @@ pub enum DecodeError {
     ///
     /// - It is `>= bytes.len()`.
     /// - When decoding variable length items, the 1st offset points "backwards" into the fixed
-    /// length items (i.e., `length[0] < BYTES_PER_LENGTH_OFFSET`).
+    ///   length items (i.e., `length[0] < BYTES_PER_LENGTH_OFFSET`).
     /// - When decoding variable-length items, the `n`'th offset was less than the `n-1`'th offset.
     OutOfBoundsByte { i: usize },
     /// An offset points “backwards” into the fixed-bytes portion of the message, essentially
@@ -44,8 +55,43 @@ pub enum DecodeError {
     InvalidListFixedBytesLen(usize),
     /// Some item has a `ssz_fixed_len` of zero. This is illegal.
     ZeroLengthItem,
+    /// The selector byte of a union is not a valid variant.
+    UnionSelectorInvalid(u8),
+    /// A list without a type-level maximum length (e.g. `Vec<T>`) was decoded directly.
+    /// Its maximum must be declared with `#[ssz(max = N)]` so decoding cannot allocate unbounded memory.
+    UnboundedList,
     /// The given bytes were invalid for some application-level reason.
     BytesInvalid(String),
+    /// A decoded value failed a `#[ssz(validate = "...")]` check. `path` holds the names of the
+    /// fields leading to it from the outermost container, joined by dots, and is empty when the
+    /// check of the outermost container itself failed.
+    ValidationFailed { path: String, reason: String },
+    /// Decoding would allocate more than the `max_allocated_bytes` of the `DecodeConfig`.
+    AllocationLimitExceeded { allocated: usize, max: usize },
+    /// Values nest deeper than the `max_depth` of the `DecodeConfig`.
+    DepthLimitExceeded { max: usize },
+    /// A list or vector holds more items than the `max_list_items` of the `DecodeConfig`.
+    ListItemsLimitExceeded { len: usize, max: usize },
+}
+
+impl DecodeError {
+    /// Prepends `field` to the path of a validation failure, as it bubbles up from the container
+    /// the field holds. Other errors are returned unchanged.
+    pub fn in_field(self, field: &str) -> Self {
+        match self {
+            DecodeError::ValidationFailed { path, reason } if path.is_empty() => {
+                DecodeError::ValidationFailed {
+                    path: field.to_string(),
+                    reason,
+                }
+            }
+            DecodeError::ValidationFailed { path, reason } => DecodeError::ValidationFailed {
+                path: format!("{}.{}", field, path),
+                reason,
+            },
+            e => e,
+        }
+    }
 }
 
 /// Reads a `BYTES_PER_LENGTH_OFFSET`-byte length from `bytes`, where `bytes.len() >=
@@ -79,7 +125,7 @@ fn decode_offset(bytes: &[u8]) -> Result<usize, DecodeError> {
     if len != expected {
         Err(DecodeError::InvalidLengthPrefix { len, expected })
     } else {
-        let mut array: [u8; BYTES_PER_LENGTH_OFFSET] = std::default::Default::default();
+        let mut array: [u8; BYTES_PER_LENGTH_OFFSET] = core::default::Default::default();
         array.clone_from_slice(bytes);
 
         Ok(u32::from_le_bytes(array) as usize)
@@ -92,11 +138,11 @@ fn decode_offset(bytes: &[u8]) -> Result<usize, DecodeError> {
 ///
 /// - `offset`: the offset bytes (e.g., result of `read_offset(..)`).
 /// - `previous_offset`: unless this is the first offset in the SSZ object, the value of the
-/// previously-read offset. Used to ensure offsets are not decreasing.
+///   previously-read offset. Used to ensure offsets are not decreasing.
 /// - `num_bytes`: the total number of bytes in the SSZ object. Used to ensure the offset is not
-/// out of bounds.
+///   out of bounds.
 /// - `num_fixed_bytes`: the number of fixed-bytes in the struct, if it is known. Used to ensure
-/// that the first offset doesn't skip any variable bytes.
+///   that the first offset doesn't skip any variable bytes.
 ///
 /// ## References
 ///
@@ -109,26 +155,124 @@ pub fn sanitize_offset(
     num_bytes: usize,
     num_fixed_bytes: Option<usize>,
 ) -> Result<usize, DecodeError> {
-    if num_fixed_bytes.map_or(false, |fixed_bytes| offset < fixed_bytes) {
+    if num_fixed_bytes.is_some_and(|fixed_bytes| offset < fixed_bytes) {
         Err(DecodeError::OffsetIntoFixedPortion(offset))
     } else if previous_offset.is_none()
-        && num_fixed_bytes.map_or(false, |fixed_bytes| offset != fixed_bytes)
+        && num_fixed_bytes.is_some_and(|fixed_bytes| offset != fixed_bytes)
     {
         Err(DecodeError::OffsetSkipsVariableBytes(offset))
     } else if offset > num_bytes {
         Err(DecodeError::OffsetOutOfBounds(offset))
-    } else if previous_offset.map_or(false, |prev| prev > offset) {
+    } else if previous_offset.is_some_and(|prev| prev > offset) {
         Err(DecodeError::OffsetsAreDecreasing(offset))
     } else {
         Ok(offset)
     }
 }
 
-pub trait SszDecode: Sized {
-    fn is_ssz_static() -> bool;
-    fn ssz_fixed_len() -> usize;
-    fn ssz_max_len() -> usize;
+/// Calls `f` with the next `len` bytes of `buf` as one slice and advances `buf` past them.
+/// The bytes are only copied when `buf` holds them in several chunks (e.g. a `Chain`).
+pub fn read_contiguous<R>(buf: &mut impl Buf, len: usize, f: impl FnOnce(&[u8]) -> R) -> R {
+    if buf.chunk().len() >= len {
+        let res = f(&buf.chunk()[..len]);
+        buf.advance(len);
+        res
+    } else {
+        f(&buf.copy_to_bytes(len))
+    }
+}
 
+// Derived containers decode from_ssz_buf through a single cursor: first the fixed portion, where
+// each field is either a static value or the offset of a variable one, then each variable field
+// in turn. These helpers keep the code generated for each field down to one call.
+
+// reads the fixed portion of a field: a static T, or the offset of a variable one
+#[doc(hidden)]
+pub fn read_fixed_field<T: SszDecode>(
+    buf: &mut impl Buf,
+    offset: &mut usize,
+    field: &str,
+) -> Result<Option<T>, DecodeError> {
+    if T::is_ssz_static() {
+        let value = read_contiguous(buf, T::ssz_fixed_len(), |mut bytes| {
+            T::ssz_read(&mut bytes, &mut &[][..])
+        });
+        value.map(Some).map_err(|e| e.in_field(field))
+    } else {
+        *offset = read_offset_from_buf(buf)?;
+        Ok(None)
+    }
+}
+
+// the length of the variable field at index, which spans from its offset to the offset of the
+// next variable field, or to the end of the container. `fields` holds whether each field is
+// static, and the offset of each variable one, and `remaining` the bytes left in the buffer
+#[doc(hidden)]
+pub fn variable_field_len(
+    fields: &[(bool, usize)],
+    index: usize,
+    num_fixed_bytes: usize,
+    end_of_buffer: usize,
+    previous_offset: &mut Option<usize>,
+    remaining: usize,
+) -> Result<usize, DecodeError> {
+    let begin = fields[index].1;
+    let end = fields[index + 1..]
+        .iter()
+        .find(|(is_static, _)| !is_static)
+        .map_or(end_of_buffer, |(_, offset)| *offset);
+    if begin > end {
+        return Err(DecodeError::OffsetsAreDecreasing(begin));
+    } else if end - begin > remaining {
+        return Err(DecodeError::InvalidByteLength {
+            len: end - begin,
+            expected: remaining,
+        });
+    }
+
+    *previous_offset = Some(sanitize_offset(
+        begin,
+        *previous_offset,
+        end_of_buffer,
+        Some(num_fixed_bytes),
+    )?);
+    Ok(end - begin)
+}
+
+// decodes a T spanning the next len bytes of buf, and advances buf past them
+#[doc(hidden)]
+pub fn read_variable_field<T: SszDecode>(
+    buf: &mut impl Buf,
+    len: usize,
+    field: &str,
+) -> Result<T, DecodeError> {
+    read_item(buf, len).map_err(|e| e.in_field(field))
+}
+
+// decodes a T spanning the next len bytes of buf through from_ssz_buf, so that it can share
+// memory with buf, and advances buf past them whether or not T read all of them
+pub(crate) fn read_item<T: SszDecode>(buf: &mut impl Buf, len: usize) -> Result<T, DecodeError> {
+    let mut bytes = (&mut *buf).take(len);
+    let res = T::from_ssz_buf(&mut bytes);
+    let rest = bytes.remaining();
+    buf.advance(rest);
+    res
+}
+
+/// Fails with `DecodeError::TrailingBytes` if `len` bytes are longer than the encoding of a
+/// static `T`. Variable-size types span the whole of their input, so they never leave bytes over.
+pub fn check_trailing_bytes<T: SszType>(len: usize) -> Result<(), DecodeError> {
+    if T::is_ssz_static() && len > T::ssz_fixed_len() {
+        Err(DecodeError::TrailingBytes {
+            len,
+            expected: T::ssz_fixed_len(),
+        })
+    } else {
+        Ok(())
+    }
+}
+
+pub trait SszDecode: SszType + Sized {
     // Decoding happens in lockstep, where either:
     // - the static type is decoded from the fixed portion at the beginning of the data (fixed_bytes)
     // - or the fixed portion contains an offset to the end of the data (variable_bytes), pointing to a dynamic type
@@ -144,7 +288,9 @@ pub trait SszDecode: Sized {
     ) -> Result<Self, DecodeError>;
 
     // dev facing helper function for decoding a (static or variable) type from a slice
+    // the slice must hold exactly one encoded value, bytes after it are an error
     fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        check_trailing_bytes::<Self>(bytes.len())?;
         if Self::is_ssz_static() {
             let (mut fixed_bytes, mut variable_bytes) = bytes.split_at(bytes.len());
             Self::ssz_read(&mut fixed_bytes, &mut variable_bytes)
@@ -153,4 +299,50 @@ pub trait SszDecode: Sized {
             Self::ssz_read(&mut fixed_bytes, &mut variable_bytes)
         }
     }
+
+    // decodes a (static or variable) type spanning all of buf, and advances buf past it.
+    // Static types go through from_ssz_bytes, variable ones read buf as their variable_bytes, so
+    // that lists of types that share memory with the input (e.g. ByteList decoding from
+    // bytes::Bytes, which overrides this) don't copy their items either.
+    fn from_ssz_buf(buf: &mut impl Buf) -> Result<Self, DecodeError> {
+        if Self::is_ssz_static() {
+            let len = buf.remaining();
+            read_contiguous(buf, len, Self::from_ssz_bytes)
+        } else {
+            let res = Self::ssz_read(&mut &[][..], buf);
+            let rest = buf.remaining();
+            buf.advance(rest);
+            res
+        }
+    }
+
+    // from_ssz_bytes for untrusted input, failing once decoding uses more than `config` allows
+    #[cfg(feature = "std")]
+    fn from_ssz_bytes_with_config(
+        bytes: &[u8],
+        config: &DecodeConfig,
+    ) -> Result<Self, DecodeError> {
+        config.apply(|| Self::from_ssz_bytes(bytes))
+    }
+
+    // from_ssz_buf for untrusted input, failing once decoding uses more than `config` allows
+    #[cfg(feature = "std")]
+    fn from_ssz_buf_with_config(
+        buf: &mut impl Buf,
+        config: &DecodeConfig,
+    ) -> Result<Self, DecodeError> {
+        config.apply(|| Self::from_ssz_buf(buf))
+    }
+
+    // decodes the items of a [Self; N] of static items from exactly their N encodings.
+    // u8 overrides it to copy the bytes at once, so byte arrays skip the per-item decoding
+    #[doc(hidden)]
+    fn ssz_read_array<const N: usize>(bytes: &[u8]) -> Result<[Self; N], DecodeError> {
+        process_results(
+            bytes
+                .chunks_exact(Self::ssz_fixed_len())
+                .map(Self::from_ssz_bytes),
+            |iter| <[Self; N]>::try_from_iter(iter),
+        )?
+    }
 }
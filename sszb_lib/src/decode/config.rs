@@ -0,0 +1,178 @@
+use crate::DecodeError;
+#[cfg(feature = "std")]
+use std::cell::Cell;
+
+/// Limits on the resources decoding may use, for inputs from untrusted peers where the bounds of
+/// the types alone are too loose (e.g. `2^30` transactions in an execution payload).
+///
+/// The limits apply to every decode running inside [`DecodeConfig::apply`], which is what
+/// `SszDecode::from_ssz_bytes_with_config` and `SszDecode::from_ssz_buf_with_config` use. Outside
+/// of it decoding is only bounded by the types.
+///
+/// The limits are kept per thread rather than passed along with each decode, so that the
+/// signatures of `SszDecode` stay the same with and without them.
+///
+/// Keeping the limits per thread needs the `std` feature, so without it there is no
+/// `DecodeConfig`, rather than one that would silently not be enforced. A `no_std` build must only
+/// decode input it trusts, or check its length against a bound of its own first.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodeConfig {
+    /// The most bytes decoding may allocate in total, counted as the in-memory size of the items
+    /// of every list and vector (bytes for byte lists and bitlists) as they are decoded.
+    pub max_allocated_bytes: usize,
+    /// The deepest containers, lists, vectors and unions may nest, the outermost value being at
+    /// depth 1.
+    pub max_depth: usize,
+    /// The most items a single list or vector may hold, counting the bits of a bitlist.
+    pub max_list_items: usize,
+}
+
+#[cfg(feature = "std")]
+impl Default for DecodeConfig {
+    fn default() -> Self {
+        Self {
+            max_allocated_bytes: usize::MAX,
+            max_depth: usize::MAX,
+            max_list_items: usize::MAX,
+        }
+    }
+}
+
+// the limits of the decode running on this thread, and what it used so far
+#[cfg(feature = "std")]
+#[derive(Clone, Copy)]
+struct DecodeState {
+    config: DecodeConfig,
+    depth: usize,
+    allocated_bytes: usize,
+}
+
+#[cfg(feature = "std")]
+thread_local! {
+    static STATE: Cell<Option<DecodeState>> = const { Cell::new(None) };
+}
+
+#[cfg(feature = "std")]
+fn with_state<R>(f: impl FnOnce(&Cell<Option<DecodeState>>) -> R) -> R {
+    STATE.with(f)
+}
+
+// restores the state of an enclosing decode, also when decoding panics
+#[cfg(feature = "std")]
+struct RestoreState(Option<DecodeState>);
+
+#[cfg(feature = "std")]
+impl Drop for RestoreState {
+    fn drop(&mut self) {
+        with_state(|state| state.set(self.0));
+    }
+}
+
+#[cfg(feature = "std")]
+impl DecodeConfig {
+    /// Runs `decode` with these limits. Nested calls apply their own limits until they return.
+    pub fn apply<R>(&self, decode: impl FnOnce() -> R) -> R {
+        let previous = with_state(|state| {
+            state.replace(Some(DecodeState {
+                config: *self,
+                depth: 0,
+                allocated_bytes: 0,
+            }))
+        });
+        let _restore = RestoreState(previous);
+        decode()
+    }
+}
+
+/// Marks one level of nesting for as long as it is held, see [`enter_nested`].
+#[cfg_attr(not(feature = "std"), allow(dead_code))]
+pub struct NestingGuard(bool);
+
+#[cfg(feature = "std")]
+impl Drop for NestingGuard {
+    fn drop(&mut self) {
+        if self.0 {
+            with_state(|state| {
+                if let Some(mut current) = state.get() {
+                    current.depth -= 1;
+                    state.set(Some(current));
+                }
+            });
+        }
+    }
+}
+
+// The hooks below are called by the decode impls and the derived containers whether or not the
+// `std` feature is enabled. Without it there is no DecodeConfig to hold decoding to, and they
+// never fail.
+
+/// Enters a container, list, vector or union being decoded, failing if that nests deeper than
+/// the `max_depth` of the active `DecodeConfig`. The level is left when the guard is dropped.
+#[cfg(feature = "std")]
+pub fn enter_nested() -> Result<NestingGuard, DecodeError> {
+    with_state(|state| match state.get() {
+        None => Ok(NestingGuard(false)),
+        Some(current) if current.depth >= current.config.max_depth => {
+            Err(DecodeError::DepthLimitExceeded {
+                max: current.config.max_depth,
+            })
+        }
+        Some(mut current) => {
+            current.depth += 1;
+            state.set(Some(current));
+            Ok(NestingGuard(true))
+        }
+    })
+}
+
+/// Enters a container, list, vector or union being decoded. Without the `std` feature there are
+/// no limits to check, so it never fails.
+#[cfg(not(feature = "std"))]
+pub fn enter_nested() -> Result<NestingGuard, DecodeError> {
+    Ok(NestingGuard(false))
+}
+
+/// Accounts for a list or vector of `len` items of type `T` about to be decoded, failing if it
+/// holds more items than the `max_list_items` of the active `DecodeConfig`, or takes the bytes
+/// allocated so far past its `max_allocated_bytes`. Without the `std` feature it never fails.
+pub fn reserve_items<T>(len: usize) -> Result<(), DecodeError> {
+    reserve(len, len.saturating_mul(core::mem::size_of::<T>()))
+}
+
+/// Accounts for a bitlist of `len` bits about to be decoded into `bytes` bytes, like
+/// [`reserve_items`] with the bits counted as items. Without the `std` feature it never fails.
+pub fn reserve_bits(len: usize, bytes: usize) -> Result<(), DecodeError> {
+    reserve(len, bytes)
+}
+
+#[cfg(feature = "std")]
+fn reserve(len: usize, bytes: usize) -> Result<(), DecodeError> {
+    with_state(|state| {
+        let Some(mut current) = state.get() else {
+            return Ok(());
+        };
+        if len > current.config.max_list_items {
+            return Err(DecodeError::ListItemsLimitExceeded {
+                len,
+                max: current.config.max_list_items,
+            });
+        }
+
+        let allocated_bytes = bytes.saturating_add(current.allocated_bytes);
+        if allocated_bytes > current.config.max_allocated_bytes {
+            return Err(DecodeError::AllocationLimitExceeded {
+                allocated: allocated_bytes,
+                max: current.config.max_allocated_bytes,
+            });
+        }
+        current.allocated_bytes = allocated_bytes;
+        state.set(Some(current));
+        Ok(())
+    })
+}
+
+#[cfg(not(feature = "std"))]
+fn reserve(_len: usize, _bytes: usize) -> Result<(), DecodeError> {
+    Ok(())
+}
@@ -1,37 +1,39 @@
 use crate::{
-    read_offset_from_slice, sanitize_offset, DecodeError, SszDecode, BYTES_PER_LENGTH_OFFSET,
+    decode::read_item, enter_nested, read_contiguous, read_fixed_field, read_offset_from_buf,
+    read_offset_from_slice, read_variable_field, reserve_items, sanitize_offset,
+    variable_field_len, DecodeError, SszDecode, SszType, BYTES_PER_LENGTH_OFFSET,
 };
-use alloy_primitives::{Address, Bloom, FixedBytes, U128, U256};
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::rc::Rc;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+#[cfg(feature = "alloy")]
+use alloy_primitives::{Address, Bloom, Bytes as AlloyBytes, FixedBytes, U128, U256};
 use bytes::buf::Buf;
+#[cfg(feature = "ethereum-types")]
 use ethereum_types::{H160, H256, H32};
-use itertools::{process_results, Itertools as _};
+use itertools::process_results;
+#[cfg(feature = "milhouse")]
 use milhouse::{Error as MilhouseError, List as PersistentList, Value, Vector as PersistentVector};
 use paste::paste;
+#[cfg(feature = "std")]
 use smallvec::ToSmallVec;
+#[cfg(feature = "std")]
 use ssz_types::{BitList, BitVector, Error as SszTypeError, FixedVector, VariableList};
+#[cfg(feature = "std")]
 use typenum::Unsigned;
 
 macro_rules! uint_ssz_decode {
     ($type: ident, $bit_size: expr) => {
         impl SszDecode for $type {
-            fn is_ssz_static() -> bool {
-                true
-            }
-
-            fn ssz_fixed_len() -> usize {
-                $bit_size / 8
-            }
-
-            fn ssz_max_len() -> usize {
-                $bit_size / 8
-            }
-
             fn ssz_read(
                 fixed_bytes: &mut impl Buf,
                 _variable_bytes: &mut impl Buf,
             ) -> Result<Self, DecodeError> {
                 let len = fixed_bytes.remaining();
-                let expected = <Self as SszDecode>::ssz_fixed_len();
+                let expected = <Self as SszType>::ssz_fixed_len();
 
                 if len < expected {
                     Err(DecodeError::InvalidByteLength { len, expected })
@@ -40,7 +42,7 @@ macro_rules! uint_ssz_decode {
                         <[u8; ($bit_size / 8)]>::try_from(&fixed_bytes.chunk()[0..($bit_size / 8)])
                             .unwrap();
                     let number = paste! { [<$type>]::from_le_bytes(bytes) };
-                    fixed_bytes.advance(($bit_size / 8));
+                    fixed_bytes.advance($bit_size / 8);
                     //Ok(paste! { fixed_bytes.[<get_ $type _le>]() })
                     Ok(number)
                 }
@@ -50,24 +52,12 @@ macro_rules! uint_ssz_decode {
 }
 
 impl SszDecode for u8 {
-    fn is_ssz_static() -> bool {
-        true
-    }
-
-    fn ssz_fixed_len() -> usize {
-        1
-    }
-
-    fn ssz_max_len() -> usize {
-        1
-    }
-
     fn ssz_read(
         fixed_bytes: &mut impl Buf,
         _variable_bytes: &mut impl Buf,
     ) -> Result<Self, DecodeError> {
         let len = fixed_bytes.remaining();
-        let expected = <Self as SszDecode>::ssz_fixed_len();
+        let expected = <Self as SszType>::ssz_fixed_len();
 
         if len < expected {
             Err(DecodeError::InvalidByteLength { len, expected })
@@ -75,6 +65,13 @@ impl SszDecode for u8 {
             Ok(fixed_bytes.get_u8())
         }
     }
+
+    fn ssz_read_array<const N: usize>(bytes: &[u8]) -> Result<[u8; N], DecodeError> {
+        <[u8; N]>::try_from(bytes).map_err(|_| DecodeError::InvalidByteLength {
+            len: bytes.len(),
+            expected: N,
+        })
+    }
 }
 
 uint_ssz_decode!(u16, 16);
@@ -83,24 +80,12 @@ uint_ssz_decode!(u64, 64);
 uint_ssz_decode!(u128, 128);
 
 impl SszDecode for bool {
-    fn is_ssz_static() -> bool {
-        true
-    }
-
-    fn ssz_fixed_len() -> usize {
-        1
-    }
-
-    fn ssz_max_len() -> usize {
-        1
-    }
-
     fn ssz_read(
         fixed_bytes: &mut impl Buf,
         _variable_bytes: &mut impl Buf,
     ) -> Result<Self, DecodeError> {
         let len = fixed_bytes.remaining();
-        let expected = <Self as SszDecode>::ssz_fixed_len();
+        let expected = <Self as SszType>::ssz_fixed_len();
 
         if len < expected {
             Err(DecodeError::InvalidByteLength { len, expected })
@@ -117,327 +102,286 @@ impl SszDecode for bool {
     }
 }
 
-impl<const N: usize> SszDecode for [u8; N] {
-    fn is_ssz_static() -> bool {
-        true
-    }
-
-    fn ssz_fixed_len() -> usize {
-        N
-    }
-
-    fn ssz_max_len() -> usize {
-        N
-    }
-
+impl<T: SszDecode, const N: usize> SszDecode for [T; N] {
     fn ssz_read(
         fixed_bytes: &mut impl Buf,
-        _variable_bytes: &mut impl Buf,
+        variable_bytes: &mut impl Buf,
     ) -> Result<Self, DecodeError> {
-        let len = fixed_bytes.remaining();
-        let expected = <Self as SszDecode>::ssz_fixed_len();
-
-        //[0u8; N];
-        let bytes: [u8; N] = <[u8; N]>::try_from(&fixed_bytes.chunk()[0..N]).unwrap();
-        fixed_bytes.advance(N);
-        // bytes[..] = fixed_bytes.chunk()[..N];
-        // fixed_bytes.copy_to_slice(&mut bytes[..]);
+        let _nesting = enter_nested()?;
+        reserve_items::<T>(N)?;
+        if T::is_ssz_static() {
+            // T is static, so data resides in fixed_bytes
+            let len = fixed_bytes.remaining();
+            let expected = <Self as SszType>::ssz_fixed_len();
+            if len < expected {
+                return Err(DecodeError::InvalidByteLength { len, expected });
+            }
+            if T::ssz_fixed_len() == 0 {
+                return Err(DecodeError::ZeroLengthItem);
+            }
 
-        if len < expected {
-            Err(DecodeError::InvalidByteLength { len, expected })
+            read_contiguous(fixed_bytes, expected, T::ssz_read_array)
         } else {
-            Ok(bytes)
+            // T is not static so data resides in variable_bytes, starting with N offsets
+            ssz_decode_variable_length_vector(N, variable_bytes)
         }
     }
 }
 
-impl SszDecode for Address {
-    fn is_ssz_static() -> bool {
-        true
-    }
+// tuples are decoded as anonymous containers, with one field per element, named by its index
+macro_rules! tuple_ssz_decode {
+    ($(($idx: tt, $T: ident)),+) => {
+        impl<$($T: SszDecode),+> SszDecode for ($($T,)+) {
+            fn ssz_read(
+                fixed_bytes: &mut impl Buf,
+                variable_bytes: &mut impl Buf,
+            ) -> Result<Self, DecodeError> {
+                let _nesting = enter_nested()?;
+                if <Self as SszType>::is_ssz_static() {
+                    return Ok(($(<$T as SszDecode>::ssz_read(fixed_bytes, variable_bytes)?,)+));
+                }
 
-    fn ssz_fixed_len() -> usize {
-        20
-    }
+                // a variable-size tuple resides entirely in variable_bytes, read through the
+                // same helpers as the fields of a derived container
+                let buf = variable_bytes;
+                let end_of_buffer = buf.remaining();
+                let num_fixed_bytes = 0 $(+ <$T as SszType>::ssz_fixed_len())+;
+                if end_of_buffer < num_fixed_bytes {
+                    return Err(DecodeError::InvalidByteLength {
+                        len: end_of_buffer,
+                        expected: num_fixed_bytes,
+                    });
+                }
 
-    fn ssz_max_len() -> usize {
-        20
-    }
+                let mut offsets = [$((<$T as SszType>::is_ssz_static(), 0)),+];
+                let mut values = ($(
+                    read_fixed_field::<$T>(buf, &mut offsets[$idx].1, stringify!($idx))?,
+                )+);
+                let mut previous_offset = None;
+                $(
+                    if !<$T as SszType>::is_ssz_static() {
+                        let len = variable_field_len(
+                            &offsets,
+                            $idx,
+                            num_fixed_bytes,
+                            end_of_buffer,
+                            &mut previous_offset,
+                            buf.remaining(),
+                        )?;
+                        values.$idx = Some(read_variable_field::<$T>(buf, len, stringify!($idx))?);
+                    }
+                )+
+
+                Ok(($(
+                    values.$idx.expect("every element is read from the fixed or variable portion"),
+                )+))
+            }
+        }
+    };
+}
 
+tuple_ssz_decode!((0, A));
+tuple_ssz_decode!((0, A), (1, B));
+tuple_ssz_decode!((0, A), (1, B), (2, C));
+tuple_ssz_decode!((0, A), (1, B), (2, C), (3, D));
+tuple_ssz_decode!((0, A), (1, B), (2, C), (3, D), (4, E));
+tuple_ssz_decode!((0, A), (1, B), (2, C), (3, D), (4, E), (5, F));
+tuple_ssz_decode!((0, A), (1, B), (2, C), (3, D), (4, E), (5, F), (6, G));
+tuple_ssz_decode!(
+    (0, A),
+    (1, B),
+    (2, C),
+    (3, D),
+    (4, E),
+    (5, F),
+    (6, G),
+    (7, H)
+);
+
+#[cfg(feature = "alloy")]
+impl SszDecode for Address {
     fn ssz_read(
         fixed_bytes: &mut impl Buf,
         _variable_bytes: &mut impl Buf,
     ) -> Result<Self, DecodeError> {
         let len = fixed_bytes.remaining();
-        let expected = <Self as SszDecode>::ssz_fixed_len();
+        let expected = <Self as SszType>::ssz_fixed_len();
 
         // let mut bytes: [u8; 20] = [0u8; 20];
         // fixed_bytes.copy_to_slice(&mut bytes[..]);
 
-        let bytes: [u8; 20] = <[u8; 20]>::try_from(&fixed_bytes.chunk()[0..20]).unwrap();
-        fixed_bytes.advance(20);
-
         if len < expected {
-            Err(DecodeError::InvalidByteLength { len, expected })
-        } else {
-            Ok(Self::from(bytes))
+            return Err(DecodeError::InvalidByteLength { len, expected });
         }
+
+        let bytes: [u8; 20] = <[u8; 20]>::try_from(&fixed_bytes.chunk()[0..20]).unwrap();
+        fixed_bytes.advance(20);
+        Ok(Self::from(bytes))
     }
 }
 
+#[cfg(feature = "alloy")]
 impl<const N: usize> SszDecode for FixedBytes<N> {
-    fn is_ssz_static() -> bool {
-        true
-    }
-
-    fn ssz_fixed_len() -> usize {
-        N
-    }
-
-    fn ssz_max_len() -> usize {
-        N
-    }
-
     fn ssz_read(
         fixed_bytes: &mut impl Buf,
         _variable_bytes: &mut impl Buf,
     ) -> Result<Self, DecodeError> {
         let len = fixed_bytes.remaining();
-        let expected = <Self as SszDecode>::ssz_fixed_len();
+        let expected = <Self as SszType>::ssz_fixed_len();
 
         // let mut bytes: [u8; N] = [0u8; N];
         // fixed_bytes.copy_to_slice(&mut bytes[..]);
 
-        let bytes: [u8; N] = <[u8; N]>::try_from(&fixed_bytes.chunk()[0..N]).unwrap();
-        fixed_bytes.advance(N);
-
         if len < expected {
-            Err(DecodeError::InvalidByteLength { len, expected })
-        } else {
-            Ok(Self(bytes))
+            return Err(DecodeError::InvalidByteLength { len, expected });
         }
+
+        let bytes: [u8; N] = <[u8; N]>::try_from(&fixed_bytes.chunk()[0..N]).unwrap();
+        fixed_bytes.advance(N);
+        Ok(Self(bytes))
     }
 }
 
+#[cfg(feature = "alloy")]
 impl SszDecode for Bloom {
-    fn is_ssz_static() -> bool {
-        true
-    }
-
-    fn ssz_fixed_len() -> usize {
-        256
-    }
-
-    fn ssz_max_len() -> usize {
-        256
-    }
-
     fn ssz_read(
         fixed_bytes: &mut impl Buf,
         _variable_bytes: &mut impl Buf,
     ) -> Result<Self, DecodeError> {
         let len = fixed_bytes.remaining();
-        let expected = <Self as SszDecode>::ssz_fixed_len();
+        let expected = <Self as SszType>::ssz_fixed_len();
 
         // let mut bytes: [u8; 256] = [0u8; 256];
         // fixed_bytes.copy_to_slice(&mut bytes[..]);
 
-        let bytes: [u8; 256] = <[u8; 256]>::try_from(&fixed_bytes.chunk()[0..256]).unwrap();
-        fixed_bytes.advance(256);
-
         if len < expected {
-            Err(DecodeError::InvalidByteLength { len, expected })
-        } else {
-            Ok(Self::from_slice(&bytes))
+            return Err(DecodeError::InvalidByteLength { len, expected });
         }
+
+        let bytes: [u8; 256] = <[u8; 256]>::try_from(&fixed_bytes.chunk()[0..256]).unwrap();
+        fixed_bytes.advance(256);
+        Ok(Self::from_slice(&bytes))
     }
 }
 
+#[cfg(feature = "alloy")]
 impl SszDecode for U256 {
-    fn is_ssz_static() -> bool {
-        true
-    }
-
-    fn ssz_fixed_len() -> usize {
-        32
-    }
-    fn ssz_max_len() -> usize {
-        32
-    }
-
     fn ssz_read(
         fixed_bytes: &mut impl Buf,
         _variable_bytes: &mut impl Buf,
     ) -> Result<Self, DecodeError> {
         let len = fixed_bytes.remaining();
-        let expected = <Self as SszDecode>::ssz_fixed_len();
+        let expected = <Self as SszType>::ssz_fixed_len();
 
         // let mut bytes: [u8; 32] = [0u8; 32];
         // fixed_bytes.copy_to_slice(&mut bytes[..]);
 
-        let bytes: [u8; 32] = <[u8; 32]>::try_from(&fixed_bytes.chunk()[0..32]).unwrap();
-        fixed_bytes.advance(32);
-
         if len < expected {
-            Err(DecodeError::InvalidByteLength { len, expected })
-        } else {
-            Ok(Self::from_le_slice(&bytes))
+            return Err(DecodeError::InvalidByteLength { len, expected });
         }
+
+        let bytes: [u8; 32] = <[u8; 32]>::try_from(&fixed_bytes.chunk()[0..32]).unwrap();
+        fixed_bytes.advance(32);
+        Ok(Self::from_le_slice(&bytes))
     }
 }
 
+#[cfg(feature = "alloy")]
 impl SszDecode for U128 {
-    fn is_ssz_static() -> bool {
-        true
-    }
-
-    fn ssz_fixed_len() -> usize {
-        16
-    }
-    fn ssz_max_len() -> usize {
-        16
-    }
-
     fn ssz_read(
         fixed_bytes: &mut impl Buf,
         _variable_bytes: &mut impl Buf,
     ) -> Result<Self, DecodeError> {
         let len = fixed_bytes.remaining();
-        let expected = <Self as SszDecode>::ssz_fixed_len();
+        let expected = <Self as SszType>::ssz_fixed_len();
 
         // let mut bytes: [u8; 16] = [0u8; 16];
         // fixed_bytes.copy_to_slice(&mut bytes[..]);
 
-        let bytes: [u8; 16] = <[u8; 16]>::try_from(&fixed_bytes.chunk()[0..16]).unwrap();
-        fixed_bytes.advance(16);
-
         if len < expected {
-            Err(DecodeError::InvalidByteLength { len, expected })
-        } else {
-            Ok(Self::from_le_slice(&bytes))
+            return Err(DecodeError::InvalidByteLength { len, expected });
         }
+
+        let bytes: [u8; 16] = <[u8; 16]>::try_from(&fixed_bytes.chunk()[0..16]).unwrap();
+        fixed_bytes.advance(16);
+        Ok(Self::from_le_slice(&bytes))
     }
 }
 
+#[cfg(feature = "ethereum-types")]
 impl SszDecode for H32 {
-    fn is_ssz_static() -> bool {
-        true
-    }
-
-    fn ssz_fixed_len() -> usize {
-        4
-    }
-    fn ssz_max_len() -> usize {
-        4
-    }
-
     fn ssz_read(
         fixed_bytes: &mut impl Buf,
         _variable_bytes: &mut impl Buf,
     ) -> Result<Self, DecodeError> {
         let len = fixed_bytes.remaining();
-        let expected = <Self as SszDecode>::ssz_fixed_len();
+        let expected = <Self as SszType>::ssz_fixed_len();
 
         // let mut bytes: [u8; 4] = [0u8; 4];
         // fixed_bytes.copy_to_slice(&mut bytes[..]);
 
-        let bytes: [u8; 4] = <[u8; 4]>::try_from(&fixed_bytes.chunk()[0..4]).unwrap();
-        fixed_bytes.advance(4);
-
         if len < expected {
-            Err(DecodeError::InvalidByteLength { len, expected })
-        } else {
-            Ok(Self::from_slice(&bytes))
+            return Err(DecodeError::InvalidByteLength { len, expected });
         }
+
+        let bytes: [u8; 4] = <[u8; 4]>::try_from(&fixed_bytes.chunk()[0..4]).unwrap();
+        fixed_bytes.advance(4);
+        Ok(Self::from_slice(&bytes))
     }
 }
 
+#[cfg(feature = "ethereum-types")]
 impl SszDecode for H160 {
-    fn is_ssz_static() -> bool {
-        true
-    }
-
-    fn ssz_fixed_len() -> usize {
-        20
-    }
-    fn ssz_max_len() -> usize {
-        20
-    }
-
     fn ssz_read(
         fixed_bytes: &mut impl Buf,
         _variable_bytes: &mut impl Buf,
     ) -> Result<Self, DecodeError> {
         let len = fixed_bytes.remaining();
-        let expected = <Self as SszDecode>::ssz_fixed_len();
+        let expected = <Self as SszType>::ssz_fixed_len();
 
         // let mut bytes: [u8; 20] = [0u8; 20];
         // fixed_bytes.copy_to_slice(&mut bytes[..]);
 
-        let bytes: [u8; 20] = <[u8; 20]>::try_from(&fixed_bytes.chunk()[0..20]).unwrap();
-        fixed_bytes.advance(20);
-
         if len < expected {
-            Err(DecodeError::InvalidByteLength { len, expected })
-        } else {
-            Ok(Self::from_slice(&bytes))
+            return Err(DecodeError::InvalidByteLength { len, expected });
         }
+
+        let bytes: [u8; 20] = <[u8; 20]>::try_from(&fixed_bytes.chunk()[0..20]).unwrap();
+        fixed_bytes.advance(20);
+        Ok(Self::from_slice(&bytes))
     }
 }
 
+#[cfg(feature = "ethereum-types")]
 impl SszDecode for H256 {
-    fn is_ssz_static() -> bool {
-        true
-    }
-
-    fn ssz_fixed_len() -> usize {
-        32
-    }
-
-    fn ssz_max_len() -> usize {
-        32
-    }
-
     fn ssz_read(
         fixed_bytes: &mut impl Buf,
         _variable_bytes: &mut impl Buf,
     ) -> Result<Self, DecodeError> {
         let len = fixed_bytes.remaining();
-        let expected = <Self as SszDecode>::ssz_fixed_len();
+        let expected = <Self as SszType>::ssz_fixed_len();
 
         // let mut bytes: [u8; 32] = [0u8; 32];
         // fixed_bytes.copy_to_slice(&mut bytes[..]);
 
-        let bytes: [u8; 32] = <[u8; 32]>::try_from(&fixed_bytes.chunk()[0..32]).unwrap();
-        fixed_bytes.advance(32);
-
         if len < expected {
-            Err(DecodeError::InvalidByteLength { len, expected })
-        } else {
-            Ok(Self::from_slice(&bytes))
+            return Err(DecodeError::InvalidByteLength { len, expected });
         }
+
+        let bytes: [u8; 32] = <[u8; 32]>::try_from(&fixed_bytes.chunk()[0..32]).unwrap();
+        fixed_bytes.advance(32);
+        Ok(Self::from_slice(&bytes))
     }
 }
 
+#[cfg(feature = "std")]
 impl<N: Unsigned + Clone> SszDecode for BitVector<N> {
-    fn is_ssz_static() -> bool {
-        true
-    }
-
-    fn ssz_fixed_len() -> usize {
-        std::cmp::max(1, (N::to_usize() + 7) / 8)
-    }
-
-    fn ssz_max_len() -> usize {
-        std::cmp::max(1, (N::to_usize() + 7) / 8)
-    }
-
     fn ssz_read(
         fixed_bytes: &mut impl Buf,
         _variable_bytes: &mut impl Buf,
     ) -> Result<Self, DecodeError> {
         let len = fixed_bytes.remaining();
-        let expected = <Self as SszDecode>::ssz_fixed_len();
+        let expected = <Self as SszType>::ssz_fixed_len();
 
         if len < expected {
             Err(DecodeError::InvalidByteLength { len, expected })
@@ -453,55 +397,215 @@ impl<N: Unsigned + Clone> SszDecode for BitVector<N> {
     }
 }
 
+#[cfg(feature = "std")]
 impl<N: Unsigned + Clone> SszDecode for BitList<N> {
-    fn is_ssz_static() -> bool {
-        false
+    fn ssz_read(
+        _fixed_bytes: &mut impl Buf,
+        variable_bytes: &mut impl Buf,
+    ) -> Result<Self, DecodeError> {
+        let num_bytes = variable_bytes.remaining();
+        read_contiguous(variable_bytes, num_bytes, |bytes| {
+            let len = crate::bitfield::bitlist_len_from_bytes(bytes, N::to_usize())?;
+            crate::reserve_bits(len, bytes.len())?;
+
+            Self::from_bytes(bytes.to_smallvec()).map_err(|e| {
+                DecodeError::BytesInvalid(format!("BitVector failed to decode: {:?}", e))
+            })
+        })
     }
+}
+
+// smart pointers are decoded as the value they point to
+macro_rules! pointer_ssz_decode {
+    ($pointer: ident) => {
+        impl<T: SszDecode> SszDecode for $pointer<T> {
+            fn ssz_read(
+                fixed_bytes: &mut impl Buf,
+                variable_bytes: &mut impl Buf,
+            ) -> Result<Self, DecodeError> {
+                T::ssz_read(fixed_bytes, variable_bytes).map($pointer::new)
+            }
 
-    fn ssz_fixed_len() -> usize {
-        BYTES_PER_LENGTH_OFFSET
+            fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+                T::from_ssz_bytes(bytes).map($pointer::new)
+            }
+        }
+    };
+}
+
+pointer_ssz_decode!(Arc);
+pointer_ssz_decode!(Box);
+pointer_ssz_decode!(Rc);
+
+impl<T: SszDecode + Clone> SszDecode for Cow<'_, T> {
+    fn ssz_read(
+        fixed_bytes: &mut impl Buf,
+        variable_bytes: &mut impl Buf,
+    ) -> Result<Self, DecodeError> {
+        T::ssz_read(fixed_bytes, variable_bytes).map(Cow::Owned)
     }
 
-    fn ssz_max_len() -> usize {
-        BYTES_PER_LENGTH_OFFSET + std::cmp::max(1, (N::to_usize() + 7) / 8)
+    fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        T::from_ssz_bytes(bytes).map(Cow::Owned)
     }
+}
+
+// Vec<T> and Box<[T]> have no maximum length in their type, so decoding them directly
+// would let an attacker pick how much memory we allocate. They can only be decoded through
+// ssz_decode_list_with_max, which the derive uses for fields annotated with #[ssz(max = N)].
+macro_rules! unbounded_list_ssz_decode {
+    ($type: ty) => {
+        impl<T: SszDecode> SszDecode for $type {
+            fn ssz_read(
+                _fixed_bytes: &mut impl Buf,
+                _variable_bytes: &mut impl Buf,
+            ) -> Result<Self, DecodeError> {
+                Err(DecodeError::UnboundedList)
+            }
+        }
+    };
+}
+
+unbounded_list_ssz_decode!(Vec<T>);
+unbounded_list_ssz_decode!(Box<[T]>);
 
+#[cfg(feature = "alloy")]
+impl SszDecode for AlloyBytes {
     fn ssz_read(
         _fixed_bytes: &mut impl Buf,
-        variable_bytes: &mut impl Buf,
+        _variable_bytes: &mut impl Buf,
     ) -> Result<Self, DecodeError> {
-        // let bytes = variable_bytes.copy_to_bytes(variable_bytes.remaining());
+        Err(DecodeError::UnboundedList)
+    }
+}
+
+/// Lists whose maximum length is supplied at runtime rather than being part of the type.
+pub trait RuntimeList: Sized {
+    type Item;
 
-        let bytes = &variable_bytes.chunk()[..]; // .copy_to_bytes(expected);
+    fn from_vec(vec: Vec<Self::Item>) -> Self;
+}
+
+impl<T> RuntimeList for Vec<T> {
+    type Item = T;
 
-        Self::from_bytes(bytes.to_smallvec())
-            .map_err(|e| DecodeError::BytesInvalid(format!("BitVector failed to decode: {:?}", e)))
+    fn from_vec(vec: Vec<T>) -> Self {
+        vec
     }
 }
 
-impl<T: SszDecode + Value, N: Unsigned> SszDecode for PersistentList<T, N> {
-    fn is_ssz_static() -> bool {
-        false
+impl<T> RuntimeList for Box<[T]> {
+    type Item = T;
+
+    fn from_vec(vec: Vec<T>) -> Self {
+        vec.into_boxed_slice()
     }
+}
 
-    fn ssz_fixed_len() -> usize {
-        BYTES_PER_LENGTH_OFFSET
+#[cfg(feature = "alloy")]
+impl RuntimeList for AlloyBytes {
+    type Item = u8;
+
+    fn from_vec(vec: Vec<u8>) -> Self {
+        Self::from(vec)
     }
+}
 
-    fn ssz_max_len() -> usize {
-        if T::is_ssz_static() {
-            <T as SszDecode>::ssz_fixed_len() * N::to_usize()
-        } else {
-            let mut len = T::ssz_max_len() * N::to_usize();
-            len += BYTES_PER_LENGTH_OFFSET * N::to_usize();
-            len
+/// Decodes a list of at most `max_len` items from `bytes`, failing before any allocation
+/// if the encoding holds more items than that.
+pub fn ssz_decode_list_with_max<L>(bytes: &[u8], max_len: usize) -> Result<L, DecodeError>
+where
+    L: RuntimeList,
+    L::Item: SszDecode,
+{
+    let _nesting = enter_nested()?;
+    if bytes.is_empty() {
+        Ok(L::from_vec(Vec::new()))
+    } else if <L::Item as SszType>::is_ssz_static() {
+        let item_len = <L::Item as SszType>::ssz_fixed_len();
+        if item_len == 0 {
+            return Err(DecodeError::ZeroLengthItem);
+        }
+        // not is_multiple_of, which needs Rust 1.87
+        if bytes.len() % item_len != 0 {
+            return Err(DecodeError::TrailingBytes {
+                len: bytes.len(),
+                expected: bytes.len() / item_len * item_len,
+            });
+        }
+
+        let num_items = bytes.len() / item_len;
+        if num_items > max_len {
+            return Err(DecodeError::BytesInvalid(format!(
+                "List of {} items exceeds maximum of {}",
+                num_items, max_len
+            )));
+        }
+        reserve_items::<L::Item>(num_items)?;
+
+        bytes
+            .chunks_exact(item_len)
+            .map(<L::Item as SszDecode>::from_ssz_bytes)
+            .collect::<Result<Vec<_>, _>>()
+            .map(L::from_vec)
+    } else {
+        let first_offset = read_offset_from_slice(bytes)?;
+        sanitize_offset(first_offset, None, bytes.len(), Some(first_offset))?;
+        if first_offset % BYTES_PER_LENGTH_OFFSET != 0 || first_offset < BYTES_PER_LENGTH_OFFSET {
+            return Err(DecodeError::InvalidListFixedBytesLen(first_offset));
+        }
+
+        // get how many items are in the list by reading the offset (only way to deduce in variable lists)
+        let num_items = first_offset / BYTES_PER_LENGTH_OFFSET;
+        if num_items > max_len {
+            return Err(DecodeError::BytesInvalid(format!(
+                "Variable length list of {} items exceeds maximum of {}",
+                num_items, max_len
+            )));
+        }
+        reserve_items::<L::Item>(num_items)?;
+
+        let mut var_items = &bytes[BYTES_PER_LENGTH_OFFSET..];
+        ssz_decode_variable_length_items::<L::Item, Vec<_>>(first_offset, &mut var_items)
+            .map(L::from_vec)
+    }
+}
+
+// Option<T> is decoded as the SSZ Union[None, T], where selector 0 is None and selector 1 is T
+impl<T: SszDecode> SszDecode for Option<T> {
+    fn ssz_read(
+        _fixed_bytes: &mut impl Buf,
+        variable_bytes: &mut impl Buf,
+    ) -> Result<Self, DecodeError> {
+        let _nesting = enter_nested()?;
+        // unions are always stored in the dynamic section, prefixed by a one byte selector
+        let len = variable_bytes.remaining();
+        if len < 1 {
+            return Err(DecodeError::InvalidByteLength { len, expected: 1 });
+        }
+
+        match variable_bytes.get_u8() {
+            0 => {
+                // None has no value, so the selector must be the only byte
+                if variable_bytes.has_remaining() {
+                    Err(DecodeError::InvalidByteLength { len, expected: 1 })
+                } else {
+                    Ok(None)
+                }
+            }
+            1 => T::from_ssz_buf(variable_bytes).map(Some),
+            selector => Err(DecodeError::UnionSelectorInvalid(selector)),
         }
     }
+}
 
+#[cfg(feature = "milhouse")]
+impl<T: SszDecode + Value, N: Unsigned> SszDecode for PersistentList<T, N> {
     fn ssz_read(
         _fixed_bytes: &mut impl Buf,
         variable_bytes: &mut impl Buf,
     ) -> Result<Self, DecodeError> {
+        let _nesting = enter_nested()?;
         let max_len = N::to_usize();
 
         // Lists are always stored in the dynamic section at the end
@@ -511,38 +615,39 @@ impl<T: SszDecode + Value, N: Unsigned> SszDecode for PersistentList<T, N> {
         } else if T::is_ssz_static() {
             let num_items = variable_bytes
                 .remaining()
-                .checked_div(<T as SszDecode>::ssz_fixed_len())
+                .checked_div(<T as SszType>::ssz_fixed_len())
                 .ok_or(DecodeError::ZeroLengthItem)?;
 
+            // a partial item at the end would be dropped by chunks_exact below
+            if num_items * <T as SszType>::ssz_fixed_len() != variable_bytes.remaining() {
+                return Err(DecodeError::TrailingBytes {
+                    len: variable_bytes.remaining(),
+                    expected: num_items * <T as SszType>::ssz_fixed_len(),
+                });
+            }
+
             if num_items > max_len {
                 return Err(DecodeError::BytesInvalid(format!(
                     "List of {} items exceeds maximum of {}",
                     num_items, max_len
                 )));
             }
-
-            // let bytes = variable_bytes.copy_to_bytes(num_items * <T as SszDecode>::ssz_fixed_len());
-
-            process_results(
-                variable_bytes
-                    .chunk()
-                    .chunks_exact(<T as SszDecode>::ssz_fixed_len())
-                    .map(|chunk| <T as SszDecode>::from_ssz_bytes(chunk)),
-                |iter| PersistentList::try_from_iter(iter),
-            )?
+            reserve_items::<T>(num_items)?;
+
+            let len = variable_bytes.remaining();
+            read_contiguous(variable_bytes, len, |bytes| {
+                process_results(
+                    bytes
+                        .chunks_exact(<T as SszType>::ssz_fixed_len())
+                        .map(|chunk| <T as SszDecode>::from_ssz_bytes(chunk)),
+                    |iter| PersistentList::try_from_iter(iter),
+                )
+            })?
             .map_err(|e| DecodeError::BytesInvalid(format!("Error processing results: {:?}", e)))
         } else {
-            // we move over variable_bytes to var_offsets (of type Bytes) since it has more methods for us to work with
-            // let mut var_offsets = variable_bytes.copy_to_bytes(variable_bytes.remaining());
-            let var_offsets = variable_bytes.chunk();
-
-            let first_offset = read_offset_from_slice(&var_offsets[0..BYTES_PER_LENGTH_OFFSET])?;
-            sanitize_offset(
-                first_offset,
-                None,
-                var_offsets[BYTES_PER_LENGTH_OFFSET..].len(),
-                Some(first_offset),
-            )?;
+            let num_bytes = variable_bytes.remaining();
+            let first_offset = read_offset_from_buf(variable_bytes)?;
+            sanitize_offset(first_offset, None, num_bytes, Some(first_offset))?;
             if first_offset % BYTES_PER_LENGTH_OFFSET != 0 || first_offset < BYTES_PER_LENGTH_OFFSET
             {
                 return Err(DecodeError::InvalidListFixedBytesLen(first_offset));
@@ -558,119 +663,66 @@ impl<T: SszDecode + Value, N: Unsigned> SszDecode for PersistentList<T, N> {
                     num_items, max_len
                 )));
             }
+            reserve_items::<T>(num_items)?;
 
-            // var_offsets now only contains the offsets, and var_items contains the list items (bytes)
-            // let mut var_items = var_offsets.split_off(num_items * BYTES_PER_LENGTH_OFFSET);
-            // ssz_decode_variable_length_items(var_offsets, &mut var_items)
-
-            let mut var_items = &var_offsets[(num_items * BYTES_PER_LENGTH_OFFSET)..];
-            ssz_decode_variable_length_items(
-                &var_offsets[..(num_items * BYTES_PER_LENGTH_OFFSET)],
-                &mut var_items,
-            )
+            ssz_decode_variable_length_items(first_offset, variable_bytes)
         }
     }
 }
 
+#[cfg(feature = "milhouse")]
 impl<T: SszDecode + Value, N: Unsigned> SszDecode for PersistentVector<T, N> {
-    fn is_ssz_static() -> bool {
-        T::is_ssz_static()
-    }
-
-    fn ssz_fixed_len() -> usize {
-        if T::is_ssz_static() {
-            <T as SszDecode>::ssz_fixed_len() * N::to_usize()
-        } else {
-            BYTES_PER_LENGTH_OFFSET
-        }
-    }
-
-    fn ssz_max_len() -> usize {
-        if T::is_ssz_static() {
-            <T as SszDecode>::ssz_fixed_len() * N::to_usize()
-        } else {
-            let mut len = T::ssz_max_len() * N::to_usize();
-            len += BYTES_PER_LENGTH_OFFSET * N::to_usize();
-            len
-        }
-    }
-
     fn ssz_read(
         fixed_bytes: &mut impl Buf,
         variable_bytes: &mut impl Buf,
     ) -> Result<Self, DecodeError> {
+        let _nesting = enter_nested()?;
         let len = N::to_usize();
+        reserve_items::<T>(len)?;
 
         // Vectors are either static, in which case the data is in the fixed bytes section
         // or it's dynamic and the data is in variable bytes.
-        // The vector is empty if both sections are empty.
-        if !(fixed_bytes.has_remaining() || variable_bytes.has_remaining()) {
-            Ok(Self::try_from(PersistentList::empty()).map_err(|e| {
-                DecodeError::BytesInvalid(format!("Error decoding empty vector: {:?}", e))
-            })?)
-        } else if T::is_ssz_static() {
+        // Either way they hold N items, so empty input is too short rather than an empty vector.
+        if T::is_ssz_static() {
             // T is static, so data resides in fixed_bytes
-            if fixed_bytes.remaining() < len * <T as SszDecode>::ssz_fixed_len() {
+            if fixed_bytes.remaining() < len * <T as SszType>::ssz_fixed_len() {
                 return Err(DecodeError::BytesInvalid(format!(
                     "Vector of {} items not equal to length {}",
                     fixed_bytes
                         .remaining()
-                        .checked_div(<T as SszDecode>::ssz_fixed_len())
+                        .checked_div(<T as SszType>::ssz_fixed_len())
                         .unwrap(),
                     len
                 )));
             }
 
-            // create slice of length `len * T::ssz_fixed_len`
-            // let bytes = fixed_bytes.copy_to_bytes(len * <T as SszDecode>::ssz_fixed_len());
-            let bytes = &fixed_bytes.chunk()[..(len * <T as SszDecode>::ssz_fixed_len())];
-
-            let res = process_results(
-                bytes
-                    .chunks_exact(<T as SszDecode>::ssz_fixed_len())
-                    .map(|chunk| <T as SszDecode>::from_ssz_bytes(chunk)),
-                |iter| PersistentVector::try_from_iter(iter),
+            read_contiguous(
+                fixed_bytes,
+                len * <T as SszType>::ssz_fixed_len(),
+                |bytes| {
+                    process_results(
+                        bytes
+                            .chunks_exact(<T as SszType>::ssz_fixed_len())
+                            .map(|chunk| <T as SszDecode>::from_ssz_bytes(chunk)),
+                        |iter| PersistentVector::try_from_iter(iter),
+                    )
+                },
             )?
-            .map_err(|e| DecodeError::BytesInvalid(format!("Error processing results: {:?}", e)));
-
-            fixed_bytes.advance(len * <T as SszDecode>::ssz_fixed_len());
-            res
+            .map_err(|e| DecodeError::BytesInvalid(format!("Error processing results: {:?}", e)))
         } else {
             // T is not static so data resides in variable_bytes
-            // let mut var_offsets = variable_bytes.copy_to_bytes(variable_bytes.remaining());
-            // let mut var_items = var_offsets.split_off(len * BYTES_PER_LENGTH_OFFSET);
-            // ssz_decode_variable_length_items(var_offsets, &mut var_items)
-
-            let var_offsets = &variable_bytes.chunk()[..(len * BYTES_PER_LENGTH_OFFSET)];
-            let mut var_items = &variable_bytes.chunk()[(len * BYTES_PER_LENGTH_OFFSET)..];
-            ssz_decode_variable_length_items(var_offsets, &mut var_items)
+            ssz_decode_variable_length_vector(len, variable_bytes)
         }
     }
 }
 
+#[cfg(feature = "std")]
 impl<T: SszDecode, N: Unsigned> SszDecode for VariableList<T, N> {
-    fn is_ssz_static() -> bool {
-        false
-    }
-
-    fn ssz_fixed_len() -> usize {
-        BYTES_PER_LENGTH_OFFSET
-    }
-
-    fn ssz_max_len() -> usize {
-        if T::is_ssz_static() {
-            <T as SszDecode>::ssz_fixed_len() * N::to_usize()
-        } else {
-            let mut len = T::ssz_max_len() * N::to_usize();
-            len += BYTES_PER_LENGTH_OFFSET * N::to_usize();
-            len
-        }
-    }
-
     fn ssz_read(
         _fixed_bytes: &mut impl Buf,
         variable_bytes: &mut impl Buf,
     ) -> Result<Self, DecodeError> {
+        let _nesting = enter_nested()?;
         let max_len = N::to_usize();
 
         if !variable_bytes.has_remaining() {
@@ -678,37 +730,39 @@ impl<T: SszDecode, N: Unsigned> SszDecode for VariableList<T, N> {
         } else if T::is_ssz_static() {
             let num_items = variable_bytes
                 .remaining()
-                .checked_div(<T as SszDecode>::ssz_fixed_len())
+                .checked_div(<T as SszType>::ssz_fixed_len())
                 .ok_or(DecodeError::ZeroLengthItem)?;
 
+            // a partial item at the end would be dropped by chunks_exact below
+            if num_items * <T as SszType>::ssz_fixed_len() != variable_bytes.remaining() {
+                return Err(DecodeError::TrailingBytes {
+                    len: variable_bytes.remaining(),
+                    expected: num_items * <T as SszType>::ssz_fixed_len(),
+                });
+            }
+
             if num_items > max_len {
                 return Err(DecodeError::BytesInvalid(format!(
                     "List of {} items exceeds maximum of {}",
                     num_items, max_len
                 )));
             }
-
-            // let bytes = variable_bytes.copy_to_bytes(num_items * <T as SszDecode>::ssz_fixed_len());
-
-            process_results(
-                variable_bytes
-                    .chunk()
-                    .chunks_exact(<T as SszDecode>::ssz_fixed_len())
-                    .map(|chunk| <T as SszDecode>::from_ssz_bytes(chunk)),
-                |iter| VariableList::try_from_iter(iter),
-            )?
+            reserve_items::<T>(num_items)?;
+
+            let len = variable_bytes.remaining();
+            read_contiguous(variable_bytes, len, |bytes| {
+                process_results(
+                    bytes
+                        .chunks_exact(<T as SszType>::ssz_fixed_len())
+                        .map(|chunk| <T as SszDecode>::from_ssz_bytes(chunk)),
+                    |iter| VariableList::try_from_iter(iter),
+                )
+            })?
             .map_err(|e| DecodeError::BytesInvalid(format!("Error processing results: {:?}", e)))
         } else {
-            // let mut var_offsets = variable_bytes.copy_to_bytes(variable_bytes.remaining());
-            let var_offsets = variable_bytes.chunk();
-
-            let first_offset = read_offset_from_slice(&var_offsets[0..BYTES_PER_LENGTH_OFFSET])?;
-            sanitize_offset(
-                first_offset,
-                None,
-                var_offsets[BYTES_PER_LENGTH_OFFSET..].len(),
-                Some(first_offset),
-            )?;
+            let num_bytes = variable_bytes.remaining();
+            let first_offset = read_offset_from_buf(variable_bytes)?;
+            sanitize_offset(first_offset, None, num_bytes, Some(first_offset))?;
             if first_offset % BYTES_PER_LENGTH_OFFSET != 0 || first_offset < BYTES_PER_LENGTH_OFFSET
             {
                 return Err(DecodeError::InvalidListFixedBytesLen(first_offset));
@@ -724,104 +778,64 @@ impl<T: SszDecode, N: Unsigned> SszDecode for VariableList<T, N> {
                     num_items, max_len
                 )));
             }
+            reserve_items::<T>(num_items)?;
 
-            let mut var_items = &var_offsets[(num_items * BYTES_PER_LENGTH_OFFSET)..];
-            ssz_decode_variable_length_items(
-                &var_offsets[..(num_items * BYTES_PER_LENGTH_OFFSET)],
-                &mut var_items,
-            )
+            ssz_decode_variable_length_items(first_offset, variable_bytes)
         }
     }
 }
 
+#[cfg(feature = "std")]
 impl<T: SszDecode, N: Unsigned> SszDecode for FixedVector<T, N> {
-    fn is_ssz_static() -> bool {
-        T::is_ssz_static()
-    }
-
-    fn ssz_fixed_len() -> usize {
-        if T::is_ssz_static() {
-            <T as SszDecode>::ssz_fixed_len() * N::to_usize()
-        } else {
-            BYTES_PER_LENGTH_OFFSET
-        }
-    }
-
-    fn ssz_max_len() -> usize {
-        if T::is_ssz_static() {
-            <T as SszDecode>::ssz_fixed_len() * N::to_usize()
-        } else {
-            let mut len = T::ssz_max_len() * N::to_usize();
-            len += BYTES_PER_LENGTH_OFFSET * N::to_usize();
-            len
-        }
-    }
-
     fn ssz_read(
         fixed_bytes: &mut impl Buf,
         variable_bytes: &mut impl Buf,
     ) -> Result<Self, DecodeError> {
+        let _nesting = enter_nested()?;
         let len = N::to_usize();
+        reserve_items::<T>(len)?;
 
-        if !(fixed_bytes.has_remaining() || variable_bytes.has_remaining()) {
-            Ok(Self::new(Vec::new()).map_err(|e| {
-                DecodeError::BytesInvalid(format!("Error deocoding empty vector: {:?}", e))
-            })?)
-        } else if T::is_ssz_static() {
-            if fixed_bytes.remaining() < len * <T as SszDecode>::ssz_fixed_len() {
+        // a vector holds N items, so empty input is too short rather than an empty vector
+        if T::is_ssz_static() {
+            if fixed_bytes.remaining() < len * <T as SszType>::ssz_fixed_len() {
                 return Err(DecodeError::BytesInvalid(format!(
                     "Vector of {} items not equal to length {}",
                     fixed_bytes
                         .remaining()
-                        .checked_div(<T as SszDecode>::ssz_fixed_len())
+                        .checked_div(<T as SszType>::ssz_fixed_len())
                         .unwrap(),
                     len
                 )));
             }
 
-            // create slice of length `len * T::ssz_fixed_len`
-            // let bytes = fixed_bytes.copy_to_bytes(len * <T as SszDecode>::ssz_fixed_len());
-            let bytes = &fixed_bytes.chunk()[..(len * <T as SszDecode>::ssz_fixed_len())];
-
-            // let res = process_results(
-            //     bytes
-            //         .chunks_exact(<T as SszDecode>::ssz_fixed_len())
-            //         .map(|chunk| <T as SszDecode>::from_ssz_bytes(chunk)),
-            //     |iter| FixedVector::try_from_iter(iter),
-            // )?
-            // .map_err(|e| DecodeError::BytesInvalid(format!("Error processing results: {:?}", e)));
-
-            // fixed_bytes.advance(len * <T as SszDecode>::ssz_fixed_len());
-            // res
-
-            let vec = bytes
-                .chunks_exact(<T as SszDecode>::ssz_fixed_len())
-                .try_fold(Vec::with_capacity(len), |mut vec, chunk| {
-                    vec.push(<T as SszDecode>::from_ssz_bytes(chunk)?);
-                    Ok(vec)
-                })?;
-            fixed_bytes.advance(len * <T as SszDecode>::ssz_fixed_len());
+            let vec = read_contiguous(
+                fixed_bytes,
+                len * <T as SszType>::ssz_fixed_len(),
+                |bytes| {
+                    bytes
+                        .chunks_exact(<T as SszType>::ssz_fixed_len())
+                        .try_fold(Vec::with_capacity(len), |mut vec, chunk| {
+                            vec.push(<T as SszDecode>::from_ssz_bytes(chunk)?);
+                            Ok::<_, DecodeError>(vec)
+                        })
+                },
+            )?;
             Self::new(vec).map_err(|e| {
                 DecodeError::BytesInvalid(format!("Wrong number of FixedVector elements: {:?}", e))
             })
         } else {
-            // let mut var_offsets = variable_bytes.copy_to_bytes(variable_bytes.remaining());
-            // let mut var_items = var_offsets.split_off(len * BYTES_PER_LENGTH_OFFSET);
-            // ssz_decode_variable_length_items(var_offsets, &mut var_items)
-
-            let var_offsets = &variable_bytes.chunk()[..(len * BYTES_PER_LENGTH_OFFSET)];
-            let mut var_items = &variable_bytes.chunk()[(len * BYTES_PER_LENGTH_OFFSET)..];
-            ssz_decode_variable_length_items(var_offsets, &mut var_items)
+            ssz_decode_variable_length_vector(len, variable_bytes)
         }
     }
 }
 
 pub trait TryFromIter<T>: Sized {
-    type Error: std::fmt::Debug;
+    type Error: core::fmt::Debug;
 
     fn try_from_iter(iter: impl Iterator<Item = T>) -> Result<Self, Self::Error>;
 }
 
+#[cfg(feature = "milhouse")]
 impl<T, N> TryFromIter<T> for PersistentList<T, N>
 where
     T: Value + SszDecode,
@@ -834,6 +848,19 @@ where
     }
 }
 
+impl<T> TryFromIter<T> for Vec<T>
+where
+    T: SszDecode,
+{
+    type Error = DecodeError;
+
+    // callers are expected to have bounded the number of items already
+    fn try_from_iter(iter: impl Iterator<Item = T>) -> Result<Self, Self::Error> {
+        Ok(iter.collect())
+    }
+}
+
+#[cfg(feature = "std")]
 impl<T, N> TryFromIter<T> for VariableList<T, N>
 where
     T: SszDecode,
@@ -848,7 +875,7 @@ where
         // Pre-allocate up to `N` elements based on the iterator size hint.
         let (_, opt_max_len) = iterator.size_hint();
         let mut l = Self::new(Vec::with_capacity(
-            opt_max_len.map_or(n, |max_len| std::cmp::min(n, max_len)),
+            opt_max_len.map_or(n, |max_len| core::cmp::min(n, max_len)),
         ))
         .map_err(|e| DecodeError::BytesInvalid(format!("Error processing results: {:?}", e)))?;
         for item in iterator {
@@ -860,6 +887,7 @@ where
     }
 }
 
+#[cfg(feature = "milhouse")]
 impl<T, N> TryFromIter<T> for PersistentVector<T, N>
 where
     T: Value + SszDecode,
@@ -872,6 +900,7 @@ where
     }
 }
 
+#[cfg(feature = "std")]
 impl<T, N> TryFromIter<T> for FixedVector<T, N>
 where
     T: SszDecode,
@@ -885,7 +914,7 @@ where
 
         let (_, opt_max_len) = iterator.size_hint();
         let mut vec =
-            Vec::with_capacity(opt_max_len.map_or(n, |max_len| std::cmp::min(n, max_len)));
+            Vec::with_capacity(opt_max_len.map_or(n, |max_len| core::cmp::min(n, max_len)));
 
         for item in iterator {
             // Bail out as soon as the length tries to exceed the limit. This guards against
@@ -906,42 +935,95 @@ where
     }
 }
 
-pub fn ssz_decode_variable_length_items<T: SszDecode, L: TryFromIter<T>>(
-    var_offsets: impl Buf,
-    var_items: &mut impl Buf,
+impl<T, const N: usize> TryFromIter<T> for [T; N]
+where
+    T: SszDecode,
+{
+    type Error = DecodeError;
+
+    fn try_from_iter(iter: impl Iterator<Item = T>) -> Result<Self, Self::Error> {
+        let mut vec = Vec::with_capacity(N);
+
+        for item in iter {
+            // Bail out as soon as the length tries to exceed the array length.
+            if vec.len() >= N {
+                return Err(DecodeError::BytesInvalid(format!(
+                    "Array of more than {} items",
+                    N
+                )));
+            }
+            vec.push(item);
+        }
+
+        let len = vec.len();
+        vec.try_into().map_err(|_| {
+            DecodeError::BytesInvalid(format!("Array of {} items not equal to length {}", len, N))
+        })
+    }
+}
+
+// decodes the len variable-size items of a vector (or array) from buf, which starts with their
+// offsets and spans the whole vector
+pub(crate) fn ssz_decode_variable_length_vector<T: SszDecode, L: TryFromIter<T>>(
+    len: usize,
+    buf: &mut impl Buf,
 ) -> Result<L, DecodeError> {
-    if !var_offsets.has_remaining() && !var_items.has_remaining() {
-        return L::try_from_iter(std::iter::empty()).map_err(|e| {
-            DecodeError::BytesInvalid(format!("Error trying to collect empty items: {:?}", e))
+    // SSZ has no vectors of length zero, and with variable-size items there would be no offset
+    // to tell where the vector ends
+    if len == 0 {
+        return Err(DecodeError::BytesInvalid(
+            "Vector of variable-size items with length 0".into(),
+        ));
+    }
+    let num_bytes = buf.remaining();
+    let offsets_len = len * BYTES_PER_LENGTH_OFFSET;
+    if num_bytes < offsets_len {
+        return Err(DecodeError::InvalidByteLength {
+            len: num_bytes,
+            expected: offsets_len,
         });
     }
 
-    // h/t the grandine team for this iter def:
-    // https://github.com/grandinetech/grandine/blob/develop/ssz/src/shared.rs#L174
-    // This iterator splits offset sections into offset sized chunks (of length BYTES_PER_LENGTH_OFFSET),
-    // reads them (maps to Result<usize, Error>), slides over them in windows of size 2,
-    // and calculates the length between offsets before decoding an item of that length (the last map).
-    // The .chain call is so we don't forget an offset at the end since it stops iterating
-    // when the window hits the the last chunk.
+    let first_offset = read_offset_from_buf(buf)?;
+    sanitize_offset(first_offset, None, num_bytes, Some(offsets_len))?;
+    ssz_decode_variable_length_items(first_offset, buf)
+}
+
+/// Decodes the variable-size items of a list or vector from `buf`, which holds their encoding
+/// after the offset of the first item, `first_offset`, has been read from it: the offsets of the
+/// other items, then the items themselves.
+///
+/// Each item is split off `buf` and decoded with `from_ssz_buf`, so items such as `ByteList`
+/// share memory with a `bytes::Bytes` input rather than being copied out of it.
+pub fn ssz_decode_variable_length_items<T: SszDecode, L: TryFromIter<T>>(
+    first_offset: usize,
+    buf: &mut impl Buf,
+) -> Result<L, DecodeError> {
+    // The first offset has to point right past the offsets, and every offset after it can't
+    // go back or past the end of the input, which also keeps each item within buf.
+    let num_bytes = buf.remaining() + BYTES_PER_LENGTH_OFFSET;
+    sanitize_offset(first_offset, None, num_bytes, None)?;
+    if first_offset % BYTES_PER_LENGTH_OFFSET != 0 || first_offset < BYTES_PER_LENGTH_OFFSET {
+        return Err(DecodeError::InvalidListFixedBytesLen(first_offset));
+    }
+
+    // the offsets are taken out of buf first (which only bumps a reference count on Bytes), as
+    // the end of each item is the offset of the next one
+    let mut offsets = buf.copy_to_bytes(first_offset - BYTES_PER_LENGTH_OFFSET);
+    let mut start = first_offset;
 
     process_results(
-        var_offsets
-            .chunk()
-            .chunks_exact(BYTES_PER_LENGTH_OFFSET)
-            .map(read_offset_from_slice)
-            .chain(core::iter::once(Ok(
-                var_offsets.remaining() + var_items.remaining()
-            )))
-            .tuple_windows()
-            .map(move |(start_result, end_result)| {
-                let start = start_result?;
-                let end = end_result?;
-                let len = end - start;
-                let bytes = &var_items.chunk()[..len];
-                let res = <T as SszDecode>::from_ssz_bytes(bytes);
-                var_items.advance(len);
-                res
-            }),
+        (0..first_offset / BYTES_PER_LENGTH_OFFSET).map(|_| {
+            let end = if offsets.has_remaining() {
+                read_offset_from_buf(&mut offsets)?
+            } else {
+                num_bytes
+            };
+            sanitize_offset(end, Some(start), num_bytes, None)?;
+            let item = read_item(buf, end - start);
+            start = end;
+            item
+        }),
         |iter| L::try_from_iter(iter),
     )?
     .map_err(|e| DecodeError::BytesInvalid(format!("Error processing results: {:?}", e)))
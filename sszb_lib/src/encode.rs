@@ -1,19 +1,15 @@
+use crate::SszType;
+use alloc::vec::Vec;
 use bytes::buf::BufMut;
 
 pub mod encode_impls;
 
 // Most of the complexity in implementing ssz macros arises from offset accounting.
 // Using the BufMut trait means that moving the buffer cursor is taken care of for us.
-pub trait SszEncode {
-    fn is_ssz_static() -> bool;
-
-    // all lengths are in number of bytes
-    fn ssz_fixed_len() -> usize;
+pub trait SszEncode: SszType {
+    // the number of bytes self encodes to
     fn ssz_bytes_len(&self) -> usize;
 
-    // helper function, use when preallocating the max bytes needed to encode this type
-    fn ssz_max_len() -> usize;
-
     // ssz_write_fixed either writes fixed types to the buffer,
     // or writes the offset to the buffer and increases the offset by self.ssz_bytes_len()
     fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl BufMut);
@@ -40,4 +36,15 @@ pub trait SszEncode {
         buf.reserve_exact(self.ssz_bytes_len());
         self.ssz_write(buf);
     }
+
+    // writes the items of a [Self; N] of static items, u8 overrides it to write them at once
+    #[doc(hidden)]
+    fn ssz_write_array(items: &[Self], buf: &mut impl BufMut)
+    where
+        Self: Sized,
+    {
+        for item in items {
+            item.ssz_write(buf);
+        }
+    }
 }
@@ -1,16 +1,27 @@
-use crate::{SszEncode, BYTES_PER_LENGTH_OFFSET};
-use alloy_primitives::{Address, Bloom, FixedBytes, U128, U256};
+use crate::{
+    ssz_element_max_len, ssz_element_min_len, SszEncode, SszType, BYTES_PER_LENGTH_OFFSET,
+};
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+#[cfg(feature = "alloy")]
+use alloy_primitives::{Address, Bloom, Bytes as AlloyBytes, FixedBytes, U128, U256};
 use bytes::buf::BufMut;
+#[cfg(feature = "ethereum-types")]
 use ethereum_types::{H160, H256, H32};
+#[cfg(feature = "milhouse")]
 use milhouse::{List as PersistentList, Value, Vector as PersistentVector};
 use paste::paste;
+#[cfg(feature = "std")]
 use ssz_types::{BitList, BitVector, FixedVector, VariableList};
-use std::sync::Arc;
+#[cfg(feature = "std")]
 use typenum::Unsigned;
 
 macro_rules! uint_sszb_encode {
     ($type: ident, $bit_size: expr) => {
-        impl SszEncode for $type {
+        impl SszType for $type {
             fn is_ssz_static() -> bool {
                 true
             }
@@ -19,11 +30,13 @@ macro_rules! uint_sszb_encode {
                 $bit_size / 8
             }
 
-            fn ssz_bytes_len(&self) -> usize {
+            fn ssz_max_len() -> usize {
                 $bit_size / 8
             }
+        }
 
-            fn ssz_max_len() -> usize {
+        impl SszEncode for $type {
+            fn ssz_bytes_len(&self) -> usize {
                 $bit_size / 8
             }
 
@@ -40,7 +53,7 @@ macro_rules! uint_sszb_encode {
     };
 }
 
-impl SszEncode for u8 {
+impl SszType for u8 {
     fn is_ssz_static() -> bool {
         true
     }
@@ -49,11 +62,13 @@ impl SszEncode for u8 {
         1
     }
 
-    fn ssz_bytes_len(&self) -> usize {
+    fn ssz_max_len() -> usize {
         1
     }
+}
 
-    fn ssz_max_len() -> usize {
+impl SszEncode for u8 {
+    fn ssz_bytes_len(&self) -> usize {
         1
     }
 
@@ -66,6 +81,10 @@ impl SszEncode for u8 {
     fn ssz_write(&self, buf: &mut impl BufMut) {
         buf.put_u8(*self);
     }
+
+    fn ssz_write_array(items: &[u8], buf: &mut impl BufMut) {
+        buf.put_slice(items);
+    }
 }
 
 uint_sszb_encode!(u16, 16);
@@ -73,7 +92,7 @@ uint_sszb_encode!(u32, 32);
 uint_sszb_encode!(u64, 64);
 uint_sszb_encode!(u128, 128);
 
-impl SszEncode for bool {
+impl SszType for bool {
     fn is_ssz_static() -> bool {
         true
     }
@@ -85,7 +104,9 @@ impl SszEncode for bool {
     fn ssz_max_len() -> usize {
         1
     }
+}
 
+impl SszEncode for bool {
     fn ssz_bytes_len(&self) -> usize {
         1
     }
@@ -101,35 +122,151 @@ impl SszEncode for bool {
     }
 }
 
-impl<const N: usize> SszEncode for [u8; N] {
+impl<T: SszType, const N: usize> SszType for [T; N] {
     fn is_ssz_static() -> bool {
-        true
+        T::is_ssz_static()
     }
 
     fn ssz_fixed_len() -> usize {
-        N
+        if T::is_ssz_static() {
+            T::ssz_fixed_len() * N
+        } else {
+            BYTES_PER_LENGTH_OFFSET
+        }
+    }
+
+    fn ssz_min_len() -> usize {
+        ssz_element_min_len::<T>() * N
     }
 
     fn ssz_max_len() -> usize {
-        N
+        ssz_element_max_len::<T>().saturating_mul(N)
     }
+}
 
+impl<T: SszEncode, const N: usize> SszEncode for [T; N] {
     fn ssz_bytes_len(&self) -> usize {
-        N
+        if T::is_ssz_static() {
+            T::ssz_fixed_len() * N
+        } else {
+            let mut len = self.iter().map(|item| item.ssz_bytes_len()).sum();
+            len += BYTES_PER_LENGTH_OFFSET * N;
+            len
+        }
     }
 
-    fn ssz_write_fixed(&self, _offset: &mut usize, buf: &mut impl BufMut) {
-        self.ssz_write(buf);
+    fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl BufMut) {
+        if T::is_ssz_static() {
+            self.ssz_write(buf);
+        } else {
+            buf.put_slice(&offset.to_le_bytes()[0..BYTES_PER_LENGTH_OFFSET]);
+            *offset += self.ssz_bytes_len();
+        }
     }
 
-    fn ssz_write_variable(&self, _buf: &mut impl BufMut) {}
+    fn ssz_write_variable(&self, buf: &mut impl BufMut) {
+        if !T::is_ssz_static() {
+            self.ssz_write(buf);
+        }
+    }
 
     fn ssz_write(&self, buf: &mut impl BufMut) {
-        buf.put_slice(self.as_slice());
+        if T::is_ssz_static() {
+            T::ssz_write_array(self, buf);
+        } else {
+            let offset = &mut (N * BYTES_PER_LENGTH_OFFSET);
+            for item in self {
+                item.ssz_write_fixed(offset, buf);
+            }
+            for item in self {
+                item.ssz_write(buf);
+            }
+        }
     }
 }
 
-impl SszEncode for Address {
+// tuples are encoded as anonymous containers, with one field per element
+macro_rules! tuple_sszb_encode {
+    ($(($idx: tt, $T: ident)),+) => {
+        impl<$($T: SszType),+> SszType for ($($T,)+) {
+            fn is_ssz_static() -> bool {
+                $(<$T as SszType>::is_ssz_static() &&)+ true
+            }
+
+            fn ssz_fixed_len() -> usize {
+                if <Self as SszType>::is_ssz_static() {
+                    0 $(+ <$T as SszType>::ssz_fixed_len())+
+                } else {
+                    BYTES_PER_LENGTH_OFFSET
+                }
+            }
+
+            fn ssz_min_len() -> usize {
+                0 $(+ ssz_element_min_len::<$T>())+
+            }
+
+            fn ssz_max_len() -> usize {
+                0usize $(.saturating_add(ssz_element_max_len::<$T>()))+
+            }
+        }
+
+        impl<$($T: SszEncode),+> SszEncode for ($($T,)+) {
+            fn ssz_bytes_len(&self) -> usize {
+                0 $(+ if <$T as SszType>::is_ssz_static() {
+                    <$T as SszType>::ssz_fixed_len()
+                } else {
+                    BYTES_PER_LENGTH_OFFSET + self.$idx.ssz_bytes_len()
+                })+
+            }
+
+            fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl BufMut) {
+                if <Self as SszType>::is_ssz_static() {
+                    self.ssz_write(buf);
+                } else {
+                    buf.put_slice(&offset.to_le_bytes()[0..BYTES_PER_LENGTH_OFFSET]);
+                    *offset += self.ssz_bytes_len();
+                }
+            }
+
+            fn ssz_write_variable(&self, buf: &mut impl BufMut) {
+                if !<Self as SszType>::is_ssz_static() {
+                    self.ssz_write(buf);
+                }
+            }
+
+            fn ssz_write(&self, buf: &mut impl BufMut) {
+                let offset = &mut (0 $(+ <$T as SszType>::ssz_fixed_len())+);
+                $(
+                    self.$idx.ssz_write_fixed(offset, buf);
+                )+
+                $(
+                    self.$idx.ssz_write_variable(buf);
+                )+
+            }
+        }
+    };
+}
+
+tuple_sszb_encode!((0, A));
+tuple_sszb_encode!((0, A), (1, B));
+tuple_sszb_encode!((0, A), (1, B), (2, C));
+tuple_sszb_encode!((0, A), (1, B), (2, C), (3, D));
+tuple_sszb_encode!((0, A), (1, B), (2, C), (3, D), (4, E));
+tuple_sszb_encode!((0, A), (1, B), (2, C), (3, D), (4, E), (5, F));
+tuple_sszb_encode!((0, A), (1, B), (2, C), (3, D), (4, E), (5, F), (6, G));
+tuple_sszb_encode!(
+    (0, A),
+    (1, B),
+    (2, C),
+    (3, D),
+    (4, E),
+    (5, F),
+    (6, G),
+    (7, H)
+);
+
+#[cfg(feature = "alloy")]
+impl SszType for Address {
     fn is_ssz_static() -> bool {
         true
     }
@@ -141,7 +278,10 @@ impl SszEncode for Address {
     fn ssz_max_len() -> usize {
         20
     }
+}
 
+#[cfg(feature = "alloy")]
+impl SszEncode for Address {
     fn ssz_bytes_len(&self) -> usize {
         20
     }
@@ -157,7 +297,8 @@ impl SszEncode for Address {
     }
 }
 
-impl<const N: usize> SszEncode for FixedBytes<N> {
+#[cfg(feature = "alloy")]
+impl<const N: usize> SszType for FixedBytes<N> {
     fn is_ssz_static() -> bool {
         true
     }
@@ -169,7 +310,10 @@ impl<const N: usize> SszEncode for FixedBytes<N> {
     fn ssz_max_len() -> usize {
         N
     }
+}
 
+#[cfg(feature = "alloy")]
+impl<const N: usize> SszEncode for FixedBytes<N> {
     fn ssz_bytes_len(&self) -> usize {
         N
     }
@@ -185,20 +329,24 @@ impl<const N: usize> SszEncode for FixedBytes<N> {
     }
 }
 
-impl SszEncode for Bloom {
+#[cfg(feature = "alloy")]
+impl SszType for Bloom {
     fn is_ssz_static() -> bool {
         true
     }
 
-    fn ssz_bytes_len(&self) -> usize {
+    fn ssz_fixed_len() -> usize {
         256
     }
 
-    fn ssz_fixed_len() -> usize {
+    fn ssz_max_len() -> usize {
         256
     }
+}
 
-    fn ssz_max_len() -> usize {
+#[cfg(feature = "alloy")]
+impl SszEncode for Bloom {
+    fn ssz_bytes_len(&self) -> usize {
         256
     }
 
@@ -213,22 +361,62 @@ impl SszEncode for Bloom {
     }
 }
 
-impl SszEncode for U256 {
+// alloy Bytes is encoded as a ByteList, with its maximum declared by the #[ssz(max = N)] field attribute
+#[cfg(feature = "alloy")]
+impl SszType for AlloyBytes {
     fn is_ssz_static() -> bool {
-        true
+        false
     }
 
     fn ssz_fixed_len() -> usize {
-        32
+        BYTES_PER_LENGTH_OFFSET
     }
 
+    fn ssz_max_len() -> usize {
+        usize::MAX
+    }
+}
+
+#[cfg(feature = "alloy")]
+impl SszEncode for AlloyBytes {
     fn ssz_bytes_len(&self) -> usize {
+        self.len()
+    }
+
+    fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl BufMut) {
+        buf.put_slice(&offset.to_le_bytes()[0..BYTES_PER_LENGTH_OFFSET]);
+        *offset += self.ssz_bytes_len();
+    }
+
+    fn ssz_write_variable(&self, buf: &mut impl BufMut) {
+        self.ssz_write(buf);
+    }
+
+    fn ssz_write(&self, buf: &mut impl BufMut) {
+        buf.put_slice(self);
+    }
+}
+
+#[cfg(feature = "alloy")]
+impl SszType for U256 {
+    fn is_ssz_static() -> bool {
+        true
+    }
+
+    fn ssz_fixed_len() -> usize {
         32
     }
 
     fn ssz_max_len() -> usize {
         32
     }
+}
+
+#[cfg(feature = "alloy")]
+impl SszEncode for U256 {
+    fn ssz_bytes_len(&self) -> usize {
+        32
+    }
 
     fn ssz_write_fixed(&self, _offset: &mut usize, buf: &mut impl BufMut) {
         self.ssz_write(buf);
@@ -241,7 +429,8 @@ impl SszEncode for U256 {
     }
 }
 
-impl SszEncode for U128 {
+#[cfg(feature = "alloy")]
+impl SszType for U128 {
     fn is_ssz_static() -> bool {
         true
     }
@@ -250,11 +439,14 @@ impl SszEncode for U128 {
         16
     }
 
-    fn ssz_bytes_len(&self) -> usize {
+    fn ssz_max_len() -> usize {
         16
     }
+}
 
-    fn ssz_max_len() -> usize {
+#[cfg(feature = "alloy")]
+impl SszEncode for U128 {
+    fn ssz_bytes_len(&self) -> usize {
         16
     }
 
@@ -269,7 +461,8 @@ impl SszEncode for U128 {
     }
 }
 
-impl SszEncode for H32 {
+#[cfg(feature = "ethereum-types")]
+impl SszType for H32 {
     fn is_ssz_static() -> bool {
         true
     }
@@ -278,11 +471,14 @@ impl SszEncode for H32 {
         4
     }
 
-    fn ssz_bytes_len(&self) -> usize {
+    fn ssz_max_len() -> usize {
         4
     }
+}
 
-    fn ssz_max_len() -> usize {
+#[cfg(feature = "ethereum-types")]
+impl SszEncode for H32 {
+    fn ssz_bytes_len(&self) -> usize {
         4
     }
 
@@ -297,7 +493,8 @@ impl SszEncode for H32 {
     }
 }
 
-impl SszEncode for H160 {
+#[cfg(feature = "ethereum-types")]
+impl SszType for H160 {
     fn is_ssz_static() -> bool {
         true
     }
@@ -306,11 +503,14 @@ impl SszEncode for H160 {
         20
     }
 
-    fn ssz_bytes_len(&self) -> usize {
+    fn ssz_max_len() -> usize {
         20
     }
+}
 
-    fn ssz_max_len() -> usize {
+#[cfg(feature = "ethereum-types")]
+impl SszEncode for H160 {
+    fn ssz_bytes_len(&self) -> usize {
         20
     }
 
@@ -325,7 +525,8 @@ impl SszEncode for H160 {
     }
 }
 
-impl SszEncode for H256 {
+#[cfg(feature = "ethereum-types")]
+impl SszType for H256 {
     fn is_ssz_static() -> bool {
         true
     }
@@ -334,11 +535,14 @@ impl SszEncode for H256 {
         32
     }
 
-    fn ssz_bytes_len(&self) -> usize {
+    fn ssz_max_len() -> usize {
         32
     }
+}
 
-    fn ssz_max_len() -> usize {
+#[cfg(feature = "ethereum-types")]
+impl SszEncode for H256 {
+    fn ssz_bytes_len(&self) -> usize {
         32
     }
 
@@ -353,21 +557,25 @@ impl SszEncode for H256 {
     }
 }
 
-impl<N: Unsigned + Clone> SszEncode for BitVector<N> {
+#[cfg(feature = "std")]
+impl<N: Unsigned + Clone> SszType for BitVector<N> {
     fn is_ssz_static() -> bool {
         true
     }
 
     fn ssz_fixed_len() -> usize {
-        std::cmp::max(1, (N::to_usize() + 7) / 8)
+        core::cmp::max(1, (N::to_usize() + 7) / 8)
     }
 
-    fn ssz_bytes_len(&self) -> usize {
-        self.as_slice().len()
+    fn ssz_max_len() -> usize {
+        core::cmp::max(1, (N::to_usize() + 7) / 8)
     }
+}
 
-    fn ssz_max_len() -> usize {
-        std::cmp::max(1, (N::to_usize() + 7) / 8)
+#[cfg(feature = "std")]
+impl<N: Unsigned + Clone> SszEncode for BitVector<N> {
+    fn ssz_bytes_len(&self) -> usize {
+        self.as_slice().len()
     }
 
     fn ssz_write_fixed(&self, _offset: &mut usize, buf: &mut impl BufMut) {
@@ -381,7 +589,8 @@ impl<N: Unsigned + Clone> SszEncode for BitVector<N> {
     }
 }
 
-impl<N: Unsigned + Clone> SszEncode for BitList<N> {
+#[cfg(feature = "std")]
+impl<N: Unsigned + Clone> SszType for BitList<N> {
     fn is_ssz_static() -> bool {
         false
     }
@@ -390,12 +599,21 @@ impl<N: Unsigned + Clone> SszEncode for BitList<N> {
         BYTES_PER_LENGTH_OFFSET
     }
 
-    fn ssz_bytes_len(&self) -> usize {
-        self.clone().into_bytes().len()
+    // an empty bitlist is just its length bit
+    fn ssz_min_len() -> usize {
+        1
     }
 
+    // N bits followed by the length bit
     fn ssz_max_len() -> usize {
-        std::cmp::max(1, (N::to_usize() + 7) / 8)
+        N::to_usize() / 8 + 1
+    }
+}
+
+#[cfg(feature = "std")]
+impl<N: Unsigned + Clone> SszEncode for BitList<N> {
+    fn ssz_bytes_len(&self) -> usize {
+        self.clone().into_bytes().len()
     }
 
     fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl BufMut) {
@@ -414,7 +632,52 @@ impl<N: Unsigned + Clone> SszEncode for BitList<N> {
     }
 }
 
-impl<T: SszEncode> SszEncode for Arc<T> {
+// smart pointers are encoded as the value they point to
+macro_rules! pointer_sszb_encode {
+    ($pointer: ident) => {
+        impl<T: SszType> SszType for $pointer<T> {
+            fn is_ssz_static() -> bool {
+                T::is_ssz_static()
+            }
+
+            fn ssz_fixed_len() -> usize {
+                T::ssz_fixed_len()
+            }
+
+            fn ssz_min_len() -> usize {
+                T::ssz_min_len()
+            }
+
+            fn ssz_max_len() -> usize {
+                T::ssz_max_len()
+            }
+        }
+
+        impl<T: SszEncode> SszEncode for $pointer<T> {
+            fn ssz_bytes_len(&self) -> usize {
+                self.as_ref().ssz_bytes_len()
+            }
+
+            fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl BufMut) {
+                self.as_ref().ssz_write_fixed(offset, buf);
+            }
+
+            fn ssz_write_variable(&self, buf: &mut impl BufMut) {
+                self.as_ref().ssz_write_variable(buf);
+            }
+
+            fn ssz_write(&self, buf: &mut impl BufMut) {
+                self.as_ref().ssz_write(buf);
+            }
+        }
+    };
+}
+
+pointer_sszb_encode!(Arc);
+pointer_sszb_encode!(Box);
+pointer_sszb_encode!(Rc);
+
+impl<T: SszType + Clone> SszType for Cow<'_, T> {
     fn is_ssz_static() -> bool {
         T::is_ssz_static()
     }
@@ -423,10 +686,16 @@ impl<T: SszEncode> SszEncode for Arc<T> {
         T::ssz_fixed_len()
     }
 
+    fn ssz_min_len() -> usize {
+        T::ssz_min_len()
+    }
+
     fn ssz_max_len() -> usize {
         T::ssz_max_len()
     }
+}
 
+impl<T: SszEncode + Clone> SszEncode for Cow<'_, T> {
     fn ssz_bytes_len(&self) -> usize {
         self.as_ref().ssz_bytes_len()
     }
@@ -444,7 +713,68 @@ impl<T: SszEncode> SszEncode for Arc<T> {
     }
 }
 
-impl<T: SszEncode + Value, N: Unsigned> SszEncode for PersistentList<T, N> {
+// Vec<T> and Box<[T]> are encoded as SSZ lists, but their maximum length is not part of the type.
+// Containers declare it with the #[ssz(max = N)] field attribute instead, so ssz_max_len is unbounded.
+macro_rules! unbounded_list_sszb_encode {
+    ($type: ty) => {
+        impl<T: SszType> SszType for $type {
+            fn is_ssz_static() -> bool {
+                false
+            }
+
+            fn ssz_fixed_len() -> usize {
+                BYTES_PER_LENGTH_OFFSET
+            }
+
+            fn ssz_max_len() -> usize {
+                usize::MAX
+            }
+        }
+
+        impl<T: SszEncode> SszEncode for $type {
+            fn ssz_bytes_len(&self) -> usize {
+                if T::is_ssz_static() {
+                    T::ssz_fixed_len() * self.len()
+                } else {
+                    let mut len = self.iter().map(|item| item.ssz_bytes_len()).sum();
+                    len += BYTES_PER_LENGTH_OFFSET * self.len();
+                    len
+                }
+            }
+
+            fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl BufMut) {
+                buf.put_slice(&offset.to_le_bytes()[0..BYTES_PER_LENGTH_OFFSET]);
+                *offset += self.ssz_bytes_len();
+            }
+
+            fn ssz_write_variable(&self, buf: &mut impl BufMut) {
+                self.ssz_write(buf);
+            }
+
+            fn ssz_write(&self, buf: &mut impl BufMut) {
+                if T::is_ssz_static() {
+                    for item in self.iter() {
+                        item.ssz_write(buf);
+                    }
+                } else {
+                    let offset = &mut (self.len() * BYTES_PER_LENGTH_OFFSET);
+                    for item in self.iter() {
+                        item.ssz_write_fixed(offset, buf);
+                    }
+                    for item in self.iter() {
+                        item.ssz_write(buf);
+                    }
+                }
+            }
+        }
+    };
+}
+
+unbounded_list_sszb_encode!(Vec<T>);
+unbounded_list_sszb_encode!(Box<[T]>);
+
+// Option<T> is encoded as the SSZ Union[None, T], where selector 0 is None and selector 1 is T
+impl<T: SszType> SszType for Option<T> {
     fn is_ssz_static() -> bool {
         false
     }
@@ -453,13 +783,64 @@ impl<T: SszEncode + Value, N: Unsigned> SszEncode for PersistentList<T, N> {
         BYTES_PER_LENGTH_OFFSET
     }
 
+    // just the selector of None
+    fn ssz_min_len() -> usize {
+        1
+    }
+
     fn ssz_max_len() -> usize {
-        T::ssz_max_len() * N::to_usize()
+        T::ssz_max_len().saturating_add(1)
     }
+}
 
+impl<T: SszEncode> SszEncode for Option<T> {
     fn ssz_bytes_len(&self) -> usize {
-        if <T as SszEncode>::is_ssz_static() {
-            <T as SszEncode>::ssz_fixed_len() * self.len()
+        match self {
+            None => 1,
+            Some(item) => 1 + item.ssz_bytes_len(),
+        }
+    }
+
+    fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl BufMut) {
+        buf.put_slice(&offset.to_le_bytes()[0..BYTES_PER_LENGTH_OFFSET]);
+        *offset += self.ssz_bytes_len();
+    }
+
+    fn ssz_write_variable(&self, buf: &mut impl BufMut) {
+        self.ssz_write(buf);
+    }
+
+    fn ssz_write(&self, buf: &mut impl BufMut) {
+        match self {
+            None => buf.put_u8(0),
+            Some(item) => {
+                buf.put_u8(1);
+                item.ssz_write(buf);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "milhouse")]
+impl<T: SszType + Value, N: Unsigned> SszType for PersistentList<T, N> {
+    fn is_ssz_static() -> bool {
+        false
+    }
+
+    fn ssz_fixed_len() -> usize {
+        BYTES_PER_LENGTH_OFFSET
+    }
+
+    fn ssz_max_len() -> usize {
+        ssz_element_max_len::<T>().saturating_mul(N::to_usize())
+    }
+}
+
+#[cfg(feature = "milhouse")]
+impl<T: SszEncode + Value, N: Unsigned> SszEncode for PersistentList<T, N> {
+    fn ssz_bytes_len(&self) -> usize {
+        if <T as SszType>::is_ssz_static() {
+            <T as SszType>::ssz_fixed_len() * self.len()
         } else {
             let mut len = self.iter().map(|item| SszEncode::ssz_bytes_len(item)).sum();
             len += BYTES_PER_LENGTH_OFFSET * self.len();
@@ -493,26 +874,34 @@ impl<T: SszEncode + Value, N: Unsigned> SszEncode for PersistentList<T, N> {
     }
 }
 
-impl<T: SszEncode + Value, N: Unsigned> SszEncode for PersistentVector<T, N> {
+#[cfg(feature = "milhouse")]
+impl<T: SszType + Value, N: Unsigned> SszType for PersistentVector<T, N> {
     fn is_ssz_static() -> bool {
         T::is_ssz_static()
     }
 
     fn ssz_fixed_len() -> usize {
-        if <T as SszEncode>::is_ssz_static() {
-            <T as SszEncode>::ssz_fixed_len() * N::to_usize()
+        if T::is_ssz_static() {
+            <T as SszType>::ssz_fixed_len() * N::to_usize()
         } else {
             BYTES_PER_LENGTH_OFFSET
         }
     }
 
+    fn ssz_min_len() -> usize {
+        ssz_element_min_len::<T>() * N::to_usize()
+    }
+
     fn ssz_max_len() -> usize {
-        T::ssz_max_len() * N::to_usize()
+        ssz_element_max_len::<T>().saturating_mul(N::to_usize())
     }
+}
 
+#[cfg(feature = "milhouse")]
+impl<T: SszEncode + Value, N: Unsigned> SszEncode for PersistentVector<T, N> {
     fn ssz_bytes_len(&self) -> usize {
-        if <T as SszEncode>::is_ssz_static() {
-            <T as SszEncode>::ssz_fixed_len() * N::to_usize()
+        if <T as SszType>::is_ssz_static() {
+            <T as SszType>::ssz_fixed_len() * N::to_usize()
         } else {
             let mut len = self.iter().map(|item| SszEncode::ssz_bytes_len(item)).sum();
             len += BYTES_PER_LENGTH_OFFSET * N::to_usize();
@@ -552,19 +941,26 @@ impl<T: SszEncode + Value, N: Unsigned> SszEncode for PersistentVector<T, N> {
     }
 }
 
-impl<T: SszEncode, N: Unsigned> SszEncode for VariableList<T, N> {
+#[cfg(feature = "std")]
+impl<T: SszType, N: Unsigned> SszType for VariableList<T, N> {
     fn is_ssz_static() -> bool {
         false
     }
+
     fn ssz_fixed_len() -> usize {
         BYTES_PER_LENGTH_OFFSET
     }
+
     fn ssz_max_len() -> usize {
-        T::ssz_max_len() * N::to_usize()
+        ssz_element_max_len::<T>().saturating_mul(N::to_usize())
     }
+}
+
+#[cfg(feature = "std")]
+impl<T: SszEncode, N: Unsigned> SszEncode for VariableList<T, N> {
     fn ssz_bytes_len(&self) -> usize {
-        if <T as SszEncode>::is_ssz_static() {
-            <T as SszEncode>::ssz_fixed_len() * self.len()
+        if <T as SszType>::is_ssz_static() {
+            <T as SszType>::ssz_fixed_len() * self.len()
         } else {
             let mut len = self.iter().map(|item| SszEncode::ssz_bytes_len(item)).sum();
             len += BYTES_PER_LENGTH_OFFSET * self.len();
@@ -595,26 +991,34 @@ impl<T: SszEncode, N: Unsigned> SszEncode for VariableList<T, N> {
     }
 }
 
-impl<T: SszEncode, N: Unsigned> SszEncode for FixedVector<T, N> {
+#[cfg(feature = "std")]
+impl<T: SszType, N: Unsigned> SszType for FixedVector<T, N> {
     fn is_ssz_static() -> bool {
         T::is_ssz_static()
     }
 
     fn ssz_fixed_len() -> usize {
-        if <T as SszEncode>::is_ssz_static() {
-            <T as SszEncode>::ssz_fixed_len() * N::to_usize()
+        if T::is_ssz_static() {
+            T::ssz_fixed_len() * N::to_usize()
         } else {
             BYTES_PER_LENGTH_OFFSET
         }
     }
 
+    fn ssz_min_len() -> usize {
+        ssz_element_min_len::<T>() * N::to_usize()
+    }
+
     fn ssz_max_len() -> usize {
-        T::ssz_max_len() * N::to_usize()
+        ssz_element_max_len::<T>().saturating_mul(N::to_usize())
     }
+}
 
+#[cfg(feature = "std")]
+impl<T: SszEncode, N: Unsigned> SszEncode for FixedVector<T, N> {
     fn ssz_bytes_len(&self) -> usize {
-        if <T as SszEncode>::is_ssz_static() {
-            <T as SszEncode>::ssz_fixed_len() * N::to_usize()
+        if <T as SszType>::is_ssz_static() {
+            <T as SszType>::ssz_fixed_len() * N::to_usize()
         } else {
             let mut len = self.iter().map(|item| SszEncode::ssz_bytes_len(item)).sum();
             len += BYTES_PER_LENGTH_OFFSET * N::to_usize();
@@ -0,0 +1,450 @@
+//! Reading and writing `.era` archive files.
+//!
+//! An e2store file is a sequence of records, each an 8 byte header (a 2 byte type, a 4 byte
+//! little-endian data length and 2 reserved zero bytes) followed by the data. An era file holds
+//! the blocks of one era of `SLOTS_PER_HISTORICAL_ROOT` slots and the state at its end:
+//!
+//! ```text
+//! Version | CompressedSignedBeaconBlock* | CompressedBeaconState | SlotIndex(block)? | SlotIndex(state)
+//! ```
+//!
+//! Blocks and the state are snappy frame compressed SSZ. A slot index holds the starting slot,
+//! the offset of the record of every slot relative to the start of the index record (zero for
+//! slots without a block) and the number of slots. Era 0 only holds the genesis state and has no
+//! block index.
+//!
+//! See <https://github.com/eth-clients/e2store-format-specs> for the format. [`EraReader`] and
+//! [`EraWriter`] are generic over the block and state types, which are decoded lazily.
+use crate::snappy::{decode_frames, encode_frames, SnappyError};
+use crate::{SszDecode, SszEncode};
+use std::io::Write;
+
+pub const SLOTS_PER_HISTORICAL_ROOT: u64 = 8192;
+
+pub const VERSION: [u8; 2] = [0x65, 0x32];
+pub const EMPTY: [u8; 2] = [0x00, 0x00];
+pub const COMPRESSED_SIGNED_BEACON_BLOCK: [u8; 2] = [0x01, 0x00];
+pub const COMPRESSED_BEACON_STATE: [u8; 2] = [0x02, 0x00];
+pub const SLOT_INDEX: [u8; 2] = [0x69, 0x32];
+
+const HEADER_LEN: usize = 8;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum EraError {
+    /// The record at `offset` runs past the end of the file.
+    TruncatedRecord { offset: usize },
+    /// The reserved bytes of the record header at `offset` are not zero.
+    ReservedNotZero { offset: usize },
+    /// The record at `offset` is not of the type expected there.
+    UnexpectedRecord { offset: usize, kind: [u8; 2] },
+    /// A slot index is malformed or inconsistent with the file.
+    InvalidSlotIndex(String),
+    /// The slot is outside of the era, or not after the previous block written.
+    InvalidSlot(u64),
+    /// The era starts past the last slot a `u64` can hold.
+    InvalidEra(u64),
+    /// A block or the state could not be decompressed or decoded.
+    Snappy(SnappyError),
+    /// Writing the file failed.
+    Io(String),
+}
+
+impl From<SnappyError> for EraError {
+    fn from(e: SnappyError) -> Self {
+        EraError::Snappy(e)
+    }
+}
+
+impl From<std::io::Error> for EraError {
+    fn from(e: std::io::Error) -> Self {
+        EraError::Io(e.to_string())
+    }
+}
+
+/// An e2store record.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Entry<'a> {
+    pub kind: [u8; 2],
+    pub data: &'a [u8],
+}
+
+/// Reads the record starting at `offset` in `bytes`.
+pub fn read_entry(bytes: &[u8], offset: usize) -> Result<Entry<'_>, EraError> {
+    let header = offset
+        .checked_add(HEADER_LEN)
+        .and_then(|end| bytes.get(offset..end))
+        .ok_or(EraError::TruncatedRecord { offset })?;
+    if header[6..8] != [0, 0] {
+        return Err(EraError::ReservedNotZero { offset });
+    }
+    let len = u32::from_le_bytes(header[2..6].try_into().unwrap()) as usize;
+    let start = offset + HEADER_LEN;
+    let data = bytes
+        .get(start..start + len)
+        .ok_or(EraError::TruncatedRecord { offset })?;
+    Ok(Entry {
+        kind: [header[0], header[1]],
+        data,
+    })
+}
+
+/// Iterates over the records of an e2store file along with their offsets, stopping after the
+/// first malformed record.
+pub fn entries(bytes: &[u8]) -> impl Iterator<Item = Result<(usize, Entry<'_>), EraError>> {
+    let mut offset = Some(0);
+    std::iter::from_fn(move || {
+        let current = offset.filter(|current| *current < bytes.len())?;
+        match read_entry(bytes, current) {
+            Ok(entry) => {
+                offset = Some(current + HEADER_LEN + entry.data.len());
+                Some(Ok((current, entry)))
+            }
+            Err(e) => {
+                offset = None;
+                Some(Err(e))
+            }
+        }
+    })
+}
+
+fn write_entry(writer: &mut impl Write, kind: [u8; 2], data: &[u8]) -> Result<usize, EraError> {
+    let len = u32::try_from(data.len())
+        .map_err(|_| EraError::Io(format!("record of {} bytes is too long", data.len())))?;
+    writer.write_all(&kind)?;
+    writer.write_all(&len.to_le_bytes())?;
+    writer.write_all(&[0, 0])?;
+    writer.write_all(data)?;
+    Ok(HEADER_LEN + data.len())
+}
+
+/// The data of a `SlotIndex` record.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SlotIndex {
+    pub starting_slot: u64,
+    /// The offset of the record of each slot relative to the start of the index record, or zero
+    /// if the slot has no record.
+    pub offsets: Vec<i64>,
+}
+
+impl SlotIndex {
+    pub fn from_bytes(data: &[u8]) -> Result<Self, EraError> {
+        if data.len() < 16 || data.len() % 8 != 0 {
+            return Err(EraError::InvalidSlotIndex(format!(
+                "{} bytes is not a valid slot index length",
+                data.len()
+            )));
+        }
+        let mut words = data
+            .chunks_exact(8)
+            .map(|word| i64::from_le_bytes(word.try_into().unwrap()));
+        let starting_slot = words.next().unwrap() as u64;
+        let count = words.next_back().unwrap();
+        let offsets = words.collect::<Vec<_>>();
+        if count != offsets.len() as i64 {
+            return Err(EraError::InvalidSlotIndex(format!(
+                "count of {} does not match the {} offsets",
+                count,
+                offsets.len()
+            )));
+        }
+        Ok(Self {
+            starting_slot,
+            offsets,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(16 + 8 * self.offsets.len());
+        data.extend_from_slice(&self.starting_slot.to_le_bytes());
+        for offset in &self.offsets {
+            data.extend_from_slice(&offset.to_le_bytes());
+        }
+        data.extend_from_slice(&(self.offsets.len() as u64).to_le_bytes());
+        data
+    }
+
+    // the length of the index record for `count` slots
+    fn record_len(count: usize) -> usize {
+        HEADER_LEN + 16 + 8 * count
+    }
+}
+
+// a slot index read from the file, along with where it starts
+#[derive(Debug, Clone)]
+struct IndexRecord {
+    position: usize,
+    index: SlotIndex,
+}
+
+impl IndexRecord {
+    // reads the index record that ends at `end`
+    fn read_ending_at(bytes: &[u8], end: usize) -> Result<Self, EraError> {
+        let count = end
+            .checked_sub(8)
+            .and_then(|start| bytes.get(start..end))
+            .map(|count| u64::from_le_bytes(count.try_into().unwrap()))
+            .ok_or(EraError::InvalidSlotIndex("missing slot index".to_string()))?;
+        let position = usize::try_from(count)
+            .ok()
+            .and_then(|count| count.checked_mul(8))
+            .and_then(|len| end.checked_sub(SlotIndex::record_len(0) + len))
+            .ok_or(EraError::InvalidSlotIndex(format!(
+                "count of {} does not fit in the file",
+                count
+            )))?;
+
+        let entry = read_entry(bytes, position)?;
+        if entry.kind != SLOT_INDEX {
+            return Err(EraError::UnexpectedRecord {
+                offset: position,
+                kind: entry.kind,
+            });
+        }
+        let index = SlotIndex::from_bytes(entry.data)?;
+        Ok(Self { position, index })
+    }
+
+    // the position of the record of the slot at `i` in the index, if it has one
+    fn record_position(&self, i: usize) -> Option<Result<usize, EraError>> {
+        let offset = *self.index.offsets.get(i)?;
+        if offset == 0 {
+            return None;
+        }
+        Some(
+            (self.position as i64)
+                .checked_add(offset)
+                .and_then(|position| usize::try_from(position).ok())
+                .filter(|position| *position < self.position)
+                .ok_or(EraError::InvalidSlotIndex(format!(
+                    "offset {} of slot {} points outside of the file",
+                    offset,
+                    self.index.starting_slot + i as u64
+                ))),
+        )
+    }
+
+    // checks that every record the index points to has the given type
+    fn validate(&self, bytes: &[u8], kind: [u8; 2]) -> Result<(), EraError> {
+        for i in 0..self.index.offsets.len() {
+            if let Some(position) = self.record_position(i) {
+                let position = position?;
+                let entry = read_entry(bytes, position)?;
+                if entry.kind != kind {
+                    return Err(EraError::UnexpectedRecord {
+                        offset: position,
+                        kind: entry.kind,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reads the blocks and state of an era file. Opening the file reads and validates its slot
+/// indexes, blocks and the state are only decompressed and decoded when they are read.
+pub struct EraReader<'a> {
+    bytes: &'a [u8],
+    block_index: Option<IndexRecord>,
+    state_index: IndexRecord,
+}
+
+impl<'a> EraReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Result<Self, EraError> {
+        let version = read_entry(bytes, 0)?;
+        if version.kind != VERSION || !version.data.is_empty() {
+            return Err(EraError::UnexpectedRecord {
+                offset: 0,
+                kind: version.kind,
+            });
+        }
+
+        let state_index = IndexRecord::read_ending_at(bytes, bytes.len())?;
+        if state_index.index.offsets.len() != 1 {
+            return Err(EraError::InvalidSlotIndex(format!(
+                "state index has {} slots instead of 1",
+                state_index.index.offsets.len()
+            )));
+        }
+        if state_index.index.offsets[0] == 0 {
+            return Err(EraError::InvalidSlotIndex(
+                "state index has no state".to_string(),
+            ));
+        }
+        state_index.validate(bytes, COMPRESSED_BEACON_STATE)?;
+
+        let era_start = state_index.index.starting_slot;
+        if era_start % SLOTS_PER_HISTORICAL_ROOT != 0 {
+            return Err(EraError::InvalidSlotIndex(format!(
+                "state slot {} is not the first slot of an era",
+                era_start
+            )));
+        }
+        let block_index = if era_start == 0 {
+            None
+        } else {
+            let block_index = IndexRecord::read_ending_at(bytes, state_index.position)?;
+            let index = &block_index.index;
+            if index.offsets.len() as u64 != SLOTS_PER_HISTORICAL_ROOT
+                || index.starting_slot.checked_add(SLOTS_PER_HISTORICAL_ROOT) != Some(era_start)
+            {
+                return Err(EraError::InvalidSlotIndex(format!(
+                    "block index of {} slots from slot {} does not end at the state slot {}",
+                    index.offsets.len(),
+                    index.starting_slot,
+                    era_start
+                )));
+            }
+            block_index.validate(bytes, COMPRESSED_SIGNED_BEACON_BLOCK)?;
+            Some(block_index)
+        };
+
+        Ok(Self {
+            bytes,
+            block_index,
+            state_index,
+        })
+    }
+
+    pub fn era(&self) -> u64 {
+        self.state_slot() / SLOTS_PER_HISTORICAL_ROOT
+    }
+
+    /// The slot of the state, which is the first slot of the next era.
+    pub fn state_slot(&self) -> u64 {
+        self.state_index.index.starting_slot
+    }
+
+    pub fn block_index(&self) -> Option<&SlotIndex> {
+        self.block_index.as_ref().map(|record| &record.index)
+    }
+
+    pub fn state_index(&self) -> &SlotIndex {
+        &self.state_index.index
+    }
+
+    /// The state at the end of the era.
+    pub fn state<S: SszDecode>(&self) -> Result<S, EraError> {
+        let position = self
+            .state_index
+            .record_position(0)
+            .expect("the state index was validated to point to a state")?;
+        Ok(decode_frames(read_entry(self.bytes, position)?.data)?)
+    }
+
+    /// The block at `slot`, or `None` if the slot is empty or outside of the era.
+    pub fn block<B: SszDecode>(&self, slot: u64) -> Result<Option<B>, EraError> {
+        let Some(block_index) = &self.block_index else {
+            return Ok(None);
+        };
+        let Some(i) = slot
+            .checked_sub(block_index.index.starting_slot)
+            .and_then(|i| usize::try_from(i).ok())
+        else {
+            return Ok(None);
+        };
+        match block_index.record_position(i) {
+            Some(position) => self.decode_block(position?).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Iterates over the blocks of the era along with their slots, decoding each as it is
+    /// reached.
+    pub fn blocks<B: SszDecode>(&self) -> impl Iterator<Item = Result<(u64, B), EraError>> + '_ {
+        self.block_index.iter().flat_map(move |block_index| {
+            (0..block_index.index.offsets.len()).filter_map(move |i| {
+                let slot = block_index.index.starting_slot + i as u64;
+                let position = block_index.record_position(i)?;
+                Some(position.and_then(|position| Ok((slot, self.decode_block(position)?))))
+            })
+        })
+    }
+
+    fn decode_block<B: SszDecode>(&self, position: usize) -> Result<B, EraError> {
+        Ok(decode_frames(read_entry(self.bytes, position)?.data)?)
+    }
+}
+
+/// Writes an era file. Blocks are written in slot order with [`EraWriter::write_block`], then
+/// [`EraWriter::finish`] writes the state and the slot indexes.
+pub struct EraWriter<W> {
+    writer: W,
+    position: usize,
+    era: u64,
+    // the slot of the state, era * SLOTS_PER_HISTORICAL_ROOT
+    era_start: u64,
+    // the position of the record of each slot of the era written so far
+    block_positions: Vec<Option<usize>>,
+}
+
+impl<W: Write> EraWriter<W> {
+    /// Starts the file for era `era`, which holds the blocks of the slots before the state at
+    /// slot `era * SLOTS_PER_HISTORICAL_ROOT`.
+    pub fn new(mut writer: W, era: u64) -> Result<Self, EraError> {
+        let era_start = era
+            .checked_mul(SLOTS_PER_HISTORICAL_ROOT)
+            .ok_or(EraError::InvalidEra(era))?;
+        let position = write_entry(&mut writer, VERSION, &[])?;
+        Ok(Self {
+            writer,
+            position,
+            era,
+            era_start,
+            block_positions: vec![],
+        })
+    }
+
+    /// Writes the block at `slot`, which must be in the era and after the previous block.
+    pub fn write_block<B: SszEncode>(&mut self, slot: u64, block: &B) -> Result<(), EraError> {
+        let i = slot
+            .checked_add(SLOTS_PER_HISTORICAL_ROOT)
+            .and_then(|slot| slot.checked_sub(self.era_start))
+            .filter(|i| self.era > 0 && *i < SLOTS_PER_HISTORICAL_ROOT)
+            .map(|i| i as usize)
+            .filter(|i| *i >= self.block_positions.len())
+            .ok_or(EraError::InvalidSlot(slot))?;
+
+        self.block_positions.resize(i, None);
+        self.block_positions.push(Some(self.position));
+        self.position += write_entry(
+            &mut self.writer,
+            COMPRESSED_SIGNED_BEACON_BLOCK,
+            &encode_frames(block),
+        )?;
+        Ok(())
+    }
+
+    /// Writes the state at the end of the era and the slot indexes, and returns the writer.
+    pub fn finish<S: SszEncode>(mut self, state: &S) -> Result<W, EraError> {
+        let state_position = self.position;
+        self.position += write_entry(
+            &mut self.writer,
+            COMPRESSED_BEACON_STATE,
+            &encode_frames(state),
+        )?;
+
+        if self.era > 0 {
+            let index_position = self.position as i64;
+            self.block_positions
+                .resize(SLOTS_PER_HISTORICAL_ROOT as usize, None);
+            let block_index = SlotIndex {
+                starting_slot: self.era_start - SLOTS_PER_HISTORICAL_ROOT,
+                offsets: self
+                    .block_positions
+                    .iter()
+                    .map(|position| position.map_or(0, |position| position as i64 - index_position))
+                    .collect(),
+            };
+            self.position += write_entry(&mut self.writer, SLOT_INDEX, &block_index.to_bytes())?;
+        }
+
+        let state_index = SlotIndex {
+            starting_slot: self.era_start,
+            offsets: vec![state_position as i64 - self.position as i64],
+        };
+        write_entry(&mut self.writer, SLOT_INDEX, &state_index.to_bytes())?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
@@ -1,6 +1,8 @@
 use crate::{
-    read_offset_from_slice, sanitize_offset, ssz_decode_variable_length_items, DecodeError,
-    SszDecode, SszEncode, TryFromIter, BYTES_PER_LENGTH_OFFSET,
+    decode::decode_impls::ssz_decode_variable_length_vector, enter_nested, read_contiguous,
+    read_offset_from_buf, reserve_items, sanitize_offset, ssz_decode_variable_length_items,
+    ssz_element_max_len, ssz_element_min_len, DecodeError, SszDecode, SszEncode, SszType,
+    TryFromIter, BYTES_PER_LENGTH_OFFSET,
 };
 use bytes::buf::{Buf, BufMut};
 use ghilhouse::{Error as GhilhouseError, List, Value, Vector};
@@ -31,7 +33,7 @@ where
     }
 }
 
-impl<T: SszEncode + Value, N: Unsigned> SszEncode for List<T, N> {
+impl<T: SszType + Value, N: Unsigned> SszType for List<T, N> {
     fn is_ssz_static() -> bool {
         false
     }
@@ -41,12 +43,14 @@ impl<T: SszEncode + Value, N: Unsigned> SszEncode for List<T, N> {
     }
 
     fn ssz_max_len() -> usize {
-        T::ssz_max_len() * N::to_usize()
+        ssz_element_max_len::<T>().saturating_mul(N::to_usize())
     }
+}
 
+impl<T: SszEncode + Value, N: Unsigned> SszEncode for List<T, N> {
     fn ssz_bytes_len(&self) -> usize {
-        if <T as SszEncode>::is_ssz_static() {
-            <T as SszEncode>::ssz_fixed_len() * self.len()
+        if <T as SszType>::is_ssz_static() {
+            <T as SszType>::ssz_fixed_len() * self.len()
         } else {
             let mut len = self.iter().map(|item| SszEncode::ssz_bytes_len(item)).sum();
             len += BYTES_PER_LENGTH_OFFSET * self.len();
@@ -81,28 +85,11 @@ impl<T: SszEncode + Value, N: Unsigned> SszEncode for List<T, N> {
 }
 
 impl<T: SszDecode + Value, N: Unsigned> SszDecode for List<T, N> {
-    fn is_ssz_static() -> bool {
-        false
-    }
-
-    fn ssz_fixed_len() -> usize {
-        BYTES_PER_LENGTH_OFFSET
-    }
-
-    fn ssz_max_len() -> usize {
-        if T::is_ssz_static() {
-            <T as SszDecode>::ssz_fixed_len() * N::to_usize()
-        } else {
-            let mut len = T::ssz_max_len() * N::to_usize();
-            len += BYTES_PER_LENGTH_OFFSET * N::to_usize();
-            len
-        }
-    }
-
     fn ssz_read(
         _fixed_bytes: &mut impl Buf,
         variable_bytes: &mut impl Buf,
     ) -> Result<Self, DecodeError> {
+        let _nesting = enter_nested()?;
         let max_len = N::to_usize();
 
         // Lists are always stored in the dynamic section at the end
@@ -112,38 +99,39 @@ impl<T: SszDecode + Value, N: Unsigned> SszDecode for List<T, N> {
         } else if T::is_ssz_static() {
             let num_items = variable_bytes
                 .remaining()
-                .checked_div(<T as SszDecode>::ssz_fixed_len())
+                .checked_div(<T as SszType>::ssz_fixed_len())
                 .ok_or(DecodeError::ZeroLengthItem)?;
 
+            // a partial item at the end would be dropped by chunks_exact below
+            if num_items * <T as SszType>::ssz_fixed_len() != variable_bytes.remaining() {
+                return Err(DecodeError::TrailingBytes {
+                    len: variable_bytes.remaining(),
+                    expected: num_items * <T as SszType>::ssz_fixed_len(),
+                });
+            }
+
             if num_items > max_len {
                 return Err(DecodeError::BytesInvalid(format!(
                     "List of {} items exceeds maximum of {}",
                     num_items, max_len
                 )));
             }
-
-            // let bytes = variable_bytes.copy_to_bytes(num_items * <T as SszDecode>::ssz_fixed_len());
-
-            process_results(
-                variable_bytes
-                    .chunk()
-                    .chunks_exact(<T as SszDecode>::ssz_fixed_len())
-                    .map(|chunk| <T as SszDecode>::from_ssz_bytes(chunk)),
-                |iter| List::try_from_iter(iter),
-            )?
+            reserve_items::<T>(num_items)?;
+
+            let len = variable_bytes.remaining();
+            read_contiguous(variable_bytes, len, |bytes| {
+                process_results(
+                    bytes
+                        .chunks_exact(<T as SszType>::ssz_fixed_len())
+                        .map(|chunk| <T as SszDecode>::from_ssz_bytes(chunk)),
+                    |iter| List::try_from_iter(iter),
+                )
+            })?
             .map_err(|e| DecodeError::BytesInvalid(format!("Error processing results: {:?}", e)))
         } else {
-            // we move over variable_bytes to var_offsets (of type Bytes) since it has more methods for us to work with
-            // let mut var_offsets = variable_bytes.copy_to_bytes(variable_bytes.remaining());
-            let var_offsets = variable_bytes.chunk();
-
-            let first_offset = read_offset_from_slice(&var_offsets[0..BYTES_PER_LENGTH_OFFSET])?;
-            sanitize_offset(
-                first_offset,
-                None,
-                var_offsets[BYTES_PER_LENGTH_OFFSET..].len(),
-                Some(first_offset),
-            )?;
+            let num_bytes = variable_bytes.remaining();
+            let first_offset = read_offset_from_buf(variable_bytes)?;
+            sanitize_offset(first_offset, None, num_bytes, Some(first_offset))?;
             if first_offset % BYTES_PER_LENGTH_OFFSET != 0 || first_offset < BYTES_PER_LENGTH_OFFSET
             {
                 return Err(DecodeError::InvalidListFixedBytesLen(first_offset));
@@ -159,38 +147,39 @@ impl<T: SszDecode + Value, N: Unsigned> SszDecode for List<T, N> {
                     num_items, max_len
                 )));
             }
+            reserve_items::<T>(num_items)?;
 
-            // var_offsets now only contains the offsets, and var_items contains the list items (bytes)
-            // let mut var_items = var_offsets.split_off(num_items * BYTES_PER_LENGTH_OFFSET);
-            let mut var_items = &var_offsets[(num_items * BYTES_PER_LENGTH_OFFSET)..];
-            ssz_decode_variable_length_items(
-                &var_offsets[..(num_items * BYTES_PER_LENGTH_OFFSET)],
-                &mut var_items,
-            )
+            ssz_decode_variable_length_items(first_offset, variable_bytes)
         }
     }
 }
 
-impl<T: SszEncode + Value, N: Unsigned> SszEncode for Vector<T, N> {
+impl<T: SszType + Value, N: Unsigned> SszType for Vector<T, N> {
     fn is_ssz_static() -> bool {
         T::is_ssz_static()
     }
 
     fn ssz_fixed_len() -> usize {
-        if <T as SszEncode>::is_ssz_static() {
-            <T as SszEncode>::ssz_fixed_len() * N::to_usize()
+        if T::is_ssz_static() {
+            T::ssz_fixed_len() * N::to_usize()
         } else {
             BYTES_PER_LENGTH_OFFSET
         }
     }
 
+    fn ssz_min_len() -> usize {
+        ssz_element_min_len::<T>() * N::to_usize()
+    }
+
     fn ssz_max_len() -> usize {
-        T::ssz_max_len() * N::to_usize()
+        ssz_element_max_len::<T>().saturating_mul(N::to_usize())
     }
+}
 
+impl<T: SszEncode + Value, N: Unsigned> SszEncode for Vector<T, N> {
     fn ssz_bytes_len(&self) -> usize {
-        if <T as SszEncode>::is_ssz_static() {
-            <T as SszEncode>::ssz_fixed_len() * N::to_usize()
+        if <T as SszType>::is_ssz_static() {
+            <T as SszType>::ssz_fixed_len() * N::to_usize()
         } else {
             let mut len = self.iter().map(|item| SszEncode::ssz_bytes_len(item)).sum();
             len += BYTES_PER_LENGTH_OFFSET * N::to_usize();
@@ -231,77 +220,46 @@ impl<T: SszEncode + Value, N: Unsigned> SszEncode for Vector<T, N> {
 }
 
 impl<T: SszDecode + Value, N: Unsigned> SszDecode for Vector<T, N> {
-    fn is_ssz_static() -> bool {
-        T::is_ssz_static()
-    }
-
-    fn ssz_fixed_len() -> usize {
-        if T::is_ssz_static() {
-            <T as SszDecode>::ssz_fixed_len() * N::to_usize()
-        } else {
-            BYTES_PER_LENGTH_OFFSET
-        }
-    }
-
-    fn ssz_max_len() -> usize {
-        if T::is_ssz_static() {
-            <T as SszDecode>::ssz_fixed_len() * N::to_usize()
-        } else {
-            let mut len = T::ssz_max_len() * N::to_usize();
-            len += BYTES_PER_LENGTH_OFFSET * N::to_usize();
-            len
-        }
-    }
-
     fn ssz_read(
         fixed_bytes: &mut impl Buf,
         variable_bytes: &mut impl Buf,
     ) -> Result<Self, DecodeError> {
+        let _nesting = enter_nested()?;
         let len = N::to_usize();
+        reserve_items::<T>(len)?;
 
         // Vectors are either static, in which case the data is in the fixed bytes section
         // or it's dynamic and the data is in variable bytes.
-        // The vector is empty if both sections are empty.
-        if !(fixed_bytes.has_remaining() || variable_bytes.has_remaining()) {
-            Ok(Self::try_from(List::empty()).map_err(|e| {
-                DecodeError::BytesInvalid(format!("Error decoding empty vector: {:?}", e))
-            })?)
-        } else if T::is_ssz_static() {
+        // Either way they hold N items, so empty input is too short rather than an empty vector.
+        if T::is_ssz_static() {
             // T is static, so data resides in fixed_bytes
-            if fixed_bytes.remaining() < len * <T as SszDecode>::ssz_fixed_len() {
+            if fixed_bytes.remaining() < len * <T as SszType>::ssz_fixed_len() {
                 return Err(DecodeError::BytesInvalid(format!(
                     "Vector of {} items not equal to length {}",
                     fixed_bytes
                         .remaining()
-                        .checked_div(<T as SszDecode>::ssz_fixed_len())
+                        .checked_div(<T as SszType>::ssz_fixed_len())
                         .unwrap(),
                     len
                 )));
             }
 
-            // create slice of length `len * T::ssz_fixed_len`
-            // let bytes = fixed_bytes.copy_to_bytes(len * <T as SszDecode>::ssz_fixed_len());
-            let bytes = &fixed_bytes.chunk()[..(len * <T as SszDecode>::ssz_fixed_len())];
-
-            let res = process_results(
-                bytes
-                    .chunks_exact(<T as SszDecode>::ssz_fixed_len())
-                    .map(|chunk| <T as SszDecode>::from_ssz_bytes(chunk)),
-                |iter| Vector::try_from_iter(iter),
+            read_contiguous(
+                fixed_bytes,
+                len * <T as SszType>::ssz_fixed_len(),
+                |bytes| {
+                    process_results(
+                        bytes
+                            .chunks_exact(<T as SszType>::ssz_fixed_len())
+                            .map(|chunk| <T as SszDecode>::from_ssz_bytes(chunk)),
+                        |iter| Vector::try_from_iter(iter),
+                    )
+                },
             )?
-            .map_err(|e| DecodeError::BytesInvalid(format!("Error processing results: {:?}", e)));
-
-            fixed_bytes.advance(len * <T as SszDecode>::ssz_fixed_len());
-            res
+            .map_err(|e| DecodeError::BytesInvalid(format!("Error processing results: {:?}", e)))
         } else {
             // T is not static so data resides in variable_bytes
-            // let mut var_offsets = variable_bytes.copy_to_bytes(variable_bytes.remaining());
-            // let mut var_items = var_offsets.split_off(len * BYTES_PER_LENGTH_OFFSET);
-            // ssz_decode_variable_length_items(var_offsets, &mut var_items)
-
-            let var_offsets = &variable_bytes.chunk()[..(len * BYTES_PER_LENGTH_OFFSET)];
-            let mut var_items = &variable_bytes.chunk()[(len * BYTES_PER_LENGTH_OFFSET)..];
-            ssz_decode_variable_length_items(var_offsets, &mut var_items)
+            ssz_decode_variable_length_vector(len, variable_bytes)
         }
     }
 }
@@ -1,8 +1,204 @@
+use alloc::vec::Vec;
 use ethereum_types::H256;
-use typenum::{Logarithm2, NonZero, Unsigned};
+use sha2::{Digest, Sha256};
+use typenum::{Logarithm2, NonZero, Unsigned, U1, U16, U2, U32, U4, U8};
 
 pub trait SszHash {
+    // the number of values of the type packed into a 32 byte chunk, 1 for types that are hashed
+    // to a chunk of their own (composite types, and basic types of 32 bytes)
     type PackingFactor: Unsigned + NonZero + Logarithm2;
 
     fn hash_tree_root(&self) -> H256;
+
+    // the bytes self takes in the chunks of a vector, shared with PackingFactor - 1 other items,
+    // which are the root of types that fill a chunk
+    #[doc(hidden)]
+    fn ssz_hash_packed(&self) -> Vec<u8> {
+        self.hash_tree_root().0.to_vec()
+    }
+}
+
+// basic values are hashed as their little-endian bytes, padded to a chunk
+macro_rules! uint_ssz_hash {
+    ($type: ident, $packing_factor: ident) => {
+        impl SszHash for $type {
+            type PackingFactor = $packing_factor;
+
+            fn hash_tree_root(&self) -> H256 {
+                let mut root = H256::zero();
+                root.0[..core::mem::size_of::<$type>()].copy_from_slice(&self.to_le_bytes());
+                root
+            }
+
+            fn ssz_hash_packed(&self) -> Vec<u8> {
+                self.to_le_bytes().to_vec()
+            }
+        }
+    };
 }
+
+uint_ssz_hash!(u8, U32);
+uint_ssz_hash!(u16, U16);
+uint_ssz_hash!(u32, U8);
+uint_ssz_hash!(u64, U4);
+uint_ssz_hash!(u128, U2);
+
+impl SszHash for bool {
+    type PackingFactor = U32;
+
+    fn hash_tree_root(&self) -> H256 {
+        (*self as u8).hash_tree_root()
+    }
+
+    fn ssz_hash_packed(&self) -> Vec<u8> {
+        (*self as u8).ssz_hash_packed()
+    }
+}
+
+impl SszHash for H256 {
+    type PackingFactor = U1;
+
+    fn hash_tree_root(&self) -> H256 {
+        *self
+    }
+}
+
+#[cfg(feature = "alloy")]
+impl SszHash for alloy_primitives::B256 {
+    type PackingFactor = U1;
+
+    fn hash_tree_root(&self) -> H256 {
+        H256(self.0)
+    }
+}
+
+#[cfg(feature = "alloy")]
+impl SszHash for alloy_primitives::U256 {
+    type PackingFactor = U1;
+
+    fn hash_tree_root(&self) -> H256 {
+        H256(self.to_le_bytes::<32>())
+    }
+}
+
+const BYTES_PER_CHUNK: usize = 32;
+
+fn hash_pair(left: &H256, right: &H256) -> H256 {
+    H256(
+        Sha256::new()
+            .chain_update(left.0)
+            .chain_update(right.0)
+            .finalize()
+            .into(),
+    )
+}
+
+// the merkle root of `bytes` split into chunks, over at least `minimum_chunk_count` leaves, like
+// tree_hash::merkle_root but without std. Missing subtrees are hashed from the zero hash of their
+// depth, so a large minimum_chunk_count doesn't take as many leaves
+fn merkle_root(bytes: &[u8], minimum_chunk_count: usize) -> H256 {
+    let chunk_count = bytes
+        .len()
+        .div_ceil(BYTES_PER_CHUNK)
+        .max(minimum_chunk_count);
+    let depth = chunk_count.next_power_of_two().trailing_zeros();
+
+    let mut nodes = bytes
+        .chunks(BYTES_PER_CHUNK)
+        .map(|chunk| {
+            let mut node = H256::zero();
+            node.0[..chunk.len()].copy_from_slice(chunk);
+            node
+        })
+        .collect::<Vec<_>>();
+    let mut zero_hash = H256::zero();
+    for _ in 0..depth {
+        if nodes.len() % 2 == 1 {
+            nodes.push(zero_hash);
+        }
+        nodes = nodes
+            .chunks_exact(2)
+            .map(|pair| hash_pair(&pair[0], &pair[1]))
+            .collect();
+        zero_hash = hash_pair(&zero_hash, &zero_hash);
+    }
+    nodes.first().copied().unwrap_or(zero_hash)
+}
+
+// the root of a vector of `items`, packed into chunks if they are basic values, the way ssz_types
+// hashes a FixedVector
+fn vector_root<T: SszHash>(items: &[T]) -> H256 {
+    let bytes = items
+        .iter()
+        .flat_map(SszHash::ssz_hash_packed)
+        .collect::<Vec<_>>();
+    let minimum_chunk_count = items.len().div_ceil(T::PackingFactor::to_usize());
+    merkle_root(&bytes, minimum_chunk_count)
+}
+
+// the root of a container with fields of these roots
+fn container_root(roots: impl ExactSizeIterator<Item = H256>) -> H256 {
+    let count = roots.len();
+    let bytes = roots.flat_map(|root| root.0).collect::<Vec<_>>();
+    merkle_root(&bytes, count)
+}
+
+// the root of a union, which mixes the selector of its variant into the root of the value
+fn mix_in_selector(root: &H256, selector: u8) -> H256 {
+    let mut selector_chunk = H256::zero();
+    selector_chunk.0[0] = selector;
+    hash_pair(root, &selector_chunk)
+}
+
+/// Arrays are hashed as SSZ vectors.
+impl<T: SszHash, const N: usize> SszHash for [T; N] {
+    type PackingFactor = U1;
+
+    fn hash_tree_root(&self) -> H256 {
+        vector_root(self)
+    }
+}
+
+/// `Option<T>` is hashed as the SSZ `Union[None, T]`, mixing the selector into the root of the
+/// value, or of zero for `None`.
+impl<T: SszHash> SszHash for Option<T> {
+    type PackingFactor = U1;
+
+    fn hash_tree_root(&self) -> H256 {
+        match self {
+            None => mix_in_selector(&H256::zero(), 0),
+            Some(value) => mix_in_selector(&value.hash_tree_root(), 1),
+        }
+    }
+}
+
+// tuples are hashed as anonymous containers, with one field per element
+macro_rules! tuple_ssz_hash {
+    ($(($idx: tt, $T: ident)),+) => {
+        impl<$($T: SszHash),+> SszHash for ($($T,)+) {
+            type PackingFactor = U1;
+
+            fn hash_tree_root(&self) -> H256 {
+                container_root([$(self.$idx.hash_tree_root()),+].into_iter())
+            }
+        }
+    };
+}
+
+tuple_ssz_hash!((0, A));
+tuple_ssz_hash!((0, A), (1, B));
+tuple_ssz_hash!((0, A), (1, B), (2, C));
+tuple_ssz_hash!((0, A), (1, B), (2, C), (3, D));
+tuple_ssz_hash!((0, A), (1, B), (2, C), (3, D), (4, E));
+tuple_ssz_hash!((0, A), (1, B), (2, C), (3, D), (4, E), (5, F));
+tuple_ssz_hash!((0, A), (1, B), (2, C), (3, D), (4, E), (5, F), (6, G));
+tuple_ssz_hash!(
+    (0, A),
+    (1, B),
+    (2, C),
+    (3, D),
+    (4, E),
+    (5, F),
+    (6, G),
+    (7, H)
+);
@@ -0,0 +1,69 @@
+use crate::{read_offset_from_slice, sanitize_offset, DecodeError};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+/// A container field, as laid out in the fixed portion of the container's encoding.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SszField {
+    pub name: &'static str,
+    pub is_static: bool,
+    /// The length of the field in the fixed portion, `BYTES_PER_LENGTH_OFFSET` if it is variable-size.
+    pub fixed_len: usize,
+}
+
+/// Field-level layout of a container, implemented by `#[derive(SszbEncode)]` and `#[derive(SszbType)]`.
+pub trait SszLayout {
+    fn ssz_fields() -> Vec<SszField>;
+}
+
+/// The byte ranges a field occupies in an encoded container.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct FieldSpan {
+    pub field: SszField,
+    /// The field's value if it is fixed-size, or its offset if it is variable-size.
+    pub fixed: Range<usize>,
+    /// The field's value if it is variable-size.
+    pub variable: Option<Range<usize>>,
+}
+
+/// Splits an encoded container into the byte ranges of its fields, checking the offsets on the way.
+pub fn ssz_layout<T: SszLayout>(bytes: &[u8]) -> Result<Vec<FieldSpan>, DecodeError> {
+    let fields = T::ssz_fields();
+    let fixed_len = fields.iter().map(|field| field.fixed_len).sum::<usize>();
+    if fixed_len > bytes.len() {
+        return Err(DecodeError::InvalidByteLength {
+            len: bytes.len(),
+            expected: fixed_len,
+        });
+    }
+
+    let mut spans = Vec::with_capacity(fields.len());
+    let mut offsets = vec![];
+    let mut position = 0;
+    for field in fields {
+        let fixed = position..position + field.fixed_len;
+        if !field.is_static {
+            let offset = read_offset_from_slice(&bytes[fixed.clone()])?;
+            let previous_offset = offsets.last().map(|(_, offset)| *offset);
+            offsets.push((
+                spans.len(),
+                sanitize_offset(offset, previous_offset, bytes.len(), Some(fixed_len))?,
+            ));
+        }
+        position = fixed.end;
+        spans.push(FieldSpan {
+            field,
+            fixed,
+            variable: None,
+        });
+    }
+
+    // each variable-size field runs up to the next offset, the last one to the end of the bytes
+    for (i, (index, offset)) in offsets.iter().enumerate() {
+        let end = offsets.get(i + 1).map_or(bytes.len(), |(_, next)| *next);
+        spans[*index].variable = Some(*offset..end);
+    }
+
+    Ok(spans)
+}
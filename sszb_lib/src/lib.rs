@@ -1,18 +1,97 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "arbitrary")]
+pub mod arbitrary;
+mod bitfield;
+mod byte_list;
 mod decode;
 mod encode;
+#[cfg(feature = "snappy")]
+pub mod era;
+#[cfg(feature = "ghilhouse")]
 mod ghilhouse_impls;
+#[cfg(feature = "ethereum-types")]
 mod hash;
+mod layout;
+#[cfg(all(feature = "std", feature = "alloy"))]
+pub mod schema;
+#[cfg(feature = "bls")]
 mod sig;
+#[cfg(feature = "snappy")]
+pub mod snappy;
+#[cfg(all(feature = "serde", feature = "alloy"))]
+pub mod spec_serde;
+mod ssz_type;
 
 pub const BYTES_PER_LENGTH_OFFSET: usize = 4;
 pub const N: usize = 1_000;
 
+#[cfg(feature = "arbitrary")]
+pub use self::arbitrary::SszArbitrary;
+pub use bitfield::*;
+pub use byte_list::*;
+#[cfg(feature = "std")]
+pub use decode::config::DecodeConfig;
 pub use decode::{
-    decode_impls::*, read_offset_from_buf, read_offset_from_slice, sanitize_offset, DecodeError,
-    SszDecode,
+    check_trailing_bytes,
+    config::{enter_nested, reserve_bits, reserve_items, NestingGuard},
+    decode_impls::*,
+    read_contiguous, read_fixed_field, read_offset_from_buf, read_offset_from_slice,
+    read_variable_field, sanitize_offset, variable_field_len, DecodeError, SszDecode,
 };
 pub use encode::*;
+#[cfg(feature = "ethereum-types")]
 pub use hash::SszHash;
+pub use layout::{ssz_layout, FieldSpan, SszField, SszLayout};
+#[cfg(all(feature = "std", feature = "alloy"))]
+pub use schema::{SchemaMismatch, SszReflect, SszSchema, SszValue};
 
+#[cfg(feature = "ghilhouse")]
 pub use ghilhouse_impls::*;
+#[cfg(feature = "bls")]
 pub use sig::*;
+#[cfg(all(feature = "serde", feature = "alloy"))]
+pub use spec_serde::SszSerde;
+pub use ssz_type::{ssz_element_max_len, ssz_element_min_len, SszType};
+
+// used by the code generated by the derives, which may be in a no_std crate
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::{format, string::ToString, vec, vec::Vec};
+}
+
+// SszbArbitrary and SszbSerde expand to a call of these, so that deriving them without the
+// feature of sszb they need fails with an error naming it
+#[cfg(feature = "arbitrary")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __require_arbitrary {
+    () => {};
+}
+
+#[cfg(not(feature = "arbitrary"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __require_arbitrary {
+    () => {
+        compile_error!("#[derive(SszbArbitrary)] needs the `arbitrary` feature of sszb");
+    };
+}
+
+#[cfg(all(feature = "serde", feature = "alloy"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __require_serde {
+    () => {};
+}
+
+#[cfg(not(all(feature = "serde", feature = "alloy")))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __require_serde {
+    () => {
+        compile_error!("#[derive(SszbSerde)] needs the `serde` and `alloy` features of sszb");
+    };
+}
@@ -0,0 +1,851 @@
+//! Runtime reflection of SSZ types, for tools working with values whose type is only known at
+//! runtime, such as explorers, debuggers and the CLI.
+//!
+//! [`SszSchema`] describes an SSZ type: its container fields, list and vector bounds, bitfields
+//! and unions. [`SszReflect`] gives the schema of a Rust type, and `#[derive(SszbSchema)]`
+//! implements it for a container. [`SszValue`] is a value of any schema, which can be decoded,
+//! encoded and hashed with the schema alone, giving the same bytes and root as the typed path.
+//!
+//! ```ignore
+//! let schema = BeaconBlock::ssz_schema();
+//! let value = SszValue::from_ssz_bytes(&schema, &bytes)?;
+//! assert_eq!(value.to_ssz(&schema)?, bytes);
+//! assert_eq!(value.tree_hash_root(&schema)?, block.tree_hash_root());
+//! ```
+use crate::bitfield::{bitlist_len_from_bytes, validate_bitvector_bytes};
+use crate::{
+    enter_nested, read_offset_from_slice, reserve_items, sanitize_offset, Bitlist, Bitvector,
+    ByteList, DecodeError, BYTES_PER_LENGTH_OFFSET,
+};
+#[cfg(feature = "bls")]
+use crate::{PKBytes, Sig};
+use alloy_primitives::{Address, Bloom, FixedBytes, U128, U256};
+#[cfg(feature = "ethereum-types")]
+use ethereum_types::{H160, H256, H32};
+#[cfg(feature = "milhouse")]
+use milhouse::{List as PersistentList, Value, Vector as PersistentVector};
+use ssz_types::{BitList, BitVector, FixedVector, VariableList};
+use std::borrow::Cow;
+use std::fmt;
+use std::rc::Rc;
+use std::sync::Arc;
+use tree_hash::{Hash256, BYTES_PER_CHUNK};
+use typenum::Unsigned;
+
+/// The description of an SSZ type.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum SszSchema {
+    /// An unsigned integer of this many bytes: 1, 2, 4, 8, 16 or 32.
+    Uint(usize),
+    Bool,
+    /// `Vector[item, len]`, byte vectors such as roots and addresses included.
+    Vector {
+        item: Box<SszSchema>,
+        len: usize,
+    },
+    /// `List[item, max]`.
+    List {
+        item: Box<SszSchema>,
+        max: usize,
+    },
+    /// `Bitvector[len]`.
+    Bitvector(usize),
+    /// `Bitlist[max]`.
+    Bitlist(usize),
+    /// A container and its fields, in order. Tuples are containers without a name.
+    Container {
+        name: String,
+        fields: Vec<(String, SszSchema)>,
+    },
+    /// `Union[...]`, by selector. `None` is the variant without a value.
+    Union(Vec<Option<SszSchema>>),
+}
+
+impl SszSchema {
+    /// `Vector[item, len]`.
+    pub fn vector(item: SszSchema, len: usize) -> Self {
+        SszSchema::Vector {
+            item: Box::new(item),
+            len,
+        }
+    }
+
+    /// `List[item, max]`.
+    pub fn list(item: SszSchema, max: usize) -> Self {
+        SszSchema::List {
+            item: Box::new(item),
+            max,
+        }
+    }
+
+    /// Whether values of the schema have a fixed size, like `SszType::is_ssz_static`.
+    pub fn is_static(&self) -> bool {
+        match self {
+            SszSchema::Uint(_) | SszSchema::Bool | SszSchema::Bitvector(_) => true,
+            SszSchema::Vector { item, .. } => item.is_static(),
+            SszSchema::Container { fields, .. } => {
+                fields.iter().all(|(_, field)| field.is_static())
+            }
+            SszSchema::List { .. } | SszSchema::Bitlist(_) | SszSchema::Union(_) => false,
+        }
+    }
+
+    /// The length values of the schema take up in the fixed portion of a container, like
+    /// `SszType::ssz_fixed_len`.
+    pub fn fixed_len(&self) -> usize {
+        if !self.is_static() {
+            return BYTES_PER_LENGTH_OFFSET;
+        }
+        match self {
+            SszSchema::Uint(size) => *size,
+            SszSchema::Bool => 1,
+            SszSchema::Bitvector(len) => std::cmp::max(1, len.div_ceil(8)),
+            SszSchema::Vector { item, len } => item.fixed_len().saturating_mul(*len),
+            SszSchema::Container { fields, .. } => fields
+                .iter()
+                .fold(0, |len, (_, field)| len.saturating_add(field.fixed_len())),
+            _ => unreachable!("variable-size schemas return early"),
+        }
+    }
+
+    // uints and booleans, which are packed together in vectors and lists
+    fn is_basic(&self) -> bool {
+        matches!(self, SszSchema::Uint(_) | SszSchema::Bool)
+    }
+
+    fn is_byte(&self) -> bool {
+        *self == SszSchema::Uint(1)
+    }
+}
+
+// the consensus spec notation, containers by name
+impl fmt::Display for SszSchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SszSchema::Uint(size) => write!(f, "uint{}", size * 8),
+            SszSchema::Bool => write!(f, "boolean"),
+            SszSchema::Vector { item, len } => write!(f, "Vector[{}, {}]", item, len),
+            SszSchema::List { item, max } => write!(f, "List[{}, {}]", item, max),
+            SszSchema::Bitvector(len) => write!(f, "Bitvector[{}]", len),
+            SszSchema::Bitlist(max) => write!(f, "Bitlist[{}]", max),
+            SszSchema::Container { name, .. } if !name.is_empty() => write!(f, "{}", name),
+            SszSchema::Container { fields, .. } => {
+                write!(f, "Container[")?;
+                for (i, (_, field)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", field)?;
+                }
+                write!(f, "]")
+            }
+            SszSchema::Union(variants) => {
+                write!(f, "Union[")?;
+                for (i, variant) in variants.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    match variant {
+                        Some(variant) => write!(f, "{}", variant)?,
+                        None => write!(f, "None")?,
+                    }
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+/// A type whose SSZ schema is known, implemented by `#[derive(SszbSchema)]`.
+pub trait SszReflect {
+    fn ssz_schema() -> SszSchema;
+}
+
+/// A value of any SSZ schema. Values don't carry their schema, which is passed along to decode,
+/// encode and hash them.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum SszValue {
+    Uint(U256),
+    Bool(bool),
+    /// A vector or list of `uint8`.
+    Bytes(Vec<u8>),
+    /// A vector or list of any other type.
+    Items(Vec<SszValue>),
+    /// A bitvector or bitlist.
+    Bits(Vec<bool>),
+    /// The values of the fields of a container, in order.
+    Container(Vec<SszValue>),
+    /// The selector of a union and its value, `None` for the variant without one.
+    Union(u8, Option<Box<SszValue>>),
+}
+
+/// A value that does not fit the schema it is encoded or hashed with.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SchemaMismatch {
+    /// The path to the value, e.g. `body.attestations[3].data.slot`, empty for the outermost one.
+    pub path: String,
+    /// The schema the value does not fit, with the reason it does not.
+    pub reason: String,
+}
+
+impl fmt::Display for SchemaMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.reason)
+        } else {
+            write!(f, "{}: {}", self.path, self.reason)
+        }
+    }
+}
+
+fn mismatch<T>(path: &str, schema: &SszSchema, reason: &str) -> Result<T, SchemaMismatch> {
+    Err(SchemaMismatch {
+        path: path.to_string(),
+        reason: format!("expected {}, {}", schema, reason),
+    })
+}
+
+fn field_path(path: &str, field: &str) -> String {
+    if path.is_empty() {
+        field.to_string()
+    } else {
+        format!("{}.{}", path, field)
+    }
+}
+
+// values of a fixed-size schema must take up exactly its length
+fn check_len(bytes: &[u8], expected: usize) -> Result<(), DecodeError> {
+    let len = bytes.len();
+    if len < expected {
+        Err(DecodeError::InvalidByteLength { len, expected })
+    } else if len > expected {
+        Err(DecodeError::TrailingBytes { len, expected })
+    } else {
+        Ok(())
+    }
+}
+
+// the items of a vector or list, bytes for uint8 items
+fn decode_items(item: &SszSchema, bytes: &[u8], len: usize) -> Result<SszValue, DecodeError> {
+    if item.is_byte() {
+        reserve_items::<u8>(len)?;
+        return Ok(SszValue::Bytes(bytes.to_vec()));
+    }
+    reserve_items::<SszValue>(len)?;
+    if item.is_static() {
+        if item.fixed_len() == 0 {
+            return Err(DecodeError::ZeroLengthItem);
+        }
+        return bytes
+            .chunks_exact(item.fixed_len())
+            .map(|bytes| SszValue::from_ssz_bytes(item, bytes))
+            .collect::<Result<_, _>>()
+            .map(SszValue::Items);
+    }
+
+    // the offsets of the items, followed by the items, each running up to the next offset
+    let offsets = (0..len)
+        .map(|i| read_offset_from_slice(&bytes[i * BYTES_PER_LENGTH_OFFSET..]))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut items = Vec::with_capacity(len);
+    for (i, offset) in offsets.iter().enumerate() {
+        let previous = i.checked_sub(1).map(|i| offsets[i]);
+        let fixed_len = (i == 0).then_some(len * BYTES_PER_LENGTH_OFFSET);
+        let start = sanitize_offset(*offset, previous, bytes.len(), fixed_len)?;
+        let end = offsets.get(i + 1).copied().unwrap_or(bytes.len());
+        let end = sanitize_offset(end, Some(start), bytes.len(), None)?;
+        items.push(SszValue::from_ssz_bytes(item, &bytes[start..end])?);
+    }
+    Ok(SszValue::Items(items))
+}
+
+// the number of variable-size items in a list, from its first offset
+fn variable_items_len(bytes: &[u8]) -> Result<usize, DecodeError> {
+    if bytes.is_empty() {
+        return Ok(0);
+    }
+    let first_offset = read_offset_from_slice(bytes)?;
+    if first_offset == 0 || first_offset % BYTES_PER_LENGTH_OFFSET != 0 {
+        return Err(DecodeError::InvalidListFixedBytesLen(first_offset));
+    }
+    if first_offset > bytes.len() {
+        return Err(DecodeError::OffsetOutOfBounds(first_offset));
+    }
+    Ok(first_offset / BYTES_PER_LENGTH_OFFSET)
+}
+
+fn bits_from_bytes(bytes: &[u8], len: usize) -> Vec<bool> {
+    (0..len).map(|i| bytes[i / 8] >> (i % 8) & 1 == 1).collect()
+}
+
+fn bytes_from_bits(bits: &[bool], len: usize) -> Vec<u8> {
+    let mut bytes = vec![0; len];
+    for (i, bit) in bits.iter().enumerate() {
+        bytes[i / 8] |= (*bit as u8) << (i % 8);
+    }
+    bytes
+}
+
+impl SszValue {
+    /// Decodes a value of `schema`, checking the encoding just like the typed path does.
+    pub fn from_ssz_bytes(schema: &SszSchema, bytes: &[u8]) -> Result<Self, DecodeError> {
+        match schema {
+            SszSchema::Uint(size) if *size > 32 => Err(DecodeError::BytesInvalid(format!(
+                "uint{} is wider than 256 bits",
+                size * 8
+            ))),
+            SszSchema::Uint(size) => {
+                check_len(bytes, *size)?;
+                Ok(SszValue::Uint(U256::from_le_slice(bytes)))
+            }
+            SszSchema::Bool => {
+                check_len(bytes, 1)?;
+                match bytes[0] {
+                    0 => Ok(SszValue::Bool(false)),
+                    1 => Ok(SszValue::Bool(true)),
+                    num => Err(DecodeError::BytesInvalid(format!(
+                        "Out-of-range for boolean: {}",
+                        num
+                    ))),
+                }
+            }
+            SszSchema::Vector { item, len } => {
+                let _nesting = enter_nested()?;
+                if item.is_static() {
+                    check_len(bytes, item.fixed_len().saturating_mul(*len))?;
+                    return decode_items(item, bytes, *len);
+                }
+                let items_len = variable_items_len(bytes)?;
+                if items_len != *len {
+                    return Err(DecodeError::BytesInvalid(format!(
+                        "Vector of {} items not equal to length {}",
+                        items_len, len
+                    )));
+                }
+                decode_items(item, bytes, *len)
+            }
+            SszSchema::List { item, max } => {
+                let _nesting = enter_nested()?;
+                let len = if item.is_static() {
+                    let item_len = item.fixed_len();
+                    if item_len == 0 {
+                        return Err(DecodeError::ZeroLengthItem);
+                    }
+                    let len = bytes.len() / item_len;
+                    if len * item_len != bytes.len() {
+                        return Err(DecodeError::TrailingBytes {
+                            len: bytes.len(),
+                            expected: len * item_len,
+                        });
+                    }
+                    len
+                } else {
+                    variable_items_len(bytes)?
+                };
+                if len > *max {
+                    return Err(DecodeError::BytesInvalid(format!(
+                        "List of {} items exceeds maximum of {}",
+                        len, max
+                    )));
+                }
+                decode_items(item, bytes, len)
+            }
+            SszSchema::Bitvector(len) => {
+                validate_bitvector_bytes(bytes, *len)?;
+                Ok(SszValue::Bits(bits_from_bytes(bytes, *len)))
+            }
+            SszSchema::Bitlist(max) => {
+                let len = bitlist_len_from_bytes(bytes, *max)?;
+                reserve_items::<bool>(len)?;
+                Ok(SszValue::Bits(bits_from_bytes(bytes, len)))
+            }
+            SszSchema::Container { fields, .. } => {
+                let _nesting = enter_nested()?;
+                let fixed_len = fields
+                    .iter()
+                    .map(|(_, field)| field.fixed_len())
+                    .sum::<usize>();
+                if fixed_len > bytes.len() {
+                    return Err(DecodeError::InvalidByteLength {
+                        len: bytes.len(),
+                        expected: fixed_len,
+                    });
+                }
+
+                // static fields are decoded in place, variable-size ones once their offsets
+                // are all known, each running up to the next offset
+                let mut values = Vec::with_capacity(fields.len());
+                let mut offsets: Vec<(usize, usize)> = vec![];
+                let mut position = 0;
+                for (i, (_, field)) in fields.iter().enumerate() {
+                    let fixed = &bytes[position..position + field.fixed_len()];
+                    if field.is_static() {
+                        values.push(Some(SszValue::from_ssz_bytes(field, fixed)?));
+                    } else {
+                        let previous = offsets.last().map(|(_, offset)| *offset);
+                        let offset = sanitize_offset(
+                            read_offset_from_slice(fixed)?,
+                            previous,
+                            bytes.len(),
+                            Some(fixed_len),
+                        )?;
+                        offsets.push((i, offset));
+                        values.push(None);
+                    }
+                    position += field.fixed_len();
+                }
+                if offsets.is_empty() {
+                    check_len(bytes, fixed_len)?;
+                }
+                for (j, (i, offset)) in offsets.iter().enumerate() {
+                    let end = offsets.get(j + 1).map_or(bytes.len(), |(_, next)| *next);
+                    values[*i] = Some(SszValue::from_ssz_bytes(
+                        &fields[*i].1,
+                        &bytes[*offset..end],
+                    )?);
+                }
+                Ok(SszValue::Container(values.into_iter().flatten().collect()))
+            }
+            SszSchema::Union(variants) => {
+                let _nesting = enter_nested()?;
+                let (selector, value) =
+                    bytes.split_first().ok_or(DecodeError::InvalidByteLength {
+                        len: 0,
+                        expected: 1,
+                    })?;
+                match variants.get(*selector as usize) {
+                    Some(Some(variant)) => Ok(SszValue::Union(
+                        *selector,
+                        Some(Box::new(SszValue::from_ssz_bytes(variant, value)?)),
+                    )),
+                    // the variant without a value has the selector as its only byte
+                    Some(None) if value.is_empty() => Ok(SszValue::Union(*selector, None)),
+                    Some(None) => Err(DecodeError::InvalidByteLength {
+                        len: bytes.len(),
+                        expected: 1,
+                    }),
+                    None => Err(DecodeError::UnionSelectorInvalid(*selector)),
+                }
+            }
+        }
+    }
+
+    /// Encodes the value as `schema`.
+    pub fn to_ssz(&self, schema: &SszSchema) -> Result<Vec<u8>, SchemaMismatch> {
+        let mut buf = vec![];
+        self.write_ssz(schema, "", &mut buf)?;
+        Ok(buf)
+    }
+
+    fn write_ssz(
+        &self,
+        schema: &SszSchema,
+        path: &str,
+        buf: &mut Vec<u8>,
+    ) -> Result<(), SchemaMismatch> {
+        match (schema, self) {
+            (SszSchema::Uint(size), SszValue::Uint(value)) => {
+                if *size > 32 || value.byte_len() > *size {
+                    return mismatch(path, schema, "the value is too large");
+                }
+                buf.extend_from_slice(&value.to_le_bytes::<32>()[..*size]);
+            }
+            (SszSchema::Bool, SszValue::Bool(value)) => buf.push(*value as u8),
+            (SszSchema::Vector { item, len }, SszValue::Bytes(bytes)) if item.is_byte() => {
+                if bytes.len() != *len {
+                    return mismatch(path, schema, &format!("got {} bytes", bytes.len()));
+                }
+                buf.extend_from_slice(bytes);
+            }
+            (SszSchema::List { item, max }, SszValue::Bytes(bytes)) if item.is_byte() => {
+                if bytes.len() > *max {
+                    return mismatch(path, schema, &format!("got {} bytes", bytes.len()));
+                }
+                buf.extend_from_slice(bytes);
+            }
+            (SszSchema::Vector { item, len }, SszValue::Items(items)) if !item.is_byte() => {
+                if items.len() != *len {
+                    return mismatch(path, schema, &format!("got {} items", items.len()));
+                }
+                write_elements(items.iter().map(|value| (&**item, value)), buf, |i| {
+                    format!("{}[{}]", path, i)
+                })?;
+            }
+            (SszSchema::List { item, max }, SszValue::Items(items)) if !item.is_byte() => {
+                if items.len() > *max {
+                    return mismatch(path, schema, &format!("got {} items", items.len()));
+                }
+                write_elements(items.iter().map(|value| (&**item, value)), buf, |i| {
+                    format!("{}[{}]", path, i)
+                })?;
+            }
+            (SszSchema::Bitvector(len), SszValue::Bits(bits)) => {
+                if bits.len() != *len {
+                    return mismatch(path, schema, &format!("got {} bits", bits.len()));
+                }
+                buf.extend(bytes_from_bits(bits, schema.fixed_len()));
+            }
+            (SszSchema::Bitlist(max), SszValue::Bits(bits)) => {
+                if bits.len() > *max {
+                    return mismatch(path, schema, &format!("got {} bits", bits.len()));
+                }
+                // the bits followed by the length delimiter
+                let mut bytes = bytes_from_bits(bits, bits.len() / 8 + 1);
+                bytes[bits.len() / 8] |= 1 << (bits.len() % 8);
+                buf.extend(bytes);
+            }
+            (SszSchema::Container { fields, .. }, SszValue::Container(values)) => {
+                if values.len() != fields.len() {
+                    return mismatch(path, schema, &format!("got {} fields", values.len()));
+                }
+                write_elements(
+                    fields.iter().map(|(_, field)| field).zip(values),
+                    buf,
+                    |i| field_path(path, &fields[i].0),
+                )?;
+            }
+            (SszSchema::Union(variants), SszValue::Union(selector, value)) => {
+                match (variants.get(*selector as usize), value) {
+                    (Some(Some(variant)), Some(value)) => {
+                        buf.push(*selector);
+                        value.write_ssz(variant, path, buf)?;
+                    }
+                    (Some(None), None) => buf.push(*selector),
+                    _ => return mismatch(path, schema, &format!("got selector {}", selector)),
+                }
+            }
+            _ => return mismatch(path, schema, "got a different kind of value"),
+        }
+        Ok(())
+    }
+
+    /// The hash tree root of the value as `schema`.
+    pub fn tree_hash_root(&self, schema: &SszSchema) -> Result<Hash256, SchemaMismatch> {
+        self.root_at(schema, "")
+    }
+
+    fn root_at(&self, schema: &SszSchema, path: &str) -> Result<Hash256, SchemaMismatch> {
+        match (schema, self) {
+            (SszSchema::Uint(_) | SszSchema::Bool, _) => {
+                let mut buf = vec![];
+                self.write_ssz(schema, path, &mut buf)?;
+                Ok(tree_hash::merkle_root(&buf, 0))
+            }
+            (SszSchema::Vector { item, len }, _) => {
+                let count = self.items_len(item, schema, path)?;
+                if count != *len {
+                    return mismatch(path, schema, &format!("got {} items", count));
+                }
+                items_root(item, self, *len, path)
+            }
+            (SszSchema::List { item, max }, _) => {
+                let count = self.items_len(item, schema, path)?;
+                if count > *max {
+                    return mismatch(path, schema, &format!("got {} items", count));
+                }
+                let root = items_root(item, self, *max, path)?;
+                Ok(tree_hash::mix_in_length(&root, count))
+            }
+            (SszSchema::Bitvector(len), SszValue::Bits(bits)) => {
+                if bits.len() != *len {
+                    return mismatch(path, schema, &format!("got {} bits", bits.len()));
+                }
+                let bytes = bytes_from_bits(bits, schema.fixed_len());
+                Ok(tree_hash::merkle_root(
+                    &bytes,
+                    len.div_ceil(BYTES_PER_CHUNK * 8),
+                ))
+            }
+            (SszSchema::Bitlist(max), SszValue::Bits(bits)) => {
+                if bits.len() > *max {
+                    return mismatch(path, schema, &format!("got {} bits", bits.len()));
+                }
+                // the delimiter is not part of the root, the length is mixed in instead
+                let bytes = bytes_from_bits(bits, bits.len().div_ceil(8));
+                let root = tree_hash::merkle_root(&bytes, max.div_ceil(BYTES_PER_CHUNK * 8));
+                Ok(tree_hash::mix_in_length(&root, bits.len()))
+            }
+            (SszSchema::Container { fields, .. }, SszValue::Container(values)) => {
+                if values.len() != fields.len() {
+                    return mismatch(path, schema, &format!("got {} fields", values.len()));
+                }
+                let mut roots = Vec::with_capacity(fields.len() * BYTES_PER_CHUNK);
+                for ((name, field), value) in fields.iter().zip(values) {
+                    let root = value.root_at(field, &field_path(path, name))?;
+                    roots.extend_from_slice(root.as_slice());
+                }
+                Ok(tree_hash::merkle_root(&roots, fields.len()))
+            }
+            (SszSchema::Union(variants), SszValue::Union(selector, value)) => {
+                let root = match (variants.get(*selector as usize), value) {
+                    (Some(Some(variant)), Some(value)) => value.root_at(variant, path)?,
+                    (Some(None), None) => Hash256::ZERO,
+                    _ => return mismatch(path, schema, &format!("got selector {}", selector)),
+                };
+                match tree_hash::mix_in_selector(&root, *selector) {
+                    Some(root) => Ok(root),
+                    None => mismatch(path, schema, "the selector is too large to hash"),
+                }
+            }
+            _ => mismatch(path, schema, "got a different kind of value"),
+        }
+    }
+
+    // the number of items of a vector or list value, bytes for uint8 items and items otherwise
+    fn items_len(
+        &self,
+        item: &SszSchema,
+        schema: &SszSchema,
+        path: &str,
+    ) -> Result<usize, SchemaMismatch> {
+        match self {
+            SszValue::Bytes(bytes) if item.is_byte() => Ok(bytes.len()),
+            SszValue::Items(items) if !item.is_byte() => Ok(items.len()),
+            _ => mismatch(path, schema, "got a different kind of value"),
+        }
+    }
+}
+
+// the elements of a container, vector or list: static ones in place, variable-size ones behind
+// offsets after the fixed portion
+fn write_elements<'a>(
+    elements: impl Iterator<Item = (&'a SszSchema, &'a SszValue)> + Clone,
+    buf: &mut Vec<u8>,
+    element_path: impl Fn(usize) -> String,
+) -> Result<(), SchemaMismatch> {
+    let start = buf.len();
+    let fixed_len = elements
+        .clone()
+        .map(|(schema, _)| schema.fixed_len())
+        .sum::<usize>();
+    let mut variable = vec![];
+    for (i, (schema, value)) in elements.enumerate() {
+        if schema.is_static() {
+            value.write_ssz(schema, &element_path(i), buf)?;
+        } else {
+            let offset = fixed_len + variable.len();
+            let Ok(offset) = u32::try_from(offset) else {
+                return mismatch(&element_path(i), schema, "the encoding is too long");
+            };
+            buf.extend_from_slice(&offset.to_le_bytes());
+            value.write_ssz(schema, &element_path(i), &mut variable)?;
+        }
+    }
+    debug_assert_eq!(buf.len() - start, fixed_len);
+    buf.extend(variable);
+    Ok(())
+}
+
+// the merkle root of the items of a vector or list, before a list mixes in its length: basic
+// items are packed into chunks, other items contribute their own roots
+fn items_root(
+    item: &SszSchema,
+    value: &SszValue,
+    limit: usize,
+    path: &str,
+) -> Result<Hash256, SchemaMismatch> {
+    match value {
+        SszValue::Bytes(bytes) => Ok(tree_hash::merkle_root(
+            bytes,
+            limit.div_ceil(BYTES_PER_CHUNK),
+        )),
+        SszValue::Items(items) if item.is_basic() => {
+            let mut packed = Vec::with_capacity(items.len() * item.fixed_len());
+            for (i, value) in items.iter().enumerate() {
+                value.write_ssz(item, &format!("{}[{}]", path, i), &mut packed)?;
+            }
+            let leaves = limit
+                .saturating_mul(item.fixed_len())
+                .div_ceil(BYTES_PER_CHUNK);
+            Ok(tree_hash::merkle_root(&packed, leaves))
+        }
+        SszValue::Items(items) => {
+            let mut roots = Vec::with_capacity(items.len() * BYTES_PER_CHUNK);
+            for (i, value) in items.iter().enumerate() {
+                let root = value.root_at(item, &format!("{}[{}]", path, i))?;
+                roots.extend_from_slice(root.as_slice());
+            }
+            Ok(tree_hash::merkle_root(&roots, limit))
+        }
+        _ => Err(SchemaMismatch {
+            path: path.to_string(),
+            reason: format!("expected items of {}, got a different kind of value", item),
+        }),
+    }
+}
+
+macro_rules! uint_reflect {
+    ($($type: ty: $size: expr),*) => {
+        $(
+            impl SszReflect for $type {
+                fn ssz_schema() -> SszSchema {
+                    SszSchema::Uint($size)
+                }
+            }
+        )*
+    };
+}
+
+uint_reflect!(u8: 1, u16: 2, u32: 4, u64: 8, u128: 16, U128: 16, U256: 32);
+
+impl SszReflect for bool {
+    fn ssz_schema() -> SszSchema {
+        SszSchema::Bool
+    }
+}
+
+// fixed-size byte types are byte vectors of their size
+macro_rules! bytes_reflect {
+    ($type: ty, $len: expr) => {
+        impl SszReflect for $type {
+            fn ssz_schema() -> SszSchema {
+                SszSchema::vector(SszSchema::Uint(1), $len)
+            }
+        }
+    };
+}
+
+bytes_reflect!(Address, 20);
+bytes_reflect!(Bloom, 256);
+#[cfg(feature = "ethereum-types")]
+bytes_reflect!(H32, 4);
+#[cfg(feature = "ethereum-types")]
+bytes_reflect!(H160, 20);
+#[cfg(feature = "ethereum-types")]
+bytes_reflect!(H256, 32);
+#[cfg(feature = "bls")]
+bytes_reflect!(PKBytes, 48);
+#[cfg(feature = "bls")]
+bytes_reflect!(Sig, 96);
+
+impl<const N: usize> SszReflect for FixedBytes<N> {
+    fn ssz_schema() -> SszSchema {
+        SszSchema::vector(SszSchema::Uint(1), N)
+    }
+}
+
+impl<N: Unsigned> SszReflect for ByteList<N> {
+    fn ssz_schema() -> SszSchema {
+        SszSchema::list(SszSchema::Uint(1), N::to_usize())
+    }
+}
+
+impl<N: Unsigned> SszReflect for Bitvector<N> {
+    fn ssz_schema() -> SszSchema {
+        SszSchema::Bitvector(N::to_usize())
+    }
+}
+
+impl<N: Unsigned> SszReflect for Bitlist<N> {
+    fn ssz_schema() -> SszSchema {
+        SszSchema::Bitlist(N::to_usize())
+    }
+}
+
+impl<N: Unsigned + Clone> SszReflect for BitVector<N> {
+    fn ssz_schema() -> SszSchema {
+        SszSchema::Bitvector(N::to_usize())
+    }
+}
+
+impl<N: Unsigned + Clone> SszReflect for BitList<N> {
+    fn ssz_schema() -> SszSchema {
+        SszSchema::Bitlist(N::to_usize())
+    }
+}
+
+impl<T: SszReflect, N: Unsigned> SszReflect for VariableList<T, N> {
+    fn ssz_schema() -> SszSchema {
+        SszSchema::list(T::ssz_schema(), N::to_usize())
+    }
+}
+
+impl<T: SszReflect, N: Unsigned> SszReflect for FixedVector<T, N> {
+    fn ssz_schema() -> SszSchema {
+        SszSchema::vector(T::ssz_schema(), N::to_usize())
+    }
+}
+
+#[cfg(feature = "milhouse")]
+impl<T: SszReflect + Value, N: Unsigned> SszReflect for PersistentList<T, N> {
+    fn ssz_schema() -> SszSchema {
+        SszSchema::list(T::ssz_schema(), N::to_usize())
+    }
+}
+
+#[cfg(feature = "milhouse")]
+impl<T: SszReflect + Value, N: Unsigned> SszReflect for PersistentVector<T, N> {
+    fn ssz_schema() -> SszSchema {
+        SszSchema::vector(T::ssz_schema(), N::to_usize())
+    }
+}
+
+#[cfg(feature = "ghilhouse")]
+impl<T: SszReflect + ghilhouse::Value, N: Unsigned> SszReflect for ghilhouse::List<T, N> {
+    fn ssz_schema() -> SszSchema {
+        SszSchema::list(T::ssz_schema(), N::to_usize())
+    }
+}
+
+#[cfg(feature = "ghilhouse")]
+impl<T: SszReflect + ghilhouse::Value, N: Unsigned> SszReflect for ghilhouse::Vector<T, N> {
+    fn ssz_schema() -> SszSchema {
+        SszSchema::vector(T::ssz_schema(), N::to_usize())
+    }
+}
+
+impl<T: SszReflect, const N: usize> SszReflect for [T; N] {
+    fn ssz_schema() -> SszSchema {
+        SszSchema::vector(T::ssz_schema(), N)
+    }
+}
+
+// Option<T> is the SSZ Union[None, T]
+impl<T: SszReflect> SszReflect for Option<T> {
+    fn ssz_schema() -> SszSchema {
+        SszSchema::Union(vec![None, Some(T::ssz_schema())])
+    }
+}
+
+impl<T: SszReflect + Clone> SszReflect for Cow<'_, T> {
+    fn ssz_schema() -> SszSchema {
+        T::ssz_schema()
+    }
+}
+
+macro_rules! pointer_reflect {
+    ($pointer: ident) => {
+        impl<T: SszReflect> SszReflect for $pointer<T> {
+            fn ssz_schema() -> SszSchema {
+                T::ssz_schema()
+            }
+        }
+    };
+}
+
+pointer_reflect!(Arc);
+pointer_reflect!(Box);
+pointer_reflect!(Rc);
+
+// tuples are anonymous containers, with their elements as fields named by index
+macro_rules! tuple_reflect {
+    ($($T: ident: $i: tt),+) => {
+        impl<$($T: SszReflect),+> SszReflect for ($($T,)+) {
+            fn ssz_schema() -> SszSchema {
+                SszSchema::Container {
+                    name: String::new(),
+                    fields: vec![$((stringify!($i).to_string(), $T::ssz_schema())),+],
+                }
+            }
+        }
+    };
+}
+
+tuple_reflect!(A: 0);
+tuple_reflect!(A: 0, B: 1);
+tuple_reflect!(A: 0, B: 1, C: 2);
+tuple_reflect!(A: 0, B: 1, C: 2, D: 3);
+tuple_reflect!(A: 0, B: 1, C: 2, D: 3, E: 4);
+tuple_reflect!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5);
+tuple_reflect!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6);
+tuple_reflect!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7);
@@ -1,9 +1,12 @@
-use crate::{DecodeError, SszDecode, SszEncode};
+use crate::{DecodeError, SszDecode, SszEncode, SszType};
 use bytes::buf::{Buf, BufMut};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use sigp_bls::{PublicKeyBytes, Signature};
 use tree_hash::TreeHash;
 
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
 pub struct PKBytes(PublicKeyBytes);
 
 impl TreeHash for PKBytes {
@@ -29,7 +32,7 @@ impl TreeHash for PKBytes {
     }
 }
 
-impl SszEncode for PKBytes {
+impl SszType for PKBytes {
     fn is_ssz_static() -> bool {
         true
     }
@@ -41,7 +44,9 @@ impl SszEncode for PKBytes {
     fn ssz_max_len() -> usize {
         48
     }
+}
 
+impl SszEncode for PKBytes {
     fn ssz_bytes_len(&self) -> usize {
         48
     }
@@ -58,24 +63,12 @@ impl SszEncode for PKBytes {
 }
 
 impl SszDecode for PKBytes {
-    fn is_ssz_static() -> bool {
-        true
-    }
-
-    fn ssz_fixed_len() -> usize {
-        48
-    }
-
-    fn ssz_max_len() -> usize {
-        48
-    }
-
     fn ssz_read(
         fixed_bytes: &mut impl Buf,
         _variable_bytes: &mut impl Buf,
     ) -> Result<Self, DecodeError> {
         let len = fixed_bytes.remaining();
-        let expected = <Self as SszDecode>::ssz_fixed_len();
+        let expected = <Self as SszType>::ssz_fixed_len();
 
         if len < expected {
             Err(DecodeError::InvalidByteLength { len, expected })
@@ -83,12 +76,13 @@ impl SszDecode for PKBytes {
             let res = PublicKeyBytes::deserialize(&fixed_bytes.chunk()[0..48])
                 .map_err(|e| DecodeError::BytesInvalid(format!("{:?}", e)));
             fixed_bytes.advance(48);
-            Ok(Self(res.unwrap()))
+            Ok(Self(res?))
         }
     }
 }
 
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
 pub struct Sig(Signature);
 
 impl TreeHash for Sig {
@@ -115,24 +109,12 @@ impl TreeHash for Sig {
 }
 
 impl SszDecode for Sig {
-    fn is_ssz_static() -> bool {
-        true
-    }
-
-    fn ssz_fixed_len() -> usize {
-        96
-    }
-
-    fn ssz_max_len() -> usize {
-        96
-    }
-
     fn ssz_read(
         fixed_bytes: &mut impl Buf,
         _variable_bytes: &mut impl Buf,
     ) -> Result<Self, DecodeError> {
         let len = fixed_bytes.remaining();
-        let expected = <Self as SszDecode>::ssz_fixed_len();
+        let expected = <Self as SszType>::ssz_fixed_len();
 
         if len < expected {
             Err(DecodeError::InvalidByteLength { len, expected })
@@ -140,12 +122,12 @@ impl SszDecode for Sig {
             let res = Signature::deserialize(&fixed_bytes.chunk()[0..96])
                 .map_err(|e| DecodeError::BytesInvalid(format!("{:?}", e)));
             fixed_bytes.advance(96);
-            Ok(Self(res.unwrap()))
+            Ok(Self(res?))
         }
     }
 }
 
-impl SszEncode for Sig {
+impl SszType for Sig {
     fn is_ssz_static() -> bool {
         true
     }
@@ -157,7 +139,9 @@ impl SszEncode for Sig {
     fn ssz_max_len() -> usize {
         96
     }
+}
 
+impl SszEncode for Sig {
     fn ssz_bytes_len(&self) -> usize {
         96
     }
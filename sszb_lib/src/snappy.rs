@@ -0,0 +1,205 @@
+//! `ssz_snappy` encoding, as used by the consensus p2p networking spec.
+//!
+//! - req/resp messages are the unsigned varint length of the SSZ bytes, followed by the SSZ bytes
+//!   compressed with the snappy frame format ([`encode_req_resp`], [`decode_req_resp`])
+//! - gossip messages are the SSZ bytes compressed as a single raw snappy block, which starts with
+//!   the uncompressed length ([`encode_gossip`], [`decode_gossip`])
+//!
+//! Decoding checks the declared length against the bounds of the type and [`MAX_PAYLOAD_SIZE`]
+//! before decompressing anything, so a small message cannot make us allocate or inflate more than
+//! a valid value of the type could take up.
+use crate::{DecodeError, SszDecode, SszEncode};
+use snap::read::FrameDecoder;
+use snap::write::FrameEncoder;
+use std::io::{Read, Write};
+
+/// The longest unsigned varint that encodes a `u64`.
+const MAX_VARINT_LEN: usize = 10;
+
+/// The longest uncompressed payload of a req/resp or gossip message, `MAX_PAYLOAD_SIZE` in the
+/// consensus p2p networking spec. Declared lengths above it are rejected whatever the type.
+pub const MAX_PAYLOAD_SIZE: usize = 10 * 1024 * 1024;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum SnappyError {
+    /// The length prefix is not a valid unsigned varint.
+    InvalidLengthPrefix,
+    /// The declared length of the SSZ bytes is outside of the bounds of the type, or above
+    /// [`MAX_PAYLOAD_SIZE`].
+    LengthOutOfBounds { len: usize, min: usize, max: usize },
+    /// The compressed bytes are longer than the compression of the declared length can be.
+    CompressedTooLong { len: usize, max: usize },
+    /// The compressed bytes decompress to something other than the declared length.
+    LengthMismatch { len: usize, declared: usize },
+    /// The compressed bytes are not valid snappy.
+    Snappy(String),
+    /// The decompressed bytes are not a valid SSZ encoding of the type.
+    Ssz(DecodeError),
+}
+
+impl From<DecodeError> for SnappyError {
+    fn from(e: DecodeError) -> Self {
+        SnappyError::Ssz(e)
+    }
+}
+
+/// The bounds on the encoded length of a `T`.
+pub fn ssz_len_bounds<T: SszDecode>() -> (usize, usize) {
+    (T::ssz_min_len(), T::ssz_max_len())
+}
+
+fn check_len<T: SszDecode>(len: usize) -> Result<(), SnappyError> {
+    let (min, max) = ssz_len_bounds::<T>();
+    if len < min || len > max {
+        Err(SnappyError::LengthOutOfBounds { len, min, max })
+    } else {
+        Ok(())
+    }
+}
+
+// messages declare their length, which is checked against MAX_PAYLOAD_SIZE too, so that the
+// bounds on the compressed length are computed for reasonable lengths only
+fn check_declared_len<T: SszDecode>(len: usize) -> Result<(), SnappyError> {
+    let (min, max) = ssz_len_bounds::<T>();
+    let max = max.min(MAX_PAYLOAD_SIZE);
+    if len < min || len > max {
+        Err(SnappyError::LengthOutOfBounds { len, min, max })
+    } else {
+        Ok(())
+    }
+}
+
+// frames hold at most 64KiB of uncompressed data, each behind a 4 byte header and a 4 byte
+// checksum, and the stream starts with a 10 byte identifier frame
+// the bound saturates rather than overflow for lengths no message can have
+fn max_frame_compress_len(len: usize) -> usize {
+    const MAX_FRAME_DATA: usize = 1 << 16;
+    let frames = len.div_ceil(MAX_FRAME_DATA).max(1);
+    let last_frame = len - (frames - 1) * MAX_FRAME_DATA;
+    (10 + frames * 8)
+        .saturating_add(snap::raw::max_compress_len(MAX_FRAME_DATA).saturating_mul(frames - 1))
+        .saturating_add(snap::raw::max_compress_len(last_frame))
+}
+
+fn write_varint(mut n: usize, buf: &mut Vec<u8>) {
+    while n >= 0x80 {
+        buf.push((n as u8 & 0x7f) | 0x80);
+        n >>= 7;
+    }
+    buf.push(n as u8);
+}
+
+// returns the value and the number of bytes it took up
+fn read_varint(bytes: &[u8]) -> Result<(usize, usize), SnappyError> {
+    let mut n: u64 = 0;
+    for (i, byte) in bytes.iter().take(MAX_VARINT_LEN).enumerate() {
+        let bits = (*byte & 0x7f) as u64;
+        if i == MAX_VARINT_LEN - 1 && bits > 1 {
+            return Err(SnappyError::InvalidLengthPrefix);
+        }
+        n |= bits << (7 * i);
+        if byte & 0x80 == 0 {
+            let n = usize::try_from(n).map_err(|_| SnappyError::InvalidLengthPrefix)?;
+            return Ok((n, i + 1));
+        }
+    }
+    Err(SnappyError::InvalidLengthPrefix)
+}
+
+// appends the frame format compression of `ssz` to `buf`
+fn compress_frames(ssz: &[u8], buf: Vec<u8>) -> Vec<u8> {
+    let mut encoder = FrameEncoder::new(buf);
+    encoder
+        .write_all(ssz)
+        .expect("writing to a Vec cannot fail");
+    encoder
+        .into_inner()
+        .unwrap_or_else(|_| unreachable!("flushing to a Vec cannot fail"))
+}
+
+// decompresses at most one byte past `max`, so callers can tell that the input inflates to more
+fn decompress_frames(compressed: &[u8], max: usize) -> Result<Vec<u8>, SnappyError> {
+    let mut ssz = Vec::with_capacity(max.min(compressed.len().saturating_mul(8)));
+    FrameDecoder::new(compressed)
+        .take((max as u64).saturating_add(1))
+        .read_to_end(&mut ssz)
+        .map_err(|e| SnappyError::Snappy(e.to_string()))?;
+    Ok(ssz)
+}
+
+/// Encodes `value` with the snappy frame format, without a length prefix. This is how e2store
+/// files store values, see [`crate::era`].
+pub fn encode_frames<T: SszEncode>(value: &T) -> Vec<u8> {
+    let ssz = value.to_ssz();
+    compress_frames(&ssz, Vec::with_capacity(max_frame_compress_len(ssz.len())))
+}
+
+/// Decodes a value encoded with [`encode_frames`]. Without a declared length, decompression
+/// stops as soon as the output outgrows the bounds of the type.
+pub fn decode_frames<T: SszDecode>(bytes: &[u8]) -> Result<T, SnappyError> {
+    let (_, max) = ssz_len_bounds::<T>();
+    let ssz = decompress_frames(bytes, max)?;
+    check_len::<T>(ssz.len())?;
+    Ok(T::from_ssz_bytes(&ssz)?)
+}
+
+/// Encodes `value` as a req/resp payload.
+pub fn encode_req_resp<T: SszEncode>(value: &T) -> Vec<u8> {
+    let ssz = value.to_ssz();
+    let mut buf = Vec::with_capacity(MAX_VARINT_LEN + max_frame_compress_len(ssz.len()));
+    write_varint(ssz.len(), &mut buf);
+
+    compress_frames(&ssz, buf)
+}
+
+/// Decodes a req/resp payload. `bytes` must hold exactly one payload.
+pub fn decode_req_resp<T: SszDecode>(bytes: &[u8]) -> Result<T, SnappyError> {
+    let (len, prefix_len) = read_varint(bytes)?;
+    check_declared_len::<T>(len)?;
+
+    let compressed = &bytes[prefix_len..];
+    let max = max_frame_compress_len(len);
+    if compressed.len() > max {
+        return Err(SnappyError::CompressedTooLong {
+            len: compressed.len(),
+            max,
+        });
+    }
+
+    let ssz = decompress_frames(compressed, len)?;
+    if ssz.len() != len {
+        return Err(SnappyError::LengthMismatch {
+            len: ssz.len(),
+            declared: len,
+        });
+    }
+
+    Ok(T::from_ssz_bytes(&ssz)?)
+}
+
+/// Encodes `value` as a gossip message.
+pub fn encode_gossip<T: SszEncode>(value: &T) -> Vec<u8> {
+    snap::raw::Encoder::new()
+        .compress_vec(&value.to_ssz())
+        .expect("the SSZ bytes of a value fit in a snappy block")
+}
+
+/// Decodes a gossip message.
+pub fn decode_gossip<T: SszDecode>(bytes: &[u8]) -> Result<T, SnappyError> {
+    let len = snap::raw::decompress_len(bytes).map_err(|e| SnappyError::Snappy(e.to_string()))?;
+    check_declared_len::<T>(len)?;
+
+    let max = snap::raw::max_compress_len(len);
+    if bytes.len() > max {
+        return Err(SnappyError::CompressedTooLong {
+            len: bytes.len(),
+            max,
+        });
+    }
+
+    let ssz = snap::raw::Decoder::new()
+        .decompress_vec(bytes)
+        .map_err(|e| SnappyError::Snappy(e.to_string()))?;
+
+    Ok(T::from_ssz_bytes(&ssz)?)
+}
@@ -0,0 +1,485 @@
+//! JSON/YAML representation of SSZ values, following the consensus spec and beacon API conventions:
+//!
+//! - unsigned integers are decimal strings (integers are accepted too when deserializing)
+//! - byte vectors and byte lists, including sequences of `u8`, are 0x-prefixed hex (sequences
+//!   of integers are accepted too when deserializing)
+//! - bitfields are the 0x-prefixed hex of their SSZ encoding
+//! - lists and vectors of anything else are sequences
+//! - `Option<T>` (`Union[None, T]`) is `null` or the value
+//!
+//! `#[derive(SszbSerde)]` implements serde's traits for a container with this representation.
+//! Fields of other serde types can opt in with `#[serde(with = "sszb::spec_serde")]`.
+use crate::{Bitlist, Bitvector, ByteList, DecodeError, SszDecode, SszEncode};
+#[cfg(feature = "bls")]
+use crate::{PKBytes, Sig};
+use alloy_primitives::{hex, Address, Bloom, Bytes as AlloyBytes, FixedBytes, U128, U256};
+#[cfg(feature = "ethereum-types")]
+use ethereum_types::{H160, H256, H32};
+#[cfg(feature = "milhouse")]
+use milhouse::{List as PersistentList, Value, Vector as PersistentVector};
+use serde::de::{Deserialize, Deserializer, Error as _, SeqAccess, Visitor};
+use serde::ser::{Serialize, Serializer};
+use ssz_types::{BitList, BitVector, FixedVector, VariableList};
+use std::borrow::Cow;
+use std::fmt;
+use std::marker::PhantomData;
+use std::rc::Rc;
+use std::str::FromStr;
+use std::sync::Arc;
+use typenum::Unsigned;
+
+// used by the code generated by #[derive(SszbSerde)]
+#[doc(hidden)]
+pub use serde;
+
+/// A type with a consensus spec JSON/YAML representation.
+pub trait SszSerde: Sized {
+    fn ssz_serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+
+    fn ssz_deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>;
+
+    /// Serializes a list or vector of `Self`. `u8` overrides this to write hex.
+    fn ssz_serialize_seq<'a, S, I>(items: I, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        I: IntoIterator<Item = &'a Self>,
+        Self: 'a,
+    {
+        serializer.collect_seq(items.into_iter().map(SszSerdeRef))
+    }
+
+    /// Deserializes a list or vector of `Self`. `u8` overrides this to read hex.
+    fn ssz_deserialize_seq<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Self>, D::Error> {
+        let items = Vec::<SszSerdeOwned<Self>>::deserialize(deserializer)?;
+        Ok(items.into_iter().map(|item| item.0).collect())
+    }
+}
+
+/// Serializes the wrapped value with its `SszSerde` representation.
+pub struct SszSerdeRef<'a, T>(pub &'a T);
+
+impl<T: SszSerde> Serialize for SszSerdeRef<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.ssz_serialize(serializer)
+    }
+}
+
+/// Deserializes the wrapped value from its `SszSerde` representation.
+pub struct SszSerdeOwned<T>(pub T);
+
+impl<'de, T: SszSerde> Deserialize<'de> for SszSerdeOwned<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::ssz_deserialize(deserializer).map(SszSerdeOwned)
+    }
+}
+
+/// For `#[serde(with = "sszb::spec_serde")]`.
+pub fn serialize<T: SszSerde, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    value.ssz_serialize(serializer)
+}
+
+/// For `#[serde(with = "sszb::spec_serde")]`.
+pub fn deserialize<'de, T: SszSerde, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+    T::ssz_deserialize(deserializer)
+}
+
+fn decode_error<E: serde::de::Error>(e: DecodeError) -> E {
+    E::custom(format!("invalid SSZ bytes: {:?}", e))
+}
+
+fn hex_to_bytes<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    let s = Cow::<str>::deserialize(deserializer)?;
+    hex::decode(s.as_ref()).map_err(D::Error::custom)
+}
+
+// serializes a value as the 0x-prefixed hex of its SSZ encoding
+fn serialize_ssz_hex<T: SszEncode, S: Serializer>(
+    value: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&hex::encode_prefixed(value.to_ssz()))
+}
+
+fn deserialize_ssz_hex<'de, T: SszDecode, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    T::from_ssz_bytes(&hex_to_bytes(deserializer)?).map_err(decode_error)
+}
+
+// accepts both decimal strings and integers
+struct QuotedVisitor<T>(PhantomData<T>);
+
+impl<T> Visitor<'_> for QuotedVisitor<T>
+where
+    T: FromStr + TryFrom<u64>,
+    <T as FromStr>::Err: fmt::Display,
+{
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a decimal string or an unsigned integer")
+    }
+
+    fn visit_str<E: serde::de::Error>(self, s: &str) -> Result<T, E> {
+        s.parse().map_err(E::custom)
+    }
+
+    fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<T, E> {
+        T::try_from(v).map_err(|_| E::custom(format!("integer {} is out of range", v)))
+    }
+
+    fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<T, E> {
+        let v = u64::try_from(v).map_err(|_| E::custom(format!("integer {} is negative", v)))?;
+        self.visit_u64(v)
+    }
+}
+
+macro_rules! quoted_spec_serde {
+    ($type: ty) => {
+        impl SszSerde for $type {
+            fn ssz_serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+
+            fn ssz_deserialize<'de, D: Deserializer<'de>>(
+                deserializer: D,
+            ) -> Result<Self, D::Error> {
+                deserializer.deserialize_any(QuotedVisitor(PhantomData))
+            }
+        }
+    };
+}
+
+quoted_spec_serde!(u16);
+quoted_spec_serde!(u32);
+quoted_spec_serde!(u64);
+quoted_spec_serde!(U128);
+quoted_spec_serde!(U256);
+
+impl SszSerde for u8 {
+    fn ssz_serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+
+    fn ssz_deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(QuotedVisitor(PhantomData))
+    }
+
+    fn ssz_serialize_seq<'a, S, I>(items: I, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        I: IntoIterator<Item = &'a Self>,
+    {
+        let bytes = items.into_iter().copied().collect::<Vec<u8>>();
+        serializer.serialize_str(&hex::encode_prefixed(bytes))
+    }
+
+    fn ssz_deserialize_seq<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Self>, D::Error> {
+        deserializer.deserialize_any(BytesVisitor)
+    }
+}
+
+// the spec writes `List[uint8, N]` and `Vector[uint8, N]` as sequences of integers, and the
+// `ByteList`/`ByteVector` aliases as hex, both are accepted for any sequence of `u8`
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a 0x-prefixed hex string or a sequence of bytes")
+    }
+
+    fn visit_str<E: serde::de::Error>(self, s: &str) -> Result<Vec<u8>, E> {
+        hex::decode(s).map_err(E::custom)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<u8>, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(SszSerdeOwned(byte)) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(bytes)
+    }
+}
+
+impl SszSerde for bool {
+    fn ssz_serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bool(*self)
+    }
+
+    fn ssz_deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        bool::deserialize(deserializer)
+    }
+}
+
+// fixed-size byte types and bitfields are the hex of their SSZ encoding
+macro_rules! hex_spec_serde {
+    ($type: ty) => {
+        impl SszSerde for $type {
+            fn ssz_serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serialize_ssz_hex(self, serializer)
+            }
+
+            fn ssz_deserialize<'de, D: Deserializer<'de>>(
+                deserializer: D,
+            ) -> Result<Self, D::Error> {
+                deserialize_ssz_hex(deserializer)
+            }
+        }
+    };
+}
+
+hex_spec_serde!(Address);
+hex_spec_serde!(Bloom);
+#[cfg(feature = "ethereum-types")]
+hex_spec_serde!(H32);
+#[cfg(feature = "ethereum-types")]
+hex_spec_serde!(H160);
+#[cfg(feature = "ethereum-types")]
+hex_spec_serde!(H256);
+#[cfg(feature = "bls")]
+hex_spec_serde!(PKBytes);
+#[cfg(feature = "bls")]
+hex_spec_serde!(Sig);
+
+impl<const N: usize> SszSerde for FixedBytes<N> {
+    fn ssz_serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&hex::encode_prefixed(self))
+    }
+
+    fn ssz_deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bytes = hex_to_bytes(deserializer)?;
+        Self::try_from(bytes.as_slice())
+            .map_err(|_| D::Error::custom(format!("expected {} bytes, got {}", N, bytes.len())))
+    }
+}
+
+impl SszSerde for AlloyBytes {
+    fn ssz_serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&hex::encode_prefixed(self))
+    }
+
+    fn ssz_deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        hex_to_bytes(deserializer).map(AlloyBytes::from)
+    }
+}
+
+impl<N: Unsigned> SszSerde for ByteList<N> {
+    fn ssz_serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&hex::encode_prefixed(self.as_bytes()))
+    }
+
+    fn ssz_deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::new(hex_to_bytes(deserializer)?).map_err(decode_error)
+    }
+}
+
+impl<N: Unsigned> SszSerde for Bitvector<N> {
+    fn ssz_serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_ssz_hex(self, serializer)
+    }
+
+    fn ssz_deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_ssz_hex(deserializer)
+    }
+}
+
+impl<N: Unsigned> SszSerde for Bitlist<N> {
+    fn ssz_serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_ssz_hex(self, serializer)
+    }
+
+    fn ssz_deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_ssz_hex(deserializer)
+    }
+}
+
+// sszb's own bitfields use the same representation with plain serde
+impl<N: Unsigned> Serialize for Bitvector<N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.ssz_serialize(serializer)
+    }
+}
+
+impl<'de, N: Unsigned> Deserialize<'de> for Bitvector<N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::ssz_deserialize(deserializer)
+    }
+}
+
+impl<N: Unsigned> Serialize for Bitlist<N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.ssz_serialize(serializer)
+    }
+}
+
+impl<'de, N: Unsigned> Deserialize<'de> for Bitlist<N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::ssz_deserialize(deserializer)
+    }
+}
+
+impl<N: Unsigned + Clone> SszSerde for BitVector<N> {
+    fn ssz_serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_ssz_hex(self, serializer)
+    }
+
+    fn ssz_deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_ssz_hex(deserializer)
+    }
+}
+
+impl<N: Unsigned + Clone> SszSerde for BitList<N> {
+    fn ssz_serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_ssz_hex(self, serializer)
+    }
+
+    fn ssz_deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_ssz_hex(deserializer)
+    }
+}
+
+// lists and vectors check their length against N when deserializing
+impl<T: SszSerde, N: Unsigned> SszSerde for VariableList<T, N> {
+    fn ssz_serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        T::ssz_serialize_seq(self.iter(), serializer)
+    }
+
+    fn ssz_deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::new(T::ssz_deserialize_seq(deserializer)?)
+            .map_err(|e| D::Error::custom(format!("{:?}", e)))
+    }
+}
+
+impl<T: SszSerde, N: Unsigned> SszSerde for FixedVector<T, N> {
+    fn ssz_serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        T::ssz_serialize_seq(self.iter(), serializer)
+    }
+
+    fn ssz_deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::new(T::ssz_deserialize_seq(deserializer)?)
+            .map_err(|e| D::Error::custom(format!("{:?}", e)))
+    }
+}
+
+#[cfg(feature = "milhouse")]
+impl<T: SszSerde + Value, N: Unsigned> SszSerde for PersistentList<T, N> {
+    fn ssz_serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        T::ssz_serialize_seq(self.iter(), serializer)
+    }
+
+    fn ssz_deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::new(T::ssz_deserialize_seq(deserializer)?)
+            .map_err(|e| D::Error::custom(format!("{:?}", e)))
+    }
+}
+
+#[cfg(feature = "milhouse")]
+impl<T: SszSerde + Value, N: Unsigned> SszSerde for PersistentVector<T, N> {
+    fn ssz_serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        T::ssz_serialize_seq(self.iter(), serializer)
+    }
+
+    fn ssz_deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::new(T::ssz_deserialize_seq(deserializer)?)
+            .map_err(|e| D::Error::custom(format!("{:?}", e)))
+    }
+}
+
+#[cfg(feature = "ghilhouse")]
+impl<T: SszSerde + ghilhouse::Value, N: Unsigned> SszSerde for ghilhouse::List<T, N> {
+    fn ssz_serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        T::ssz_serialize_seq(self.iter(), serializer)
+    }
+
+    fn ssz_deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::new(T::ssz_deserialize_seq(deserializer)?)
+            .map_err(|e| D::Error::custom(format!("{:?}", e)))
+    }
+}
+
+#[cfg(feature = "ghilhouse")]
+impl<T: SszSerde + ghilhouse::Value, N: Unsigned> SszSerde for ghilhouse::Vector<T, N> {
+    fn ssz_serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        T::ssz_serialize_seq(self.iter(), serializer)
+    }
+
+    fn ssz_deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::new(T::ssz_deserialize_seq(deserializer)?)
+            .map_err(|e| D::Error::custom(format!("{:?}", e)))
+    }
+}
+
+impl<T: SszSerde, const N: usize> SszSerde for [T; N] {
+    fn ssz_serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        T::ssz_serialize_seq(self.iter(), serializer)
+    }
+
+    fn ssz_deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let items = T::ssz_deserialize_seq(deserializer)?;
+        let len = items.len();
+        items
+            .try_into()
+            .map_err(|_| D::Error::invalid_length(len, &format!("{} items", N).as_str()))
+    }
+}
+
+// runtime-bounded lists are checked against #[ssz(max = N)] when they are decoded from SSZ
+impl<T: SszSerde> SszSerde for Vec<T> {
+    fn ssz_serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        T::ssz_serialize_seq(self.iter(), serializer)
+    }
+
+    fn ssz_deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::ssz_deserialize_seq(deserializer)
+    }
+}
+
+impl<T: SszSerde> SszSerde for Box<[T]> {
+    fn ssz_serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        T::ssz_serialize_seq(self.iter(), serializer)
+    }
+
+    fn ssz_deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::ssz_deserialize_seq(deserializer).map(Vec::into_boxed_slice)
+    }
+}
+
+impl<T: SszSerde> SszSerde for Option<T> {
+    fn ssz_serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            None => serializer.serialize_none(),
+            Some(value) => serializer.serialize_some(&SszSerdeRef(value)),
+        }
+    }
+
+    fn ssz_deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Option::<SszSerdeOwned<T>>::deserialize(deserializer)?;
+        Ok(value.map(|value| value.0))
+    }
+}
+
+macro_rules! pointer_spec_serde {
+    ($pointer: ident) => {
+        impl<T: SszSerde> SszSerde for $pointer<T> {
+            fn ssz_serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                self.as_ref().ssz_serialize(serializer)
+            }
+
+            fn ssz_deserialize<'de, D: Deserializer<'de>>(
+                deserializer: D,
+            ) -> Result<Self, D::Error> {
+                T::ssz_deserialize(deserializer).map($pointer::new)
+            }
+        }
+    };
+}
+
+pointer_spec_serde!(Arc);
+pointer_spec_serde!(Box);
+pointer_spec_serde!(Rc);
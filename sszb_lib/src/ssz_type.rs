@@ -0,0 +1,53 @@
+use crate::BYTES_PER_LENGTH_OFFSET;
+
+/// The layout of a type's SSZ encoding, shared by `SszEncode` and `SszDecode` so that both sides
+/// agree on it.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` has no SSZ layout, as it does not implement `SszType`",
+    note = "`#[derive(SszbEncode)]` implements it, containers that are only decoded derive it with `#[derive(SszbDecode, SszbType)]`"
+)]
+pub trait SszType {
+    // whether the type has a fixed size, static types are written in place in containers,
+    // variable-sized ones behind an offset
+    fn is_ssz_static() -> bool;
+
+    // all lengths are in number of bytes
+    // the length the type takes up in the fixed portion of a container: its encoded length if
+    // it is static, and the length of an offset otherwise
+    fn ssz_fixed_len() -> usize;
+
+    // the shortest valid encoding, e.g. to reject a payload before decoding it
+    // static types are always their fixed length, and variable-sized ones default to 0 (an empty
+    // list), types with a longer minimum override it
+    fn ssz_min_len() -> usize {
+        if Self::is_ssz_static() {
+            Self::ssz_fixed_len()
+        } else {
+            0
+        }
+    }
+
+    // helper function, use when preallocating the max bytes needed to encode this type
+    // types without a maximum length return usize::MAX, so lengths adding up to it saturate
+    fn ssz_max_len() -> usize;
+}
+
+/// The minimum length of a `T` as an element of a container, vector or list: its fixed length if
+/// it is static, and its offset followed by its shortest encoding otherwise.
+pub fn ssz_element_min_len<T: SszType>() -> usize {
+    if T::is_ssz_static() {
+        T::ssz_fixed_len()
+    } else {
+        BYTES_PER_LENGTH_OFFSET.saturating_add(T::ssz_min_len())
+    }
+}
+
+/// The maximum length of a `T` as an element of a container, vector or list: its fixed length if
+/// it is static, and its offset followed by its longest encoding otherwise.
+pub fn ssz_element_max_len<T: SszType>() -> usize {
+    if T::is_ssz_static() {
+        T::ssz_fixed_len()
+    } else {
+        BYTES_PER_LENGTH_OFFSET.saturating_add(T::ssz_max_len())
+    }
+}
@@ -0,0 +1,300 @@
+use bytes::buf::{Buf, BufMut};
+use sszb::era::*;
+use sszb::ByteList;
+use sszb_derive::{SszbDecode, SszbEncode};
+
+// stand-ins for the block and state types
+#[derive(PartialEq, Debug, SszbDecode, SszbEncode)]
+struct Block {
+    slot: u16,
+    body: ByteList<typenum::U10>,
+}
+
+#[derive(PartialEq, Debug, SszbDecode, SszbEncode)]
+struct State {
+    a: u16,
+    b: Option<u64>,
+}
+
+fn block(slot: u64) -> Block {
+    Block {
+        slot: slot as u16,
+        body: ByteList::new((0..(slot % 10) as u8).collect::<Vec<_>>()).unwrap(),
+    }
+}
+
+fn state() -> State {
+    State { a: 7, b: Some(3) }
+}
+
+const SLOTS: [u64; 5] = [8192, 8193, 8200, 12000, 16383];
+
+// the file of era 2, with the blocks of SLOTS
+fn era_file() -> Vec<u8> {
+    let mut writer = EraWriter::new(vec![], 2).unwrap();
+    for slot in SLOTS {
+        writer.write_block(slot, &block(slot)).unwrap();
+    }
+    writer.finish(&state()).unwrap()
+}
+
+// the state index is the last record, of one offset
+fn state_index_start(bytes: &[u8]) -> usize {
+    bytes.len() - 32
+}
+
+fn block_index_start(bytes: &[u8]) -> usize {
+    state_index_start(bytes) - (8 + 8 + 8192 * 8 + 8)
+}
+
+#[test]
+fn test_writer() {
+    let bytes = era_file();
+    let kinds = entries(&bytes)
+        .map(|entry| entry.unwrap().1.kind)
+        .collect::<Vec<_>>();
+    assert_eq!(kinds[0], VERSION);
+    assert_eq!(kinds[1..6], [COMPRESSED_SIGNED_BEACON_BLOCK; 5]);
+    assert_eq!(
+        kinds[6..],
+        [COMPRESSED_BEACON_STATE, SLOT_INDEX, SLOT_INDEX]
+    );
+
+    // blocks are written in slot order, within the era
+    let mut writer = EraWriter::new(vec![], 2).unwrap();
+    writer.write_block(8200, &block(8200)).unwrap();
+    assert_eq!(
+        writer.write_block(8200, &block(8200)),
+        Err(EraError::InvalidSlot(8200))
+    );
+    assert_eq!(
+        writer.write_block(8192, &block(8192)),
+        Err(EraError::InvalidSlot(8192))
+    );
+    assert_eq!(
+        writer.write_block(16384, &block(0)),
+        Err(EraError::InvalidSlot(16384))
+    );
+    assert_eq!(
+        writer.write_block(8191, &block(0)),
+        Err(EraError::InvalidSlot(8191))
+    );
+
+    // era 0 has no blocks, and no era starts past the last slot
+    let mut genesis = EraWriter::new(vec![], 0).unwrap();
+    assert_eq!(
+        genesis.write_block(0, &block(0)),
+        Err(EraError::InvalidSlot(0))
+    );
+    assert_eq!(
+        EraWriter::new(vec![], u64::MAX).err(),
+        Some(EraError::InvalidEra(u64::MAX))
+    );
+}
+
+#[test]
+fn test_reader() {
+    let bytes = era_file();
+    let era = EraReader::new(&bytes).unwrap();
+    assert_eq!(era.era(), 2);
+    assert_eq!(era.state_slot(), 16384);
+    assert_eq!(era.block_index().unwrap().starting_slot, 8192);
+    assert_eq!(era.block_index().unwrap().offsets.len(), 8192);
+    assert_eq!(era.state_index().starting_slot, 16384);
+    assert_eq!(era.state::<State>(), Ok(state()));
+    let blocks = era
+        .blocks::<Block>()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(
+        blocks,
+        SLOTS
+            .map(|slot| (slot, block(slot)))
+            .into_iter()
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(era.block::<Block>(8200), Ok(Some(block(8200))));
+    assert_eq!(era.block::<Block>(8201), Ok(None));
+    assert_eq!(era.block::<Block>(8191), Ok(None));
+    assert_eq!(era.block::<Block>(16384), Ok(None));
+
+    // era 0 only holds the genesis state
+    let genesis = EraWriter::new(vec![], 0).unwrap().finish(&state()).unwrap();
+    let era = EraReader::new(&genesis).unwrap();
+    assert_eq!(era.era(), 0);
+    assert_eq!(era.block_index(), None);
+    assert_eq!(era.blocks::<Block>().count(), 0);
+    assert_eq!(era.block::<Block>(0), Ok(None));
+    assert_eq!(era.state::<State>(), Ok(state()));
+
+    // records are only decoded when read
+    let mut bad = bytes.clone();
+    let block_start = 8 + 8;
+    bad[block_start..block_start + 4].copy_from_slice(&[0xff; 4]);
+    let era = EraReader::new(&bad).unwrap();
+    assert!(matches!(era.block::<Block>(8192), Err(EraError::Snappy(_))));
+    assert_eq!(era.block::<Block>(8193), Ok(Some(block(8193))));
+}
+
+#[test]
+fn test_records() {
+    let bytes = era_file();
+    assert_eq!(
+        read_entry(&bytes, 0),
+        Ok(Entry {
+            kind: VERSION,
+            data: &[]
+        })
+    );
+    assert_eq!(
+        read_entry(&bytes, bytes.len() - 4),
+        Err(EraError::TruncatedRecord {
+            offset: bytes.len() - 4
+        })
+    );
+    assert_eq!(
+        read_entry(&bytes[..bytes.len() - 1], state_index_start(&bytes)),
+        Err(EraError::TruncatedRecord {
+            offset: state_index_start(&bytes)
+        })
+    );
+    let mut bad = bytes.clone();
+    bad[7] = 1;
+    assert_eq!(
+        read_entry(&bad, 0),
+        Err(EraError::ReservedNotZero { offset: 0 })
+    );
+
+    // iteration stops after the first malformed record
+    let mut iter = entries(&bytes[..bytes.len() - 1]);
+    assert_eq!(iter.by_ref().filter(Result::is_ok).count(), 8);
+    assert_eq!(iter.next(), None);
+    let entries = entries(&bytes[..bytes.len() - 1]).collect::<Vec<_>>();
+    assert_eq!(
+        entries.last(),
+        Some(&Err(EraError::TruncatedRecord {
+            offset: state_index_start(&bytes)
+        }))
+    );
+}
+
+#[test]
+fn test_slot_index() {
+    let index = SlotIndex {
+        starting_slot: 8192,
+        offsets: vec![-16, 0, -8],
+    };
+    let data = index.to_bytes();
+    assert_eq!(data.len(), 8 + 3 * 8 + 8);
+    assert_eq!(SlotIndex::from_bytes(&data), Ok(index));
+
+    // the data is the starting slot, the offsets and their count, in words of 8 bytes
+    for len in [0, 8, 15, 20, 31] {
+        assert!(matches!(
+            SlotIndex::from_bytes(&data[..len]),
+            Err(EraError::InvalidSlotIndex(_))
+        ));
+    }
+    let mut bad = data.clone();
+    bad[32..].copy_from_slice(&4u64.to_le_bytes());
+    assert!(matches!(
+        SlotIndex::from_bytes(&bad),
+        Err(EraError::InvalidSlotIndex(_))
+    ));
+    assert_eq!(
+        SlotIndex::from_bytes(&[0; 16]),
+        Ok(SlotIndex {
+            starting_slot: 0,
+            offsets: vec![]
+        })
+    );
+}
+
+#[test]
+fn test_index_validation() {
+    let bytes = era_file();
+    let state_index_start = state_index_start(&bytes);
+    let block_index_start = block_index_start(&bytes);
+
+    // slot indexes must point to records of the right type, within the file
+    let mut bad = bytes.clone();
+    bad[state_index_start + 16..state_index_start + 24]
+        .copy_from_slice(&(8 - state_index_start as i64).to_le_bytes());
+    assert_eq!(
+        EraReader::new(&bad).err(),
+        Some(EraError::UnexpectedRecord {
+            offset: 8,
+            kind: COMPRESSED_SIGNED_BEACON_BLOCK
+        })
+    );
+    for offset in [1, -(bytes.len() as i64), i64::MIN] {
+        let mut bad = bytes.clone();
+        bad[state_index_start + 16..state_index_start + 24].copy_from_slice(&offset.to_le_bytes());
+        assert!(matches!(
+            EraReader::new(&bad),
+            Err(EraError::InvalidSlotIndex(_))
+        ));
+    }
+    let mut bad = bytes.clone();
+    bad[state_index_start + 16..state_index_start + 24].copy_from_slice(&0i64.to_le_bytes());
+    assert!(matches!(
+        EraReader::new(&bad),
+        Err(EraError::InvalidSlotIndex(_))
+    ));
+    let mut bad = bytes.clone();
+    bad[block_index_start + 16..block_index_start + 24]
+        .copy_from_slice(&(state_index_start as i64 - block_index_start as i64).to_le_bytes());
+    assert!(matches!(
+        EraReader::new(&bad),
+        Err(EraError::InvalidSlotIndex(_))
+    ));
+
+    // the state slot starts an era, and the block index ends there without overflowing
+    let mut bad = bytes.clone();
+    bad[state_index_start + 8..state_index_start + 16].copy_from_slice(&16385u64.to_le_bytes());
+    assert!(matches!(
+        EraReader::new(&bad),
+        Err(EraError::InvalidSlotIndex(_))
+    ));
+    let mut bad = bytes.clone();
+    bad[block_index_start + 8..block_index_start + 16]
+        .copy_from_slice(&(u64::MAX - 100).to_le_bytes());
+    assert!(matches!(
+        EraReader::new(&bad),
+        Err(EraError::InvalidSlotIndex(_))
+    ));
+
+    // the count of the last index locates its record, which must be a slot index
+    let mut bad = bytes.clone();
+    let len = bad.len();
+    bad[len - 8..].copy_from_slice(&u64::MAX.to_le_bytes());
+    assert!(matches!(
+        EraReader::new(&bad),
+        Err(EraError::InvalidSlotIndex(_))
+    ));
+    bad[len - 8..].copy_from_slice(&2u64.to_le_bytes());
+    assert!(matches!(
+        EraReader::new(&bad),
+        Err(EraError::UnexpectedRecord { .. })
+    ));
+}
+
+#[test]
+fn test_truncated_files() {
+    let bytes = era_file();
+    assert_eq!(
+        EraReader::new(&[]).err(),
+        Some(EraError::TruncatedRecord { offset: 0 })
+    );
+    assert!(matches!(
+        EraReader::new(&bytes[..8]),
+        Err(EraError::InvalidSlotIndex(_))
+    ));
+    for len in [bytes.len() - 1, bytes.len() - 8, bytes.len() - 32] {
+        assert!(EraReader::new(&bytes[..len]).is_err());
+    }
+    assert!(matches!(
+        EraReader::new(&bytes[8..]),
+        Err(EraError::UnexpectedRecord { offset: 0, .. })
+    ));
+}
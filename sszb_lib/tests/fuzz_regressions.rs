@@ -0,0 +1,14 @@
+//! Replays the inputs under `fuzz/regressions/<target>` with the workspace tests, since the fuzz
+//! crate builds on its own.
+use std::path::Path;
+
+#[allow(dead_code)]
+#[path = "../../fuzz/src/lib.rs"]
+mod sszb_fuzz;
+
+#[test]
+fn test_fuzz_regressions() {
+    sszb_fuzz::replay_regressions(
+        &Path::new(env!("CARGO_MANIFEST_DIR")).join("../fuzz/regressions"),
+    );
+}
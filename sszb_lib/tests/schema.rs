@@ -0,0 +1,207 @@
+use ssz_types::{BitList, FixedVector, VariableList};
+use sszb::{ByteList, SszEncode, SszReflect, SszSchema, SszValue};
+use tree_hash::TreeHash;
+use typenum::{U10, U3, U32, U70};
+
+type Uint16s = VariableList<u16, U10>;
+
+fn container(name: &str, fields: Vec<(&str, SszSchema)>) -> SszSchema {
+    SszSchema::Container {
+        name: name.to_string(),
+        fields: fields
+            .into_iter()
+            .map(|(name, schema)| (name.to_string(), schema))
+            .collect(),
+    }
+}
+
+// Container VariableB: a: uint16, b: List[uint16, 10]
+fn variable_b() -> SszSchema {
+    container(
+        "VariableB",
+        vec![
+            ("a", SszSchema::Uint(2)),
+            ("b", SszSchema::list(SszSchema::Uint(2), 10)),
+        ],
+    )
+}
+
+fn uint(n: u64) -> SszValue {
+    SszValue::Uint(alloy_primitives::U256::from(n))
+}
+
+// a value of T decodes into an SszValue that encodes back to the same bytes
+fn assert_dynamic_roundtrip<T: SszEncode + SszReflect>(value: &T) -> SszValue {
+    let schema = T::ssz_schema();
+    let bytes = value.to_ssz();
+    let dynamic = SszValue::from_ssz_bytes(&schema, &bytes).unwrap();
+    assert_eq!(dynamic.to_ssz(&schema).unwrap(), bytes, "{}", schema);
+    dynamic
+}
+
+// and hashes to the same root
+fn assert_dynamic_root<T: SszEncode + SszReflect + TreeHash>(value: &T) {
+    let dynamic = assert_dynamic_roundtrip(value);
+    assert_eq!(
+        dynamic.tree_hash_root(&T::ssz_schema()).unwrap(),
+        value.tree_hash_root()
+    );
+}
+
+#[test]
+fn test_schema_notation() {
+    assert_eq!(bool::ssz_schema().to_string(), "boolean");
+    assert_eq!(u64::ssz_schema().to_string(), "uint64");
+    assert_eq!(<[u64; 4]>::ssz_schema().to_string(), "Vector[uint64, 4]");
+    assert_eq!(
+        alloy_primitives::Address::ssz_schema().to_string(),
+        "Vector[uint8, 20]"
+    );
+    assert_eq!(ByteList::<U32>::ssz_schema().to_string(), "List[uint8, 32]");
+    assert_eq!(BitList::<U70>::ssz_schema().to_string(), "Bitlist[70]");
+    assert_eq!(
+        sszb::Bitvector::<U10>::ssz_schema().to_string(),
+        "Bitvector[10]"
+    );
+    assert_eq!(
+        <Option<Uint16s>>::ssz_schema().to_string(),
+        "Union[None, List[uint16, 10]]"
+    );
+    // tuples are containers without a name, and named containers show their name
+    assert_eq!(
+        <(u8, Uint16s, u32)>::ssz_schema().to_string(),
+        "Container[uint8, List[uint16, 10], uint32]"
+    );
+    assert_eq!(variable_b().to_string(), "VariableB");
+}
+
+#[test]
+fn test_schema_layout() {
+    assert!(SszSchema::Bool.is_static());
+    assert_eq!(SszSchema::Uint(8).fixed_len(), 8);
+    assert_eq!(SszSchema::Bitvector(10).fixed_len(), 2);
+    assert_eq!(<[u64; 4]>::ssz_schema().fixed_len(), 32);
+    assert_eq!(<(u8, u16)>::ssz_schema().fixed_len(), 3);
+
+    // variable-size schemas take an offset in the fixed portion
+    for schema in [
+        SszSchema::list(SszSchema::Uint(2), 10),
+        SszSchema::Bitlist(70),
+        <Option<u8>>::ssz_schema(),
+        <[Uint16s; 2]>::ssz_schema(),
+        variable_b(),
+    ] {
+        assert!(!schema.is_static(), "{}", schema);
+        assert_eq!(schema.fixed_len(), 4, "{}", schema);
+    }
+}
+
+#[test]
+fn test_dynamic_values() {
+    let list = Uint16s::new(vec![1, 2, 3]).unwrap();
+    let mut bitlist = BitList::<U70>::with_capacity(20).unwrap();
+    bitlist.set(3, true).unwrap();
+    let mut bitvector = sszb::Bitvector::<U10>::new();
+    bitvector.set(0, true).unwrap();
+
+    assert_dynamic_root(&7u16);
+    assert_dynamic_root(&list);
+    assert_dynamic_root(&FixedVector::<Uint16s, U3>::new(vec![list.clone(); 3]).unwrap());
+    assert_dynamic_root(&bitlist);
+    assert_dynamic_root(&bitvector);
+    assert_dynamic_root(&ByteList::<U32>::new(vec![1, 2, 3]).unwrap());
+    assert_dynamic_roundtrip(&(1u8, list.clone(), 7u32));
+    assert_dynamic_roundtrip(&[list.clone(), Uint16s::new(vec![]).unwrap()]);
+    assert_dynamic_roundtrip(&Some(list));
+    assert_dynamic_roundtrip(&None::<u64>);
+
+    assert_eq!(
+        SszValue::from_ssz_bytes(&variable_b(), &[1, 0, 6, 0, 0, 0, 2, 0]),
+        Ok(SszValue::Container(vec![
+            uint(1),
+            SszValue::Items(vec![uint(2)])
+        ]))
+    );
+    assert_eq!(
+        SszValue::from_ssz_bytes(&ByteList::<U32>::ssz_schema(), &[1, 2]),
+        Ok(SszValue::Bytes(vec![1, 2]))
+    );
+    assert_eq!(
+        SszValue::from_ssz_bytes(&<Option<u8>>::ssz_schema(), &[1, 5]),
+        Ok(SszValue::Union(1, Some(Box::new(uint(5)))))
+    );
+}
+
+#[test]
+fn test_invalid_encodings() {
+    // what the typed path rejects, the dynamic one rejects as well
+    let optional_fields = container(
+        "OptionalFields",
+        vec![
+            ("a", SszSchema::Uint(2)),
+            ("b", <Option<u64>>::ssz_schema()),
+            ("c", <Option<Uint16s>>::ssz_schema()),
+        ],
+    );
+    let bitfields = container(
+        "Bitfields",
+        vec![
+            ("a", SszSchema::Bitvector(10)),
+            ("b", SszSchema::Bitlist(70)),
+        ],
+    );
+    let invalid: [(SszSchema, &[u8]); 7] = [
+        (variable_b(), &[1, 0, 6, 0, 0, 0, 2]),
+        (variable_b(), &[1, 0, 7, 0, 0, 0, 2, 0]),
+        (optional_fields, &[1, 0, 10, 0, 0, 0, 10, 0, 0, 0, 2]),
+        (bitfields, &[0, 4, 4, 0, 0, 0, 0]),
+        (<[u64; 2]>::ssz_schema(), &[0; 17]),
+        (<Option<u8>>::ssz_schema(), &[0, 0]),
+        (SszSchema::Bool, &[2]),
+    ];
+    for (schema, bytes) in invalid {
+        assert!(
+            SszValue::from_ssz_bytes(&schema, bytes).is_err(),
+            "{}",
+            schema
+        );
+    }
+}
+
+#[test]
+fn test_schema_mismatch() {
+    // values are checked against the schema when encoding, and errors carry their path
+    let schema = container(
+        "Payload",
+        vec![
+            ("a", SszSchema::Uint(2)),
+            ("extra_data", SszSchema::list(SszSchema::Uint(1), 32)),
+            ("el_data", SszSchema::list(SszSchema::Uint(1), 8)),
+        ],
+    );
+    let value = SszValue::Container(vec![
+        uint(1),
+        SszValue::Bytes(vec![]),
+        SszValue::Bytes(vec![0; 9]),
+    ]);
+    let e = value.to_ssz(&schema).unwrap_err();
+    assert_eq!(e.path, "el_data");
+    assert_eq!(
+        e.to_string(),
+        "el_data: expected List[uint8, 8], got 9 bytes"
+    );
+    let value = SszValue::Container(vec![
+        uint(1 << 16),
+        SszValue::Bytes(vec![]),
+        SszValue::Bytes(vec![]),
+    ]);
+    assert_eq!(value.to_ssz(&schema).unwrap_err().path, "a");
+    assert!(value.tree_hash_root(&schema).is_err());
+
+    // the outermost value has an empty path
+    let e = SszValue::Bool(true)
+        .to_ssz(&SszSchema::Uint(8))
+        .unwrap_err();
+    assert_eq!(e.path, "");
+    assert_eq!(e.to_string(), e.reason);
+}
@@ -0,0 +1,145 @@
+use bytes::buf::{Buf, BufMut};
+use sszb::snappy::*;
+use sszb::{ByteList, DecodeError};
+use sszb_derive::{SszbDecode, SszbEncode};
+
+type Bytes32 = ByteList<typenum::U32>;
+
+#[derive(PartialEq, Debug, SszbDecode, SszbEncode)]
+struct VariableA {
+    a: u16,
+    b: u32,
+}
+
+#[test]
+fn test_roundtrip() {
+    let var_a = VariableA { a: 1, b: 32 };
+    let list = Bytes32::new(vec![7; 20]).unwrap();
+
+    let req_resp = encode_req_resp(&var_a);
+    assert_eq!(req_resp[0], 6);
+    assert_eq!(decode_req_resp::<VariableA>(&req_resp), Ok(var_a));
+    assert_eq!(
+        decode_req_resp::<Bytes32>(&encode_req_resp(&list)),
+        Ok(list.clone())
+    );
+    assert_eq!(
+        decode_gossip::<Bytes32>(&encode_gossip(&list)),
+        Ok(list.clone())
+    );
+    assert_eq!(decode_frames::<Bytes32>(&encode_frames(&list)), Ok(list));
+}
+
+#[test]
+fn test_declared_length() {
+    // the declared length is checked before decompressing
+    assert_eq!(
+        decode_req_resp::<VariableA>(&[0x80, 0x80, 0x80, 0x80, 0x08]),
+        Err(SnappyError::LengthOutOfBounds {
+            len: 1 << 31,
+            min: 6,
+            max: 6
+        })
+    );
+    assert_eq!(
+        decode_gossip::<Bytes32>(&[33]),
+        Err(SnappyError::LengthOutOfBounds {
+            len: 33,
+            min: 0,
+            max: 32
+        })
+    );
+
+    // types without an upper bound are held to the payload size of the p2p spec
+    let huge = [0x80, 0x80, 0x80, 0x80, 0x08];
+    let unbounded = SnappyError::LengthOutOfBounds {
+        len: 1 << 31,
+        min: 0,
+        max: MAX_PAYLOAD_SIZE,
+    };
+    assert_eq!(decode_req_resp::<Vec<u8>>(&huge), Err(unbounded.clone()));
+    assert_eq!(decode_gossip::<Box<[u16]>>(&huge), Err(unbounded));
+    assert_eq!(
+        decode_frames::<Vec<u8>>(&encode_frames(&vec![1u8, 2])),
+        Err(SnappyError::Ssz(DecodeError::UnboundedList))
+    );
+    assert_eq!(
+        decode_req_resp::<Option<Vec<u8>>>(&encode_req_resp(&Some(vec![1u8]))),
+        Err(SnappyError::Ssz(DecodeError::UnboundedList))
+    );
+}
+
+#[test]
+fn test_length_prefix() {
+    assert_eq!(
+        decode_req_resp::<VariableA>(&[]),
+        Err(SnappyError::InvalidLengthPrefix)
+    );
+    // the prefix ends without a byte that has the continuation bit unset
+    assert_eq!(
+        decode_req_resp::<VariableA>(&[0x80, 0x80]),
+        Err(SnappyError::InvalidLengthPrefix)
+    );
+    // a u64 takes at most 10 bytes, of which the last holds a single bit
+    assert_eq!(
+        decode_req_resp::<VariableA>(&[0xff; 11]),
+        Err(SnappyError::InvalidLengthPrefix)
+    );
+    let mut overflow = vec![0xff; 9];
+    overflow.push(0x02);
+    assert_eq!(
+        decode_req_resp::<VariableA>(&overflow),
+        Err(SnappyError::InvalidLengthPrefix)
+    );
+    let mut max = vec![0xff; 9];
+    max.push(0x01);
+    assert_eq!(
+        decode_req_resp::<VariableA>(&max),
+        Err(SnappyError::LengthOutOfBounds {
+            len: u64::MAX as usize,
+            min: 6,
+            max: 6
+        })
+    );
+}
+
+#[test]
+fn test_compressed_too_long() {
+    // 6 bytes compress to at most 39 bytes in a snappy block, and to 57 bytes in frames, after
+    // the stream identifier and the header and checksum of the one frame
+    let var_a = VariableA { a: 1, b: 32 };
+    let mut padded = encode_req_resp(&var_a);
+    padded.extend([0; 64]);
+    assert_eq!(
+        decode_req_resp::<VariableA>(&padded),
+        Err(SnappyError::CompressedTooLong {
+            len: padded.len() - 1,
+            max: 57
+        })
+    );
+
+    let mut padded = encode_gossip(&var_a);
+    padded.extend([0; 64]);
+    assert_eq!(
+        decode_gossip::<VariableA>(&padded),
+        Err(SnappyError::CompressedTooLong {
+            len: padded.len(),
+            max: 39
+        })
+    );
+}
+
+#[test]
+fn test_length_mismatch() {
+    // the payload must decompress to exactly the declared length
+    let list = Bytes32::new(vec![7; 20]).unwrap();
+    let mut short = encode_req_resp(&list);
+    short[0] = 19;
+    assert_eq!(
+        decode_req_resp::<Bytes32>(&short),
+        Err(SnappyError::LengthMismatch {
+            len: 20,
+            declared: 19
+        })
+    );
+}
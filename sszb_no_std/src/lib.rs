@@ -0,0 +1,58 @@
+//! Containers derived in a `no_std` crate, which the no_std workflow builds for embedded targets
+//! to check that sszb and the code of its derives only need `core` and `alloc`.
+#![no_std]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use bytes::buf::{Buf, BufMut};
+use sszb::{Bitlist, Bitvector, ByteList, DecodeError, SszDecode, SszEncode};
+use sszb_derive::{SszbDecode, SszbEncode, SszbForks};
+use typenum::{U2048, U32, U4};
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode)]
+pub struct Checkpoint {
+    pub epoch: u64,
+    pub root: [u8; 32],
+}
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode)]
+pub struct Attestation {
+    pub aggregation_bits: Bitlist<U2048>,
+    pub source: Checkpoint,
+    #[ssz(validate = "after_source")]
+    pub target: Checkpoint,
+    pub justification_bits: Bitvector<U4>,
+    #[ssz(max = 16)]
+    pub attesting_indices: Vec<u64>,
+    pub graffiti: ByteList<U32>,
+    pub previous: Option<Checkpoint>,
+}
+
+fn after_source(target: &Checkpoint) -> Result<(), &'static str> {
+    if target.epoch > 0 {
+        Ok(())
+    } else {
+        Err("the target is the genesis epoch")
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ForkName {
+    Phase0,
+    Altair,
+}
+
+#[derive(SszbForks)]
+#[ssz(forks(Phase0, Altair), fork_name = ForkName, variant_derives(Clone, PartialEq, Debug))]
+pub struct Block {
+    pub slot: u64,
+    pub attestation: Attestation,
+    #[ssz(forks(Altair))]
+    pub sync_bits: Bitvector<U32>,
+}
+
+/// Decodes an `Attestation` and encodes it back, as a guest checking its input would.
+pub fn reencode_attestation(bytes: &[u8]) -> Result<Vec<u8>, DecodeError> {
+    Attestation::from_ssz_bytes(bytes).map(|attestation| attestation.to_ssz())
+}
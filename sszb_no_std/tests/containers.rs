@@ -0,0 +1,52 @@
+use sszb::{Bitlist, ByteList, DecodeError, SszEncode, SszLayout};
+use sszb_no_std::*;
+
+fn attestation() -> Attestation {
+    let mut aggregation_bits = Bitlist::with_capacity(10).unwrap();
+    aggregation_bits.set(3, true).unwrap();
+    Attestation {
+        aggregation_bits,
+        source: Checkpoint {
+            epoch: 1,
+            root: [0xaa; 32],
+        },
+        target: Checkpoint {
+            epoch: 2,
+            root: [0xbb; 32],
+        },
+        justification_bits: Default::default(),
+        attesting_indices: vec![3, 5, 8],
+        graffiti: ByteList::new(&b"sszb"[..]).unwrap(),
+        previous: None,
+    }
+}
+
+#[test]
+fn test_reencode() {
+    let bytes = attestation().to_ssz();
+    assert_eq!(reencode_attestation(&bytes).unwrap(), bytes);
+    assert_eq!(Attestation::ssz_fields().len(), 7);
+
+    let mut genesis = attestation();
+    genesis.target.epoch = 0;
+    assert_eq!(
+        reencode_attestation(&genesis.to_ssz()),
+        Err(DecodeError::ValidationFailed {
+            path: "target".to_string(),
+            reason: "the target is the genesis epoch".to_string(),
+        })
+    );
+}
+
+#[test]
+fn test_forks() {
+    let block = BlockAltair {
+        slot: 7,
+        attestation: attestation(),
+        sync_bits: Default::default(),
+    };
+    let bytes = block.to_ssz();
+    let decoded = BlockVariant::from_ssz_bytes_by_fork(&bytes, ForkName::Altair).unwrap();
+    assert_eq!(decoded, BlockVariant::Altair(block));
+    assert_eq!(decoded.fork_name(), ForkName::Altair);
+}